@@ -3,8 +3,36 @@ use std::ops::Bound;
 use storey::storage::{IterableStorage, RevIterableStorage, StorageBackend, StorageBackendMut};
 
 /// A wrapper around a type implementing [`cosmwasm_std::Storage`] that integrates it with [`storey`].
+///
+/// Wrap the storage reference handed to an entry point (`deps.storage`) and pass a reference
+/// to the wrapper to container accessors. Both `&dyn Storage` (queries) and
+/// `&mut dyn Storage` (execution) are supported.
+///
+/// # Example
+/// ```
+/// use cw_storey::containers::Item;
+/// use cw_storey::CwStorage;
+///
+/// const COUNTER: Item<u64> = Item::new(0);
+///
+/// fn execute(storage: &mut dyn cosmwasm_std::Storage) {
+///     let mut storage = CwStorage(storage);
+///     COUNTER.access(&mut storage).set(&42).unwrap();
+/// }
+///
+/// fn query(storage: &dyn cosmwasm_std::Storage) -> Option<u64> {
+///     COUNTER.access(&CwStorage(storage)).get().unwrap()
+/// }
+///
+/// let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+/// execute(&mut raw_storage);
+/// assert_eq!(query(&raw_storage), Some(42));
+/// ```
 pub struct CwStorage<S>(pub S);
 
+// `cosmwasm_std::Storage` has no existence-only query, so `has` keeps the default
+// implementation based on `get`.
+
 impl<S> StorageBackend for CwStorage<&S>
 where
     S: cosmwasm_std::Storage + ?Sized,
@@ -0,0 +1,114 @@
+use cosmwasm_std::{Addr, StdResult, Uint128};
+use storey::storage::{Storage, StorageBranch, StorageMut, StorageRef};
+
+use crate::containers::{Item, Map};
+use crate::math::UintItemExt as _;
+
+/// The map of balances, keyed by address.
+const BALANCES: Map<String, Item<Uint128>> = Map::new(0);
+/// The sum of all balances.
+const TOTAL: Item<Uint128> = Item::new(1);
+
+/// An address-keyed map of [`Uint128`] balances that keeps track of their total.
+///
+/// This covers the common case of a contract holding token balances. Missing balances
+/// are treated as zero. [`add`](BalancesAccess::add) and [`sub`](BalancesAccess::sub) use
+/// checked arithmetic, so overflow and insufficient funds surface as
+/// [`cosmwasm_std::StdError::Overflow`] and leave the stored balances untouched.
+///
+/// The balances are laid out like a [`Map`] of [`Item`]s under one subkey of the prefix,
+/// while the total is kept under another.
+///
+/// # Example
+/// ```
+/// use cosmwasm_std::{Addr, Uint128};
+/// use cw_storey::containers::Balances;
+/// use cw_storey::CwStorage;
+///
+/// const BALANCES: Balances = Balances::new(0);
+///
+/// let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+/// let mut storage = CwStorage(&mut raw_storage);
+/// let mut access = BALANCES.access(&mut storage);
+///
+/// let alice = Addr::unchecked("alice");
+/// access.add(&alice, Uint128::new(100)).unwrap();
+/// access.sub(&alice, Uint128::new(30)).unwrap();
+///
+/// assert_eq!(access.balance_of(&alice).unwrap(), Uint128::new(70));
+/// assert_eq!(access.total().unwrap(), Uint128::new(70));
+/// assert!(access.sub(&alice, Uint128::new(71)).is_err());
+/// ```
+pub struct Balances {
+    prefix: u8,
+}
+
+impl Balances {
+    /// Creates a new balance map with the given prefix.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    pub const fn new(prefix: u8) -> Self {
+        Self { prefix }
+    }
+
+    /// Acquires an accessor for the balance map.
+    pub fn access<S: StorageRef>(&self, storage: S) -> BalancesAccess<StorageBranch<S>> {
+        BalancesAccess {
+            storage: StorageBranch::new(storage, vec![self.prefix]),
+        }
+    }
+}
+
+/// An accessor for a [`Balances`] map.
+pub struct BalancesAccess<S> {
+    storage: S,
+}
+
+impl<S> BalancesAccess<StorageBranch<S>>
+where
+    StorageBranch<S>: Storage,
+{
+    /// Get the balance of `address`, or zero if it has none.
+    pub fn balance_of(&self, address: &Addr) -> StdResult<Uint128> {
+        let balance = BALANCES
+            .access(&self.storage)
+            .entry(address.as_str())
+            .get()?;
+        Ok(balance.unwrap_or_default())
+    }
+
+    /// Get the sum of all balances.
+    pub fn total(&self) -> StdResult<Uint128> {
+        Ok(TOTAL.access(&self.storage).get()?.unwrap_or_default())
+    }
+}
+
+impl<S> BalancesAccess<StorageBranch<S>>
+where
+    StorageBranch<S>: Storage + StorageMut,
+{
+    /// Credit `amount` to `address` and return its new balance.
+    pub fn add(&mut self, address: &Addr, amount: Uint128) -> StdResult<Uint128> {
+        // The total bounds every balance, so once it's updated the balance can't overflow.
+        TOTAL.access(&mut self.storage).add_assign(amount)?;
+        BALANCES
+            .access(&mut self.storage)
+            .entry_mut(address.as_str())
+            .add_assign(amount)
+    }
+
+    /// Debit `amount` from `address` and return its new balance.
+    ///
+    /// Fails if the balance of `address` is lower than `amount`.
+    pub fn sub(&mut self, address: &Addr, amount: Uint128) -> StdResult<Uint128> {
+        // The total bounds every balance, so once the balance is updated the total can't
+        // underflow.
+        let balance = BALANCES
+            .access(&mut self.storage)
+            .entry_mut(address.as_str())
+            .sub_assign(amount)?;
+        TOTAL.access(&mut self.storage).sub_assign(amount)?;
+        Ok(balance)
+    }
+}
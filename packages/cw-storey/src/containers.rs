@@ -11,7 +11,87 @@ pub type Item<T> = storey::containers::Item<T, crate::encoding::CwEncoding>;
 /// The [`storey::containers::Column`] type with the default encoding for [*CosmWasm*] smart
 /// contracts.
 ///
+/// The ID type `I` defaults to `u32`.
+///
+/// [*CosmWasm*]: https://github.com/CosmWasm/cosmwasm
+pub type Column<T, I = u32> = storey::containers::Column<T, crate::encoding::CwEncoding, I>;
+
+/// The [`storey::containers::Deque`] type with the default encoding for [*CosmWasm*] smart
+/// contracts.
+///
+/// A deque is a good fit for FIFO work queues: jobs are pushed to the back as they come in
+/// and popped from the front as they're processed.
+///
+/// # Example
+/// ```
+/// use cw_storey::containers::Deque;
+/// use cw_storey::CwStorage;
+///
+/// const JOBS: Deque<u64> = Deque::new(0);
+///
+/// let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+/// let mut storage = CwStorage(&mut raw_storage);
+///
+/// JOBS.access(&mut storage).push_back(&1).unwrap();
+/// JOBS.access(&mut storage).push_back(&2).unwrap();
+///
+/// assert_eq!(JOBS.access(&mut storage).pop_front().unwrap(), Some(1));
+/// assert_eq!(JOBS.access(&storage).len().unwrap(), 1);
+/// ```
+///
 /// [*CosmWasm*]: https://github.com/CosmWasm/cosmwasm
-pub type Column<T> = storey::containers::Column<T, crate::encoding::CwEncoding>;
+pub type Deque<T> = storey::containers::Deque<T, crate::encoding::CwEncoding>;
+
+/// The [`storey::containers::CountedMap`] type with the default encoding for [*CosmWasm*]
+/// smart contracts.
+///
+/// [*CosmWasm*]: https://github.com/CosmWasm/cosmwasm
+pub type CountedMap<K, T> = storey::containers::CountedMap<K, T, crate::encoding::CwEncoding>;
+
+pub use crate::balances::{Balances, BalancesAccess};
+
+pub mod json {
+    //! Storage containers using the human-readable [*JSON*] encoding ([`CwJsonEncoding`])
+    //! instead of the default [*MessagePack*] one.
+    //!
+    //! These are drop-in replacements for the containers in the parent module. Containers that
+    //! don't encode values themselves, like [`Map`](super::Map), work with either.
+    //!
+    //! # Example
+    //! ```
+    //! use cw_storey::containers::json::Item;
+    //! use cw_storey::CwStorage;
+    //!
+    //! const CONFIG: Item<Vec<String>> = Item::new(0);
+    //!
+    //! let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    //! let mut storage = CwStorage(&mut raw_storage);
+    //!
+    //! CONFIG.access(&mut storage).set(&vec!["foo".to_string()]).unwrap();
+    //!
+    //! assert_eq!(
+    //!     cosmwasm_std::Storage::get(&raw_storage, &[0]),
+    //!     Some(br#"["foo"]"#.to_vec())
+    //! );
+    //! ```
+    //!
+    //! [*JSON*]: https://www.json.org/
+    //! [*MessagePack*]: https://msgpack.org/
+
+    use crate::encoding::CwJsonEncoding;
+
+    /// The [`storey::containers::Item`] type with the *JSON* encoding.
+    pub type Item<T> = storey::containers::Item<T, CwJsonEncoding>;
+
+    /// The [`storey::containers::Column`] type with the *JSON* encoding.
+    ///
+    /// The ID type `I` defaults to `u32`.
+    pub type Column<T, I = u32> = storey::containers::Column<T, CwJsonEncoding, I>;
+
+    /// The [`storey::containers::Deque`] type with the *JSON* encoding.
+    pub type Deque<T> = storey::containers::Deque<T, CwJsonEncoding>;
 
-pub use storey::containers::Map;
+    /// The [`storey::containers::CountedMap`] type with the *JSON* encoding.
+    pub type CountedMap<K, T> = storey::containers::CountedMap<K, T, CwJsonEncoding>;
+}
+pub use storey::containers::{Map, Set};
@@ -25,6 +25,13 @@ where
     fn encode_impl(self) -> Result<Vec<u8>, StdError> {
         cosmwasm_std::to_msgpack_vec(self.0)
     }
+
+    fn encode_into_impl(self, buf: &mut Vec<u8>) -> Result<(), StdError> {
+        // This mirrors `cosmwasm_std::to_msgpack_vec`, serializing straight into `buf`.
+        self.0
+            .serialize(&mut rmp_serde::Serializer::new(buf).with_struct_map())
+            .map_err(|e| StdError::serialize_err(std::any::type_name::<T>(), e))
+    }
 }
 
 impl<T> DecodableWithImpl<CwEncoding> for Cover<T>
@@ -35,3 +42,36 @@ where
         cosmwasm_std::from_msgpack(data).map(Cover)
     }
 }
+
+/// An encoding that delegates to the [*JSON*] encoding provided by the [`cosmwasm_std`] crate.
+///
+/// *JSON* is less compact than [`CwEncoding`]'s *MessagePack*, but human-readable. This makes
+/// it handy for containers whose on-chain state you want to inspect while developing. Type
+/// aliases using this encoding are available in [`containers::json`](crate::containers::json).
+///
+/// [*JSON*]: https://www.json.org/
+/// [`cosmwasm_std`]: https://docs.rs/cosmwasm-std
+pub struct CwJsonEncoding;
+
+impl Encoding for CwJsonEncoding {
+    type DecodeError = StdError;
+    type EncodeError = StdError;
+}
+
+impl<T> EncodableWithImpl<CwJsonEncoding> for Cover<&T>
+where
+    T: serde::Serialize,
+{
+    fn encode_impl(self) -> Result<Vec<u8>, StdError> {
+        cosmwasm_std::to_json_vec(self.0)
+    }
+}
+
+impl<T> DecodableWithImpl<CwJsonEncoding> for Cover<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn decode_impl(data: &[u8]) -> Result<Self, StdError> {
+        cosmwasm_std::from_json(data).map(Cover)
+    }
+}
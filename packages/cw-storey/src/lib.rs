@@ -3,18 +3,27 @@
 //! This crate provides
 //! - a [*CosmWasm*] storage backend for use with [`storey`] collections,
 //! - a [*MessagePack*] encoding integration to be used for serializing and deserializing
-//!   values, and
+//!   values, along with a re-export of [`RawEncoding`] for storing
+//!   [`Binary`](cosmwasm_std::Binary) values as-is,
 //! - a set of container re-exports that remove the need to manually specify the
-//!   encoding, instead relying on the default [*MessagePack*] encoding.
+//!   encoding, instead relying on the default [*MessagePack*] encoding (or on *JSON*, via
+//!   [`containers::json`]), and
+//! - checked arithmetic helpers for items holding [`Uint64`](cosmwasm_std::Uint64) and
+//!   [`Uint128`](cosmwasm_std::Uint128) values, along with an address-keyed
+//!   [`Balances`](containers::Balances) container built on them.
 //!
 //! [*CosmWasm*]: https://github.com/CosmWasm/cosmwasm
 //! [*MessagePack*]: https://msgpack.org/
 
 mod backend;
+mod balances;
 pub mod containers;
 mod encoding;
+mod math;
 mod std_error;
 
 pub use backend::CwStorage;
-pub use encoding::CwEncoding;
+pub use encoding::{CwEncoding, CwJsonEncoding};
+pub use math::UintItemExt;
 pub use std_error::IntoStdError;
+pub use storey::encoding::RawEncoding;
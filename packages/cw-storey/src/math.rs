@@ -0,0 +1,72 @@
+use cosmwasm_std::{StdResult, Uint128, Uint64};
+use storey::containers::ItemAccess;
+use storey::storage::{Storage, StorageMut};
+
+use crate::encoding::CwEncoding;
+
+/// Checked arithmetic helpers for items holding unsigned integers, like balances.
+///
+/// These save the read-modify-write dance when adjusting a stored amount. A missing value
+/// is treated as zero. Overflow and underflow surface as [`cosmwasm_std::StdError`] and
+/// leave the stored value untouched.
+///
+/// This works on any accessor for an [`Item`](crate::containers::Item) of [`Uint64`] or
+/// [`Uint128`], including map entries.
+///
+/// # Example
+/// ```
+/// use cosmwasm_std::Uint128;
+/// use cw_storey::containers::{Item, Map};
+/// use cw_storey::{CwStorage, UintItemExt as _};
+///
+/// const BALANCES: Map<String, Item<Uint128>> = Map::new(0);
+///
+/// let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+/// let mut storage = CwStorage(&mut raw_storage);
+/// let mut balances = BALANCES.access(&mut storage);
+///
+/// let new_balance = balances.entry_mut("alice").add_assign(Uint128::new(100)).unwrap();
+/// assert_eq!(new_balance, Uint128::new(100));
+///
+/// let new_balance = balances.entry_mut("alice").sub_assign(Uint128::new(30)).unwrap();
+/// assert_eq!(new_balance, Uint128::new(70));
+///
+/// assert!(balances.entry_mut("alice").sub_assign(Uint128::new(71)).is_err());
+/// ```
+pub trait UintItemExt {
+    /// The integer type stored in the item.
+    type Value;
+
+    /// Add `amount` to the stored value and return the new value.
+    fn add_assign(&mut self, amount: Self::Value) -> StdResult<Self::Value>;
+
+    /// Subtract `amount` from the stored value and return the new value.
+    fn sub_assign(&mut self, amount: Self::Value) -> StdResult<Self::Value>;
+}
+
+macro_rules! impl_uint_item_ext {
+    ($($t:ty),*) => {
+        $(
+            impl<S> UintItemExt for ItemAccess<CwEncoding, $t, S>
+            where
+                S: Storage + StorageMut,
+            {
+                type Value = $t;
+
+                fn add_assign(&mut self, amount: $t) -> StdResult<$t> {
+                    let value = self.get()?.unwrap_or_default().checked_add(amount)?;
+                    self.set(&value)?;
+                    Ok(value)
+                }
+
+                fn sub_assign(&mut self, amount: $t) -> StdResult<$t> {
+                    let value = self.get()?.unwrap_or_default().checked_sub(amount)?;
+                    self.set(&value)?;
+                    Ok(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_uint_item_ext!(Uint64, Uint128);
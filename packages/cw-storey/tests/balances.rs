@@ -0,0 +1,225 @@
+use cosmwasm_std::{Addr, StdError, StdResult, Uint128, Uint64};
+use cw_storey::containers::{Balances, Item, Map};
+use cw_storey::{CwStorage, UintItemExt as _};
+
+// This module imitates a contract keeping track of token balances and a total supply.
+
+const BALANCES: Map<String, Item<Uint128>> = Map::new(0);
+const SUPPLY: Item<Uint128> = Item::new(1);
+const TRANSFERS: Item<Uint64> = Item::new(2);
+
+// The same contract logic, built on the `Balances` container instead.
+const LEDGER: Balances = Balances::new(3);
+
+fn execute_mint(storage: &mut dyn cosmwasm_std::Storage, to: &str, amount: u128) -> StdResult<()> {
+    let mut storage = CwStorage(storage);
+    let amount = Uint128::new(amount);
+
+    BALANCES
+        .access(&mut storage)
+        .entry_mut(to)
+        .add_assign(amount)?;
+    SUPPLY.access(&mut storage).add_assign(amount)?;
+
+    Ok(())
+}
+
+fn execute_transfer(
+    storage: &mut dyn cosmwasm_std::Storage,
+    from: &str,
+    to: &str,
+    amount: u128,
+) -> StdResult<Uint128> {
+    let mut storage = CwStorage(storage);
+    let amount = Uint128::new(amount);
+
+    let mut balances = BALANCES.access(&mut storage);
+    let remaining = balances.entry_mut(from).sub_assign(amount)?;
+    balances.entry_mut(to).add_assign(amount)?;
+
+    TRANSFERS.access(&mut storage).add_assign(Uint64::one())?;
+
+    Ok(remaining)
+}
+
+fn execute_credit(
+    storage: &mut dyn cosmwasm_std::Storage,
+    to: &str,
+    amount: u128,
+) -> StdResult<Uint128> {
+    let mut storage = CwStorage(storage);
+    let amount = Uint128::new(amount);
+
+    let balance = BALANCES
+        .access(&mut storage)
+        .entry_api(to)?
+        .and_modify(|balance| *balance += amount)
+        .or_insert(amount)?;
+
+    Ok(balance)
+}
+
+fn query_balance(storage: &dyn cosmwasm_std::Storage, address: &str) -> Uint128 {
+    BALANCES
+        .access(&CwStorage(storage))
+        .entry(address)
+        .get()
+        .unwrap()
+        .unwrap_or_default()
+}
+
+#[test]
+fn credit_and_debit() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    execute_mint(&mut storage, "alice", 100).unwrap();
+    execute_mint(&mut storage, "bob", 20).unwrap();
+
+    assert_eq!(
+        execute_transfer(&mut storage, "alice", "bob", 30).unwrap(),
+        Uint128::new(70)
+    );
+    assert_eq!(query_balance(&storage, "alice"), Uint128::new(70));
+    assert_eq!(query_balance(&storage, "bob"), Uint128::new(50));
+    assert_eq!(query_balance(&storage, "carol"), Uint128::zero());
+
+    let supply = SUPPLY.access(&CwStorage(&storage)).get().unwrap();
+    assert_eq!(supply, Some(Uint128::new(120)));
+    let transfers = TRANSFERS.access(&CwStorage(&storage)).get().unwrap();
+    assert_eq!(transfers, Some(Uint64::one()));
+}
+
+#[test]
+fn underflow_and_overflow() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    execute_mint(&mut storage, "alice", 10).unwrap();
+
+    let err = execute_transfer(&mut storage, "alice", "bob", 11).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(query_balance(&storage, "alice"), Uint128::new(10));
+
+    // debiting an account that was never credited
+    let err = execute_transfer(&mut storage, "carol", "bob", 1).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+
+    let err = execute_mint(&mut storage, "alice", u128::MAX).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(query_balance(&storage, "alice"), Uint128::new(10));
+}
+
+#[test]
+fn credit_with_entry_api() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    execute_mint(&mut storage, "alice", 100).unwrap();
+
+    // existing account
+    assert_eq!(
+        execute_credit(&mut storage, "alice", 25).unwrap(),
+        Uint128::new(125)
+    );
+    // new account
+    assert_eq!(
+        execute_credit(&mut storage, "bob", 10).unwrap(),
+        Uint128::new(10)
+    );
+
+    assert_eq!(query_balance(&storage, "alice"), Uint128::new(125));
+    assert_eq!(query_balance(&storage, "bob"), Uint128::new(10));
+}
+
+fn ledger_mint(storage: &mut dyn cosmwasm_std::Storage, to: &str, amount: u128) -> StdResult<()> {
+    LEDGER
+        .access(&mut CwStorage(storage))
+        .add(&Addr::unchecked(to), Uint128::new(amount))?;
+    Ok(())
+}
+
+fn ledger_burn(
+    storage: &mut dyn cosmwasm_std::Storage,
+    from: &str,
+    amount: u128,
+) -> StdResult<Uint128> {
+    LEDGER
+        .access(&mut CwStorage(storage))
+        .sub(&Addr::unchecked(from), Uint128::new(amount))
+}
+
+fn ledger_transfer(
+    storage: &mut dyn cosmwasm_std::Storage,
+    from: &str,
+    to: &str,
+    amount: u128,
+) -> StdResult<Uint128> {
+    let mut storage = CwStorage(storage);
+    let mut ledger = LEDGER.access(&mut storage);
+    let amount = Uint128::new(amount);
+
+    let remaining = ledger.sub(&Addr::unchecked(from), amount)?;
+    ledger.add(&Addr::unchecked(to), amount)?;
+
+    Ok(remaining)
+}
+
+fn ledger_balance(storage: &dyn cosmwasm_std::Storage, address: &str) -> Uint128 {
+    LEDGER
+        .access(&CwStorage(storage))
+        .balance_of(&Addr::unchecked(address))
+        .unwrap()
+}
+
+fn ledger_total(storage: &dyn cosmwasm_std::Storage) -> Uint128 {
+    LEDGER.access(&CwStorage(storage)).total().unwrap()
+}
+
+#[test]
+fn ledger_credit_and_debit() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    assert_eq!(ledger_total(&storage), Uint128::zero());
+
+    ledger_mint(&mut storage, "alice", 100).unwrap();
+    ledger_mint(&mut storage, "bob", 20).unwrap();
+    assert_eq!(ledger_total(&storage), Uint128::new(120));
+
+    assert_eq!(
+        ledger_transfer(&mut storage, "alice", "bob", 30).unwrap(),
+        Uint128::new(70)
+    );
+    assert_eq!(ledger_balance(&storage, "alice"), Uint128::new(70));
+    assert_eq!(ledger_balance(&storage, "bob"), Uint128::new(50));
+    assert_eq!(ledger_balance(&storage, "carol"), Uint128::zero());
+    assert_eq!(ledger_total(&storage), Uint128::new(120));
+
+    assert_eq!(
+        ledger_burn(&mut storage, "bob", 50).unwrap(),
+        Uint128::zero()
+    );
+    assert_eq!(ledger_total(&storage), Uint128::new(70));
+
+    // the ledger doesn't interfere with the other containers
+    assert_eq!(query_balance(&storage, "alice"), Uint128::zero());
+}
+
+#[test]
+fn ledger_insufficient_funds() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    ledger_mint(&mut storage, "alice", 10).unwrap();
+
+    let err = ledger_transfer(&mut storage, "alice", "bob", 11).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(ledger_balance(&storage, "alice"), Uint128::new(10));
+    assert_eq!(ledger_total(&storage), Uint128::new(10));
+
+    // debiting an account that was never credited
+    let err = ledger_burn(&mut storage, "carol", 1).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(ledger_total(&storage), Uint128::new(10));
+
+    let err = ledger_mint(&mut storage, "bob", u128::MAX).unwrap_err();
+    assert!(matches!(err, StdError::Overflow { .. }));
+    assert_eq!(ledger_balance(&storage, "bob"), Uint128::zero());
+    assert_eq!(ledger_total(&storage), Uint128::new(10));
+}
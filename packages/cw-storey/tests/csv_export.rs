@@ -0,0 +1,50 @@
+use cosmwasm_std::Uint128;
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use storey::containers::IterableAccessor as _;
+
+#[test]
+fn export_balances() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let mut storage = CwStorage(&mut raw_storage);
+
+    let balances: Map<String, Item<Uint128>> = Map::new(0);
+    let mut access = balances.access(&mut storage);
+    access.entry_mut("bob").set(&Uint128::new(20)).unwrap();
+    access.entry_mut("alice").set(&Uint128::new(100)).unwrap();
+    access
+        .entry_mut("carol, jr.")
+        .set(&Uint128::new(5))
+        .unwrap();
+
+    let mut csv = vec![];
+    access.export_csv(&mut csv).unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "alice,,100\nbob,,20\n\"carol, jr.\",,5\n"
+    );
+}
+
+#[test]
+fn export_nested() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let mut storage = CwStorage(&mut raw_storage);
+
+    let allowances: Map<String, Map<String, Item<u64>>> = Map::new(0);
+    let mut access = allowances.access(&mut storage);
+    access.entry_mut("alice").entry_mut("bob").set(&1).unwrap();
+    access
+        .entry_mut("alice")
+        .entry_mut("carol")
+        .set(&2)
+        .unwrap();
+
+    let mut csv = vec![];
+    access.export_csv(&mut csv).unwrap();
+
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "alice,bob,,1\nalice,carol,,2\n"
+    );
+}
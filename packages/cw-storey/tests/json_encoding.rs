@@ -0,0 +1,65 @@
+use cw_storey::containers::json::{Column, Item};
+use cw_storey::containers::Map;
+use cw_storey::CwStorage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    owner: String,
+    fee_bps: u16,
+}
+
+#[test]
+fn item_roundtrip() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let dyn_storage: &mut dyn cosmwasm_std::Storage = &mut raw_storage;
+    let mut storage = CwStorage(dyn_storage);
+
+    let config = Config {
+        owner: "alice".to_string(),
+        fee_bps: 30,
+    };
+
+    let item = Item::<Config>::new(0);
+    item.access(&mut storage).set(&config).unwrap();
+    assert_eq!(item.access(&storage).get().unwrap(), Some(config));
+
+    // the stored value is plain JSON
+    assert_eq!(
+        cosmwasm_std::Storage::get(&raw_storage, &[0]),
+        Some(br#"{"owner":"alice","fee_bps":30}"#.to_vec())
+    );
+}
+
+#[test]
+fn nested_containers() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let mut storage = CwStorage(&mut raw_storage);
+
+    let map = Map::<String, Item<Vec<u32>>>::new(0);
+    map.access(&mut storage)
+        .entry_mut("foo")
+        .set(&vec![1, 2])
+        .unwrap();
+    assert_eq!(
+        map.access(&storage).entry("foo").get().unwrap(),
+        Some(vec![1, 2])
+    );
+
+    let column = Column::<String>::new(1);
+    let id = column
+        .access(&mut storage)
+        .push(&"bar".to_string())
+        .unwrap();
+    assert_eq!(
+        column.access(&storage).get(id).unwrap(),
+        Some("bar".to_string())
+    );
+
+    // a value that doesn't match the stored JSON fails to decode
+    Item::<String>::new(2)
+        .access(&mut storage)
+        .set(&"baz".to_string())
+        .unwrap();
+    assert!(Item::<Config>::new(2).access(&storage).get().is_err());
+}
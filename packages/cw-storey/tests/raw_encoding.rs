@@ -0,0 +1,21 @@
+use cosmwasm_std::Binary;
+use cw_storey::{CwStorage, RawEncoding};
+use storey::containers::Item;
+
+#[test]
+fn binary_item_stores_exact_bytes() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let mut storage = CwStorage(&mut raw_storage);
+
+    let item = Item::<Binary, RawEncoding>::new(0);
+    let blob = Binary::from(b"\x00\x01pre-serialized\xff".as_slice());
+
+    item.access(&mut storage).set(&blob).unwrap();
+    assert_eq!(item.access(&storage).get().unwrap(), Some(blob.clone()));
+
+    // no MessagePack framing around the bytes
+    assert_eq!(
+        cosmwasm_std::Storage::get(&raw_storage, &[0]),
+        Some(blob.to_vec())
+    );
+}
@@ -1,6 +1,9 @@
-use cw_storey::{containers::Item, CwStorage};
+use cw_storey::{
+    containers::{Column, Item},
+    CwStorage,
+};
 
-use storey::containers::{IterableAccessor as _, Map};
+use storey::containers::{IterableAccessor as _, Map, Ref};
 
 // The tests in this module are meant to briefly test the integration of `storey`
 // with `cosmwasm_std::Storage` and MessagePack serialization.
@@ -58,3 +61,95 @@ fn iteration() {
     assert_eq!(iter.next().unwrap().unwrap().0, "foo");
     assert!(iter.next().is_none());
 }
+
+#[test]
+fn references() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let dyn_storage: &mut dyn cosmwasm_std::Storage = &mut raw_storage;
+    let mut storage = CwStorage(dyn_storage);
+
+    let names = Column::<String>::new(0);
+    let owners = Map::<String, Item<Ref<Column<String>>>>::new(1);
+
+    let alice = names
+        .access(&mut storage)
+        .push(&"alice".to_string())
+        .unwrap();
+    let bob = names.access(&mut storage).push(&"bob".to_string()).unwrap();
+
+    let mut owners_access = owners.access(&mut storage);
+    owners_access
+        .entry_mut("foo")
+        .set(&Ref::new(alice))
+        .unwrap();
+    owners_access.entry_mut("bar").set(&Ref::new(bob)).unwrap();
+
+    names.access(&mut storage).remove(bob).unwrap();
+
+    let owners_access = owners.access(&storage);
+    let names_access = names.access(&storage);
+
+    let foo = owners_access.entry("foo").get().unwrap().unwrap();
+    assert_eq!(foo, Ref::new(alice));
+    assert_eq!(
+        foo.resolve(&names_access).unwrap(),
+        Some("alice".to_string())
+    );
+
+    let bar = owners_access.entry("bar").get().unwrap().unwrap();
+    assert_eq!(bar.resolve(&names_access).unwrap(), None);
+}
+
+#[test]
+fn metadata_over_immutable_dyn_storage() {
+    let mut raw_storage = cosmwasm_std::testing::MockStorage::new();
+    let column = Column::<u64>::new(0);
+
+    {
+        let dyn_storage: &mut dyn cosmwasm_std::Storage = &mut raw_storage;
+        let mut storage = CwStorage(dyn_storage);
+        let mut access = column.access(&mut storage);
+        access.push(&1).unwrap();
+        access.push(&2).unwrap();
+
+        // read-only access through a shared borrow of a mutable storage
+        assert_eq!(column.access(&storage).len().unwrap(), 2);
+    }
+
+    // read-only access, like in a CosmWasm query entry point
+    let dyn_storage: &dyn cosmwasm_std::Storage = &raw_storage;
+    let storage = CwStorage(dyn_storage);
+    let access = column.access(&storage);
+
+    assert_eq!(access.len().unwrap(), 2);
+    assert!(!access.is_empty().unwrap());
+    assert_eq!(access.get(2).unwrap(), Some(2));
+}
+
+#[test]
+fn encode_into_matches_encode() {
+    use cw_storey::CwEncoding;
+    use storey::encoding::EncodableWith;
+
+    #[derive(serde::Serialize)]
+    struct Config {
+        owner: String,
+        fees: Vec<u16>,
+    }
+
+    let config = Config {
+        owner: "alice".to_string(),
+        fees: vec![1, 300],
+    };
+
+    let mut buf = b"prefix".to_vec();
+    EncodableWith::<CwEncoding>::encode_into(&config, &mut buf).unwrap();
+    assert_eq!(
+        buf,
+        [
+            b"prefix".to_vec(),
+            cosmwasm_std::to_msgpack_vec(&config).unwrap()
+        ]
+        .concat()
+    );
+}
@@ -0,0 +1,71 @@
+use cw_storey::{containers::Deque, CwStorage};
+
+// This module imitates a contract that maintains a FIFO job queue. New jobs are
+// enqueued in an `execute` entrypoint, while a cron-like `execute` entrypoint
+// processes a batch of jobs from the front of the queue.
+
+const JOBS: Deque<Job> = Deque::new(0);
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Job {
+    id: u64,
+    payload: String,
+}
+
+fn execute_enqueue(storage: &mut dyn cosmwasm_std::Storage, job: Job) {
+    JOBS.access(&mut CwStorage(storage))
+        .push_back(&job)
+        .unwrap();
+}
+
+fn execute_process(storage: &mut dyn cosmwasm_std::Storage, batch_size: usize) -> Vec<Job> {
+    let mut storage = CwStorage(storage);
+    let mut access = JOBS.access(&mut storage);
+
+    let mut processed = Vec::new();
+    while processed.len() < batch_size {
+        match access.pop_front().unwrap() {
+            Some(job) => processed.push(job),
+            None => break,
+        }
+    }
+    processed
+}
+
+fn query_pending(storage: &dyn cosmwasm_std::Storage) -> u32 {
+    JOBS.access(&CwStorage(storage)).len().unwrap()
+}
+
+fn job(id: u64) -> Job {
+    Job {
+        id,
+        payload: format!("job {id}"),
+    }
+}
+
+#[test]
+fn fifo_job_queue() {
+    let mut storage = cosmwasm_std::testing::MockStorage::new();
+
+    execute_enqueue(&mut storage, job(1));
+    execute_enqueue(&mut storage, job(2));
+    execute_enqueue(&mut storage, job(3));
+    assert_eq!(query_pending(&storage), 3);
+
+    let processed = execute_process(&mut storage, 2);
+    assert_eq!(processed, vec![job(1), job(2)]);
+    assert_eq!(query_pending(&storage), 1);
+
+    // interleave pushes and pops
+    execute_enqueue(&mut storage, job(4));
+    assert_eq!(execute_process(&mut storage, 1), vec![job(3)]);
+    execute_enqueue(&mut storage, job(5));
+    execute_enqueue(&mut storage, job(6));
+    assert_eq!(query_pending(&storage), 3);
+
+    let processed = execute_process(&mut storage, 10);
+    assert_eq!(processed, vec![job(4), job(5), job(6)]);
+    assert_eq!(query_pending(&storage), 0);
+
+    assert_eq!(execute_process(&mut storage, 10), vec![]);
+}
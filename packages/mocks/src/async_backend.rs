@@ -0,0 +1,116 @@
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use storey_storage::{AsyncStorage, AsyncStorageMut, Storage, StorageMut};
+
+use crate::backend::TestStorage;
+
+/// An async wrapper around [`TestStorage`].
+///
+/// Every operation yields to the executor once before touching the storage, so code
+/// under test can't get away with never actually awaiting.
+#[derive(Default)]
+pub struct AsyncTestStorage(pub TestStorage);
+
+impl AsyncTestStorage {
+    pub fn new() -> Self {
+        Self(TestStorage::new())
+    }
+}
+
+impl AsyncStorage for AsyncTestStorage {
+    async fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        YieldOnce(false).await;
+        Storage::get(&self.0, key)
+    }
+
+    async fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
+        YieldOnce(false).await;
+        Storage::get_meta(&self.0, key)
+    }
+}
+
+impl AsyncStorageMut for AsyncTestStorage {
+    async fn set(&mut self, key: &[u8], value: &[u8]) {
+        YieldOnce(false).await;
+        StorageMut::set(&mut self.0, key, value)
+    }
+
+    async fn remove(&mut self, key: &[u8]) {
+        YieldOnce(false).await;
+        StorageMut::remove(&mut self.0, key)
+    }
+
+    async fn set_meta(&mut self, key: &[u8], value: &[u8]) {
+        YieldOnce(false).await;
+        StorageMut::set_meta(&mut self.0, key, value)
+    }
+
+    async fn remove_meta(&mut self, key: &[u8]) {
+        YieldOnce(false).await;
+        StorageMut::remove_meta(&mut self.0, key)
+    }
+}
+
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Run a future to completion on the current thread.
+///
+/// This is a minimal executor for tests - it busy-polls the future.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn noop_waker() -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(|_| RAW, |_| {}, |_| {}, |_| {});
+    const RAW: RawWaker = RawWaker::new(std::ptr::null(), &VTABLE);
+
+    // Safety: the vtable functions don't touch the data pointer.
+    unsafe { Waker::from_raw(RAW) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn async_storage() {
+        let mut storage = AsyncTestStorage::new();
+
+        block_on(async {
+            storage.set(b"foo", b"bar").await;
+            storage.set_meta(b"foo", b"baz").await;
+
+            assert_eq!(storage.get(b"foo").await, Some(b"bar".to_vec()));
+            assert_eq!(storage.get_meta(b"foo").await, Some(b"baz".to_vec()));
+            assert!(storage.has(b"foo").await);
+
+            storage.remove(b"foo").await;
+            assert!(!storage.has(b"foo").await);
+            assert!(storage.has_meta(b"foo").await);
+        });
+    }
+}
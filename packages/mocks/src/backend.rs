@@ -1,6 +1,8 @@
 use std::{cell::UnsafeCell, collections::BTreeMap, ops::Bound};
 
-use storey_storage::{IterableStorage, RevIterableStorage, StorageBackend, StorageBackendMut};
+use storey_storage::{
+    IterableStorage, RevIterableStorage, Seekable, StorageBackend, StorageBackendMut,
+};
 
 // `UnsafeCell` is needed here to implement interior mutability.
 // https://doc.rust-lang.org/book/ch15-05-interior-mutability.html
@@ -52,49 +54,79 @@ impl StorageBackendMut for TestStorage {
             (*self.0.get()).remove(key);
         }
     }
+
+    fn clear_all(&mut self) {
+        *self.0.get_mut() = BTreeMap::new();
+    }
 }
 
 impl IterableStorage for TestStorage {
-    type KeysIterator<'a> = Box<dyn DoubleEndedIterator<Item = Vec<u8>> + 'a>;
-    type ValuesIterator<'a> = Box<dyn DoubleEndedIterator<Item = Vec<u8>> + 'a>;
-    type PairsIterator<'a> = Box<dyn DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+    type KeysIterator<'a> = TestIter<Vec<u8>>;
+    type ValuesIterator<'a> = TestIter<Vec<u8>>;
+    type PairsIterator<'a> = TestIter<(Vec<u8>, Vec<u8>)>;
 
     fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
-        let start = start.map(|x| x.to_vec());
-        let end = end.map(|x| x.to_vec());
-
-        Box::new(
-            // Safety: see above
-            unsafe { (*self.0.get()).clone() }
-                .into_iter()
-                .filter(move |(k, _)| check_bounds(k, start.as_ref(), end.as_ref()))
-                .map(|(k, _)| k),
-        )
+        self.iter(start, end, |k, _| k)
     }
 
     fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
-        let start = start.map(|x| x.to_vec());
-        let end = end.map(|x| x.to_vec());
-
-        Box::new(
-            // Safety: see above
-            unsafe { (*self.0.get()).clone() }
-                .into_iter()
-                .filter(move |(k, _)| check_bounds(k, start.as_ref(), end.as_ref()))
-                .map(|(_, v)| v),
-        )
+        self.iter(start, end, |_, v| v)
     }
 
     fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        self.iter(start, end, |k, v| (k, v))
+    }
+}
+
+impl TestStorage {
+    fn iter<T>(
+        &self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+        project: fn(Vec<u8>, Vec<u8>) -> T,
+    ) -> TestIter<T> {
         let start = start.map(|x| x.to_vec());
         let end = end.map(|x| x.to_vec());
 
-        Box::new(
-            // Safety: see above
-            unsafe { (*self.0.get()).clone() }
-                .into_iter()
-                .filter(move |(k, _)| check_bounds(k, start.as_ref(), end.as_ref())),
-        )
+        // Safety: see above
+        let entries = unsafe { (*self.0.get()).clone() }
+            .into_iter()
+            .filter(|(k, _)| check_bounds(k, start.as_ref(), end.as_ref()))
+            .collect();
+
+        TestIter { entries, project }
+    }
+}
+
+/// An iterator over a snapshot of the entries of a [`TestStorage`].
+///
+/// Seeking is done by splitting the snapshot rather than scanning it.
+pub struct TestIter<T> {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+    project: fn(Vec<u8>, Vec<u8>) -> T,
+}
+
+impl<T> Iterator for TestIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.entries.pop_first().map(|(k, v)| (self.project)(k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.entries.len(), Some(self.entries.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for TestIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.entries.pop_last().map(|(k, v)| (self.project)(k, v))
+    }
+}
+
+impl<T> Seekable for TestIter<T> {
+    fn seek(&mut self, key: &[u8]) {
+        self.entries = self.entries.split_off(key);
     }
 }
 
@@ -268,6 +300,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn seek() {
+        let mut storage = TestStorage::new();
+
+        storage.set(&[0], b"bar");
+        storage.set(&[1], b"baz");
+        storage.set(&[1, 0], b"qux");
+        storage.set(&[1, 1], b"quux");
+        storage.set(&[3], b"qux");
+
+        let mut keys = storage.keys(Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(keys.next(), Some(vec![0]));
+
+        keys.seek(&[1, 1]);
+        assert_eq!(keys.next(), Some(vec![1, 1]));
+
+        // seeking backwards doesn't revisit entries
+        keys.seek(&[0]);
+        keys.seek(&[2]);
+        assert_eq!(keys.next(), Some(vec![3]));
+        assert_eq!(keys.next(), None);
+
+        let mut pairs = storage.pairs(Bound::Unbounded, Bound::Excluded(&[3]));
+        pairs.seek(&[1, 0]);
+        assert_eq!(pairs.next(), Some((vec![1, 0], b"qux".to_vec())));
+        assert_eq!(pairs.next_back(), Some((vec![1, 1], b"quux".to_vec())));
+        assert_eq!(pairs.next(), None);
+    }
+
+    #[test]
+    fn clear_all() {
+        use storey_storage::StorageMut as _;
+
+        let mut storage = TestStorage::new();
+        for i in 0..10u8 {
+            StorageBackendMut::set(&mut storage, &[i], &[i]);
+        }
+        storage.set_meta(&[0], b"meta");
+
+        storage.clear_all();
+
+        assert_eq!(storage.keys(Bound::Unbounded, Bound::Unbounded).count(), 0);
+        assert_eq!(StorageBackend::get(&storage, &[0]), None);
+        assert_eq!(StorageBackend::get(&storage, &[255, 0]), None);
+
+        // the storage is still usable afterwards
+        StorageBackendMut::set(&mut storage, &[0], b"foo");
+        assert_eq!(StorageBackend::get(&storage, &[0]), Some(b"foo".to_vec()));
+    }
+
     #[test]
     fn metadata() {
         use storey_storage::StorageMut as _;
@@ -0,0 +1,140 @@
+use std::ops::Bound;
+
+use storey_storage::{CachedStorage, RevIterableStorage, StorageBackend};
+
+use crate::backend::TestStorage;
+
+/// A copy-on-write fork of a [`TestStorage`], created with [`TestStorage::fork`].
+///
+/// Reads fall through to the forked storage unless the key has been written to in the fork.
+/// Writes are staged in the fork, so the forked storage is never modified.
+/// [`CachedStorage::diff`] lists the keys the fork changed.
+pub type CowStorage<'a> = CachedStorage<&'a TestStorage>;
+
+impl TestStorage {
+    /// Cheaply fork this storage. Writes to the fork are not visible in `self`.
+    pub fn fork(&self) -> CowStorage<'_> {
+        CachedStorage::new(self)
+    }
+}
+
+impl StorageBackend for &TestStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        (**self).get(key)
+    }
+}
+
+impl RevIterableStorage for &TestStorage {
+    type RevKeysIterator<'a> = <TestStorage as RevIterableStorage>::RevKeysIterator<'a> where Self: 'a;
+    type RevValuesIterator<'a> = <TestStorage as RevIterableStorage>::RevValuesIterator<'a> where Self: 'a;
+    type RevPairsIterator<'a> = <TestStorage as RevIterableStorage>::RevPairsIterator<'a> where Self: 'a;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        (**self).rev_keys(start, end)
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        (**self).rev_values(start, end)
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        (**self).rev_pairs(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use storey_storage::{IterableStorage, StorageBackendMut};
+
+    use super::*;
+
+    #[test]
+    fn fork() {
+        let mut storage = TestStorage::new();
+        storage.set(&[0], b"foo");
+        storage.set(&[1], b"bar");
+        storage.set(&[2], b"baz");
+
+        let mut fork = storage.fork();
+        fork.set(&[1], b"qux");
+        fork.remove(&[2]);
+        fork.set(&[3], b"quux");
+        // writing the same value as the base is not a change
+        fork.set(&[0], b"foo");
+
+        assert_eq!(fork.get(&[0]), Some(b"foo".to_vec()));
+        assert_eq!(fork.get(&[1]), Some(b"qux".to_vec()));
+        assert_eq!(fork.get(&[2]), None);
+        assert_eq!(fork.get(&[3]), Some(b"quux".to_vec()));
+
+        let pairs: Vec<_> = fork.pairs(Bound::Unbounded, Bound::Unbounded).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (vec![0], b"foo".to_vec()),
+                (vec![1], b"qux".to_vec()),
+                (vec![3], b"quux".to_vec()),
+            ]
+        );
+
+        let rev_keys: Vec<_> = fork
+            .rev_keys(Bound::Included(&[1]), Bound::Unbounded)
+            .collect();
+        assert_eq!(rev_keys, vec![vec![3], vec![1]]);
+
+        assert_eq!(fork.diff(), vec![vec![1], vec![2], vec![3]]);
+
+        // the base storage is unchanged
+        assert_eq!(storage.get(&[0]), Some(b"foo".to_vec()));
+        assert_eq!(storage.get(&[1]), Some(b"bar".to_vec()));
+        assert_eq!(storage.get(&[2]), Some(b"baz".to_vec()));
+        assert_eq!(storage.get(&[3]), None);
+    }
+
+    #[test]
+    fn empty_ranges() {
+        let storage = TestStorage::new();
+        let mut fork = storage.fork();
+        fork.set(&[1], b"foo");
+
+        assert_eq!(
+            fork.keys(Bound::Excluded(&[1]), Bound::Excluded(&[1]))
+                .count(),
+            0
+        );
+        assert_eq!(
+            fork.keys(Bound::Included(&[2]), Bound::Included(&[1]))
+                .count(),
+            0
+        );
+        assert_eq!(
+            fork.rev_pairs(Bound::Included(&[2]), Bound::Excluded(&[0]))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn clear_all() {
+        let mut storage = TestStorage::new();
+        storage.set(&[0], b"foo");
+        storage.set(&[1], b"bar");
+
+        // `CachedStorage` relies on the default implementation
+        let mut fork = storage.fork();
+        fork.set(&[2], b"baz");
+        fork.clear_all();
+
+        assert_eq!(fork.keys(Bound::Unbounded, Bound::Unbounded).count(), 0);
+        assert_eq!(fork.diff(), vec![vec![0], vec![1]]);
+        assert_eq!(storage.get(&[0]), Some(b"foo".to_vec()));
+    }
+}
@@ -1,2 +1,4 @@
+pub mod async_backend;
 pub mod backend;
+pub mod cow;
 pub mod encoding;
@@ -1,17 +1,40 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
 pub trait Encoding {
     /// The error type returned when encoding fails.
-    type EncodeError: std::fmt::Display;
+    type EncodeError: core::fmt::Display;
 
     /// The error type returned when decoding fails.
-    type DecodeError: std::fmt::Display;
+    type DecodeError: core::fmt::Display;
 }
 
 pub trait EncodableWith<E: Encoding>: sealed::SealedE<E> {
     fn encode(&self) -> Result<Vec<u8>, E::EncodeError>;
+
+    /// Encode the value, appending the result to `buf`.
+    ///
+    /// Depending on the encoding, this can avoid allocating a temporary buffer.
+    fn encode_into(&self, buf: &mut Vec<u8>) -> Result<(), E::EncodeError>;
 }
 
 pub trait EncodableWithImpl<E: Encoding> {
     fn encode_impl(self) -> Result<Vec<u8>, E::EncodeError>;
+
+    /// Encode the value, appending the result to `buf`.
+    ///
+    /// The default implementation calls [`encode_impl`](Self::encode_impl) and copies the
+    /// result. Encodings that can write into an existing buffer should override this.
+    fn encode_into_impl(self, buf: &mut Vec<u8>) -> Result<(), E::EncodeError>
+    where
+        Self: Sized,
+    {
+        buf.extend_from_slice(&self.encode_impl()?);
+        Ok(())
+    }
 }
 
 impl<E: Encoding, T> EncodableWith<E> for T
@@ -21,6 +44,10 @@ where
     fn encode(&self) -> Result<Vec<u8>, <E as Encoding>::EncodeError> {
         Cover(self).encode_impl()
     }
+
+    fn encode_into(&self, buf: &mut Vec<u8>) -> Result<(), <E as Encoding>::EncodeError> {
+        Cover(self).encode_into_impl(buf)
+    }
 }
 
 pub trait DecodableWith<E: Encoding>: Sized + sealed::SealedD<E> {
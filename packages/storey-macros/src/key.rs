@@ -0,0 +1,526 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt as _;
+use syn::{Data, DataEnum, DeriveInput, Fields, Ident, LitInt, Member, Type, WherePredicate};
+
+/// A field that's part of the key.
+struct KeyField {
+    member: Member,
+    ty: Type,
+    /// The name of the local variable holding the field while decoding.
+    binding: Ident,
+}
+
+/// The fields of a struct, as laid out in the key.
+struct Layout {
+    /// Key fields, in key order.
+    key_fields: Vec<KeyField>,
+    /// All fields, in declaration order.
+    fields: Fields,
+    /// The first field excluded from the key, if any.
+    skipped: Option<Member>,
+}
+
+/// The options set on a field with `#[key(...)]`.
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    order: Option<(u32, LitInt)>,
+}
+
+pub fn derive_key(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return derive_enum_key(&input, data);
+    }
+
+    let layout = parse_layout(&input)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (kind, mut predicates) = kind_of(&layout.key_fields);
+    predicates.extend(layout.key_fields.iter().map(|field| {
+        let ty = &field.ty;
+        syn::parse_quote!(#ty: ::storey::containers::map::key::Key)
+    }));
+    let where_clause = extend_where_clause(where_clause, predicates);
+
+    let (last, init) = layout
+        .key_fields
+        .split_last()
+        .expect("checked by parse_layout");
+    let init = init.iter().map(|field| &field.member);
+    let (last_member, last_ty) = (&last.member, &last.ty);
+
+    Ok(quote! {
+        impl #impl_generics ::storey::containers::map::key::Key for #name #ty_generics
+        #where_clause
+        {
+            type Kind = #kind;
+
+            fn encode(&self) -> ::storey::__private::Vec<u8> {
+                let mut result = ::storey::__private::Vec::new();
+                #(
+                    ::storey::containers::map::key::__private::push_member(
+                        &mut result,
+                        &self.#init,
+                    );
+                )*
+                result.extend_from_slice(
+                    &<#last_ty as ::storey::containers::map::key::Key>::encode(&self.#last_member),
+                );
+                result
+            }
+        }
+    })
+}
+
+pub fn derive_owned_key(input: DeriveInput) -> syn::Result<TokenStream> {
+    if let Data::Enum(data) = &input.data {
+        return derive_enum_owned_key(&input, data);
+    }
+
+    let layout = parse_layout(&input)?;
+
+    if let Some(skipped) = &layout.skipped {
+        return Err(syn::Error::new_spanned(
+            skipped,
+            "`OwnedKey` can't be derived for a struct with skipped fields, since they can't be \
+             decoded from the key",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (_, mut predicates) = kind_of(&layout.key_fields);
+    predicates.extend(layout.key_fields.iter().map(|field| {
+        let ty = &field.ty;
+        syn::parse_quote!(#ty: ::storey::containers::map::key::OwnedKey)
+    }));
+    let where_clause = extend_where_clause(where_clause, predicates);
+
+    let (last, init) = layout
+        .key_fields
+        .split_last()
+        .expect("checked by parse_layout");
+    let split = init.iter().map(|KeyField { ty, binding, .. }| {
+        quote! {
+            let (#binding, rest) =
+                ::storey::containers::map::key::__private::split_member::<#ty>(rest)
+                    .ok_or(::storey::containers::map::key::StructKeyDecodeError::KeyTooShort)?;
+        }
+    });
+    let last_binding = &last.binding;
+
+    let decode = layout.key_fields.iter().map(|field| {
+        let KeyField { ty, binding, .. } = field;
+        let field_name = match &field.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        quote! {
+            let #binding = <#ty as ::storey::containers::map::key::OwnedKey>::from_bytes(#binding)
+                .map_err(|_| {
+                    ::storey::containers::map::key::StructKeyDecodeError::Field(#field_name)
+                })?;
+        }
+    });
+
+    let bindings = layout.fields.members().map(|member| binding_for(&member));
+    let construct = match &layout.fields {
+        Fields::Named(_) => {
+            let members = layout.fields.members();
+            quote!(Self { #(#members: #bindings),* })
+        }
+        _ => quote!(Self(#(#bindings),*)),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::storey::containers::map::key::OwnedKey for #name #ty_generics
+        #where_clause
+        {
+            type Error = ::storey::containers::map::key::StructKeyDecodeError;
+
+            fn from_bytes(bytes: &[u8]) -> ::core::result::Result<Self, Self::Error>
+            where
+                Self: Sized,
+            {
+                let rest = bytes;
+                #(#split)*
+                let #last_binding = rest;
+
+                #(#decode)*
+
+                ::core::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+/// Fieldless enums are encoded as the index of the variant, in declaration order, in a
+/// single byte.
+fn derive_enum_key(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let variants = parse_variants(input, data)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let indices = (0..variants.len()).map(|index| index as u8);
+
+    Ok(quote! {
+        impl #impl_generics ::storey::containers::map::key::Key for #name #ty_generics
+        #where_clause
+        {
+            type Kind = ::storey::containers::map::key::FixedSizeKey<1>;
+
+            fn encode(&self) -> ::storey::__private::Vec<u8> {
+                let index: u8 = match self {
+                    #(Self::#variants => #indices,)*
+                };
+                ::storey::__private::vec![index]
+            }
+        }
+    })
+}
+
+fn derive_enum_owned_key(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let variants = parse_variants(input, data)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let indices = (0..variants.len()).map(|index| index as u8);
+
+    // With 256 variants, every byte is a valid index.
+    let invalid_variant = (variants.len() < 256).then(|| {
+        quote! {
+            [index] => ::core::result::Result::Err(
+                ::storey::containers::map::key::EnumKeyDecodeError::InvalidVariant(*index),
+            ),
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::storey::containers::map::key::OwnedKey for #name #ty_generics
+        #where_clause
+        {
+            type Error = ::storey::containers::map::key::EnumKeyDecodeError;
+
+            fn from_bytes(bytes: &[u8]) -> ::core::result::Result<Self, Self::Error>
+            where
+                Self: Sized,
+            {
+                match bytes {
+                    #([#indices] => ::core::result::Result::Ok(Self::#variants),)*
+                    #invalid_variant
+                    _ => ::core::result::Result::Err(
+                        ::storey::containers::map::key::EnumKeyDecodeError::InvalidLength,
+                    ),
+                }
+            }
+        }
+    })
+}
+
+/// Collect the variants of a fieldless enum, checking it can be used as a key.
+fn parse_variants<'a>(input: &DeriveInput, data: &'a DataEnum) -> syn::Result<Vec<&'a Ident>> {
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "a key enum needs at least one variant",
+        ));
+    }
+    if data.variants.len() > 256 {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "a key enum can have at most 256 variants",
+        ));
+    }
+
+    data.variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => Ok(&variant.ident),
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                "key derives are only supported for enums without fields",
+            )),
+        })
+        .collect()
+}
+
+fn parse_layout(input: &DeriveInput) -> syn::Result<Layout> {
+    let fields = match &input.data {
+        Data::Struct(data) => data.fields.clone(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "key derives are only supported for structs and fieldless enums",
+            ))
+        }
+    };
+
+    let mut key_fields = Vec::new();
+    let mut skipped = None;
+    let mut ordered = Vec::new();
+
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let options = parse_field_options(field)?;
+
+        if options.skip {
+            if let Some((_, lit)) = options.order {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "a skipped field can't have a key order",
+                ));
+            }
+            skipped.get_or_insert(member);
+            continue;
+        }
+
+        ordered.push(options.order);
+        key_fields.push(KeyField {
+            binding: binding_for(&member),
+            member,
+            ty: field.ty.clone(),
+        });
+    }
+
+    if key_fields.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "a key needs at least one field that isn't skipped",
+        ));
+    }
+
+    // Either every key field has an explicit order, or none of them does.
+    if ordered.iter().any(Option::is_some) {
+        let mut orders = Vec::with_capacity(ordered.len());
+        for (order, field) in ordered.into_iter().zip(&key_fields) {
+            let Some((order, lit)) = order else {
+                return Err(syn::Error::new_spanned(
+                    &field.member,
+                    "if any field has a key order, every field that isn't skipped needs one",
+                ));
+            };
+            if orders.contains(&order) {
+                return Err(syn::Error::new_spanned(lit, "duplicate key order"));
+            }
+            orders.push(order);
+        }
+
+        let mut indexed: Vec<_> = orders.into_iter().zip(key_fields).collect();
+        indexed.sort_by_key(|(order, _)| *order);
+        key_fields = indexed.into_iter().map(|(_, field)| field).collect();
+    }
+
+    Ok(Layout {
+        key_fields,
+        fields,
+        skipped,
+    })
+}
+
+fn parse_field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("key"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("order") {
+                let lit: LitInt = meta.value()?.parse()?;
+                options.order = Some((lit.base10_parse()?, lit));
+                Ok(())
+            } else {
+                Err(meta.error("unsupported key attribute, expected `skip` or `order = N`"))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// Compute the key kind of the given fields, folding them pairwise with `CompositeKind`
+/// just like tuple keys do. Also returns the predicates needed for the fold to resolve.
+fn kind_of(fields: &[KeyField]) -> (Type, Vec<WherePredicate>) {
+    let mut predicates = Vec::new();
+
+    let mut fields = fields.iter();
+    let first = &fields.next().expect("checked by parse_layout").ty;
+    let mut kind: Type = syn::parse_quote!(<#first as ::storey::containers::map::key::Key>::Kind);
+
+    for field in fields {
+        let ty = &field.ty;
+        let pair: Type = syn::parse_quote! {
+            (#kind, <#ty as ::storey::containers::map::key::Key>::Kind)
+        };
+        predicates.push(syn::parse_quote! {
+            #pair: ::storey::containers::map::key::CompositeKind
+        });
+        kind = syn::parse_quote! {
+            <#pair as ::storey::containers::map::key::CompositeKind>::Kind
+        };
+    }
+
+    (kind, predicates)
+}
+
+fn extend_where_clause(
+    where_clause: Option<&syn::WhereClause>,
+    predicates: Vec<WherePredicate>,
+) -> syn::WhereClause {
+    let mut where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+    where_clause.predicates.extend(predicates);
+    where_clause
+}
+
+fn binding_for(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => format_ident!("__field_{}", ident.unraw()),
+        Member::Unnamed(index) => format_ident!("__field_{}", index.index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens as _;
+
+    use super::*;
+
+    fn key_error(input: DeriveInput) -> String {
+        derive_key(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn key_order() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Entry {
+                #[key(order = 1)]
+                name: String,
+                #[key(skip)]
+                note: String,
+                #[key(order = 0)]
+                id: u64,
+            }
+        };
+        let layout = parse_layout(&input).unwrap();
+
+        let members: Vec<_> = layout
+            .key_fields
+            .iter()
+            .map(|field| field.member.to_token_stream().to_string())
+            .collect();
+        assert_eq!(members, ["id", "name"]);
+
+        let skipped = layout.skipped.unwrap().to_token_stream().to_string();
+        assert_eq!(skipped, "note");
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct Empty;
+            )),
+            "a key needs at least one field that isn't skipped"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct Skipped(#[key(skip)] u8);
+            )),
+            "a key needs at least one field that isn't skipped"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                union NotAStruct {
+                    a: u8,
+                }
+            )),
+            "key derives are only supported for structs and fieldless enums"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct Partial(#[key(order = 1)] u8, u8);
+            )),
+            "if any field has a key order, every field that isn't skipped needs one"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct Duplicate(#[key(order = 1)] u8, #[key(order = 1)] u8);
+            )),
+            "duplicate key order"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct SkipOrder(u8, #[key(skip, order = 1)] u8);
+            )),
+            "a skipped field can't have a key order"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                struct Unknown(#[key(rename = "foo")] u8);
+            )),
+            "unsupported key attribute, expected `skip` or `order = N`"
+        );
+    }
+
+    #[test]
+    fn invalid_enums() {
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                enum Empty {}
+            )),
+            "a key enum needs at least one variant"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                enum WithData {
+                    A,
+                    B(u8),
+                }
+            )),
+            "key derives are only supported for enums without fields"
+        );
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                enum WithNamedData {
+                    A { b: u8 },
+                }
+            )),
+            "key derives are only supported for enums without fields"
+        );
+
+        let variants = (0..257u32).map(|i| format_ident!("V{}", i));
+        assert_eq!(
+            key_error(syn::parse_quote!(
+                enum TooMany {
+                    #(#variants,)*
+                }
+            )),
+            "a key enum can have at most 256 variants"
+        );
+    }
+
+    #[test]
+    fn owned_key_with_skipped_field() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Entry {
+                id: u64,
+                #[key(skip)]
+                note: String,
+            }
+        };
+
+        assert!(derive_key(input.clone()).is_ok());
+        assert_eq!(
+            derive_owned_key(input).unwrap_err().to_string(),
+            "`OwnedKey` can't be derived for a struct with skipped fields, since they can't be \
+             decoded from the key"
+        );
+    }
+}
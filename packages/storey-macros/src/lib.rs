@@ -0,0 +1,137 @@
+//! Derive macros for [`storey`](https://docs.rs/storey).
+//!
+//! These are re-exported by `storey` when its `derive` feature is enabled. Use them from
+//! there rather than depending on this crate directly.
+
+mod key;
+mod storable;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derive the `Key` trait for a struct or a fieldless enum, so that it can be used as a
+/// map key.
+///
+/// A struct key is encoded like a tuple of the struct's fields. Fixed-size fields are
+/// concatenated as they are. Every dynamically sized field other than the last one is
+/// length-prefixed so that we know where it ends, the same way tuple key members are. If
+/// every field is fixed-size, the whole key is too.
+///
+/// Keys are ordered by their encoding. As with tuple keys, a struct key is ordered field by
+/// field as long as only the last field is dynamically sized. A length-prefixed field is
+/// ordered by its length first.
+///
+/// A fieldless enum is encoded as the index of the variant, in declaration order, in a
+/// single byte. Explicit discriminants are ignored. Enums with variants that carry data
+/// aren't supported.
+///
+/// # Field attributes
+///
+/// - `#[key(skip)]` leaves the field out of the key. Such a struct can't derive `OwnedKey`,
+///   since the skipped field can't be decoded from the key.
+/// - `#[key(order = N)]` places the field in the key by `N` (ascending) rather than by
+///   declaration order. If any field has an order, every field that isn't skipped needs one.
+///
+/// # Example
+/// ```
+/// use storey::containers::map::{Key, OwnedKey};
+///
+/// #[derive(Debug, PartialEq, Key, OwnedKey)]
+/// struct Entry {
+///     #[key(order = 1)]
+///     name: String,
+///     #[key(order = 0)]
+///     id: u32,
+/// }
+///
+/// let entry = Entry { name: "foo".to_string(), id: 5 };
+/// assert_eq!(entry.encode(), [0, 0, 0, 5, 102, 111, 111]);
+/// assert_eq!(Entry::from_bytes(&entry.encode()), Ok(entry));
+/// ```
+#[proc_macro_derive(Key, attributes(key))]
+pub fn derive_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    key::derive_key(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive the `OwnedKey` trait for a struct, so that it can be decoded from a map key.
+///
+/// This needs a matching `Key` implementation, usually derived as well. See the `Key` derive
+/// for the encoding and the field attributes. Decoding errors are reported as
+/// `StructKeyDecodeError`, naming the field that failed to decode, or as
+/// `EnumKeyDecodeError` for enums.
+///
+/// `OwnedKey` can't be derived if any field is marked `#[key(skip)]`.
+#[proc_macro_derive(OwnedKey, attributes(key))]
+pub fn derive_owned_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    key::derive_owned_key(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive the `Storable` trait for a struct describing a storage layout.
+///
+/// Every field is a container (anything implementing `Storable`) and needs a
+/// `#[key(n)]` attribute giving the byte it's stored under, relative to the layout. Keys
+/// must be unique within the struct.
+///
+/// The struct itself is never constructed; it only describes the layout. The derive
+/// generates:
+///
+/// - an accessor struct named after the layout with an `Access` suffix, with a method per
+///   field returning the field's accessor, plus a `_mut` variant for mutable access. Doc
+///   comments on fields are copied onto these methods.
+/// - an associated `access` function acquiring an accessor for a layout at the root of the
+///   storage.
+/// - the `Storable` implementation, so the layout can itself be nested in other containers,
+///   like a `Map`. Iterating over such a map yields `()` in place of the layout's keys
+///   and values.
+///
+/// # Wide keys
+///
+/// With `#[storable(wide_keys)]` on the struct, keys can go up to 65535 and are stored as
+/// two big-endian bytes instead of one. This is chosen per layout. Don't mix key widths
+/// between layouts sharing a namespace (e.g. a wide layout at the root next to containers
+/// created with `Item::new` or `Map::new`): a one-byte key is a prefix of some two-byte
+/// keys, so their storage would overlap.
+///
+/// The generated accessor struct, its methods and the `access` function all get the
+/// visibility of the layout struct, so a `pub(crate)` layout stays crate-private.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::{Item, Map, Storable};
+///
+/// #[derive(Storable)]
+/// pub struct Contract {
+///     /// The contract's configuration.
+///     #[key(0)]
+///     config: Item<u64, TestEncoding>,
+///     #[key(1)]
+///     balances: Map<String, Item<u64, TestEncoding>>,
+/// }
+///
+/// let mut storage = TestStorage::new();
+/// let mut access = Contract::access(&mut storage);
+///
+/// access.config_mut().set(&42).unwrap();
+/// access.balances_mut().entry_mut("alice").set(&100).unwrap();
+///
+/// assert_eq!(access.config().get().unwrap(), Some(42));
+/// assert_eq!(access.balances().entry("alice").get().unwrap(), Some(100));
+/// ```
+#[proc_macro_derive(Storable, attributes(key, storable))]
+pub fn derive_storable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    storable::derive_storable(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
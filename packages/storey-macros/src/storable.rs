@@ -0,0 +1,369 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt as _;
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, LitInt, Type};
+
+/// A container field of a storage layout.
+struct StorableField {
+    name: Ident,
+    ty: Type,
+    /// The encoded key the container is stored under, relative to the layout.
+    key: Vec<u8>,
+    /// Doc comments of the field, forwarded to the generated accessor methods.
+    docs: Vec<Attribute>,
+}
+
+pub fn derive_storable(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = parse_fields(&input)?;
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let access = format_ident!("{}Access", name);
+    let access_doc = format!("An accessor for [`{name}`].");
+
+    let methods = fields.iter().map(|field| {
+        let StorableField {
+            name,
+            ty,
+            key,
+            docs,
+        } = field;
+        let getter_mut = format_ident!("{}_mut", name.unraw());
+
+        quote! {
+            #(#docs)*
+            #vis fn #name(
+                &self,
+            ) -> <#ty as ::storey::containers::Storable>::Accessor<
+                ::storey::storage::StorageBranch<&S>,
+            > {
+                <#ty as ::storey::containers::Storable>::access_impl(
+                    ::storey::storage::StorageBranch::new(&self.storage, ::storey::__private::vec![#(#key),*]),
+                )
+            }
+
+            #(#docs)*
+            #vis fn #getter_mut(
+                &mut self,
+            ) -> <#ty as ::storey::containers::Storable>::Accessor<
+                ::storey::storage::StorageBranch<&mut S>,
+            > {
+                <#ty as ::storey::containers::Storable>::access_impl(
+                    ::storey::storage::StorageBranch::new(&mut self.storage, ::storey::__private::vec![#(#key),*]),
+                )
+            }
+        }
+    });
+
+    let field_names = fields.iter().map(|field| &field.name);
+
+    Ok(quote! {
+        #[doc = #access_doc]
+        #vis struct #access<S> {
+            storage: S,
+        }
+
+        // Not every layout uses both the shared and the mutable accessor of every field.
+        #[allow(dead_code)]
+        impl<S> #access<S> {
+            #(#methods)*
+        }
+
+        impl #name {
+            /// Acquire an accessor for this layout, rooted at the top of `storage`.
+            #vis fn access<S: ::storey::storage::StorageRef>(storage: S) -> #access<::storey::storage::StorageBranch<S>> {
+                <Self as ::storey::containers::Storable>::access_impl(
+                    ::storey::storage::StorageBranch::new(storage, ::storey::__private::Vec::new()),
+                )
+            }
+        }
+
+        // The layout struct is never constructed, so its fields would otherwise be reported
+        // as never read.
+        const _: () = {
+            #[allow(dead_code)]
+            fn read_fields(layout: &#name) {
+                #(let _ = &layout.#field_names;)*
+            }
+        };
+
+        impl ::storey::containers::Storable for #name {
+            type Kind = ::storey::containers::NonTerminal;
+            type Accessor<S> = #access<S>;
+            type Key = ();
+            type KeyDecodeError = ::core::convert::Infallible;
+            type Value = ();
+            type ValueDecodeError = ::core::convert::Infallible;
+
+            fn access_impl<S>(storage: S) -> #access<S> {
+                #access { storage }
+            }
+
+            fn decode_key(_key: &[u8]) -> ::core::result::Result<(), ::core::convert::Infallible> {
+                ::core::result::Result::Ok(())
+            }
+
+            fn decode_value(
+                _value: &[u8],
+            ) -> ::core::result::Result<(), ::core::convert::Infallible> {
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<StorableField>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`Storable` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`Storable` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`Storable` can't be derived for generic structs",
+        ));
+    }
+
+    let wide_keys = parse_wide_keys(input)?;
+    let mut result: Vec<StorableField> = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let name = field.ident.clone().expect("fields are named");
+        let (key, lit) = parse_key(field, wide_keys)?;
+
+        if let Some(other) = result.iter().find(|other| other.key == key) {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!("duplicate key, already used by `{}`", other.name.unraw()),
+            ));
+        }
+
+        result.push(StorableField {
+            name,
+            ty: field.ty.clone(),
+            key,
+            docs: field
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .cloned()
+                .collect(),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Parse the `#[storable(...)]` attributes of the struct, returning whether keys are `u16`.
+fn parse_wide_keys(input: &DeriveInput) -> syn::Result<bool> {
+    let mut wide_keys = false;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("storable"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("wide_keys") {
+                wide_keys = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported storable attribute, expected `wide_keys`"))
+            }
+        })?;
+    }
+
+    Ok(wide_keys)
+}
+
+/// Parse the `#[key(n)]` attribute of a field, returning the encoded key.
+///
+/// Keys are a single byte, or two big-endian bytes with `wide_keys`.
+fn parse_key(field: &syn::Field, wide_keys: bool) -> syn::Result<(Vec<u8>, LitInt)> {
+    let mut attrs = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("key"));
+
+    let Some(attr) = attrs.next() else {
+        return Err(syn::Error::new_spanned(
+            field,
+            "every field of a `Storable` struct needs a `#[key(n)]` attribute",
+        ));
+    };
+    if let Some(attr) = attrs.next() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "a field can only have one key",
+        ));
+    }
+
+    let lit: LitInt = attr.parse_args()?;
+    let key = if wide_keys {
+        lit.base10_parse::<u16>()?.to_be_bytes().to_vec()
+    } else {
+        vec![lit.base10_parse::<u8>()?]
+    };
+    Ok((key, lit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storable_error(input: DeriveInput) -> String {
+        derive_storable(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn keys_and_docs() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Layout {
+                /// The config.
+                #[key(3)]
+                config: Item<u64, E>,
+                #[key(0)]
+                r#type: Map<String, Item<u64, E>>,
+            }
+        };
+        let fields = parse_fields(&input).unwrap();
+
+        let keys: Vec<_> = fields.iter().map(|field| field.key.as_slice()).collect();
+        assert_eq!(keys, [[3], [0]]);
+        assert_eq!(fields[0].docs.len(), 1);
+        assert!(fields[1].docs.is_empty());
+    }
+
+    #[test]
+    fn wide_keys() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[storable(wide_keys)]
+            struct Layout {
+                #[key(3)]
+                a: Item<u64, E>,
+                #[key(65535)]
+                b: Item<u64, E>,
+                #[key(768)]
+                c: Item<u64, E>,
+            }
+        };
+        let fields = parse_fields(&input).unwrap();
+
+        let keys: Vec<_> = fields.iter().map(|field| field.key.as_slice()).collect();
+        assert_eq!(keys, [[0, 3], [255, 255], [3, 0]]);
+
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                #[storable(wide_keys)]
+                struct OutOfRange {
+                    #[key(65536)]
+                    a: Item<u64, E>,
+                }
+            )),
+            "number too large to fit in target type"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                #[storable(narrow_keys)]
+                struct Unknown {
+                    #[key(0)]
+                    a: Item<u64, E>,
+                }
+            )),
+            "unsupported storable attribute, expected `wide_keys`"
+        );
+    }
+
+    #[test]
+    fn raw_field_names() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Layout {
+                #[key(0)]
+                r#type: Item<u64, E>,
+            }
+        };
+        let output = derive_storable(input).unwrap().to_string();
+
+        assert!(output.contains("fn r#type ("));
+        assert!(output.contains("fn type_mut ("));
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct Tuple(#[key(0)] Item<u64, E>);
+            )),
+            "`Storable` can only be derived for structs with named fields"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                enum NotAStruct {
+                    A,
+                }
+            )),
+            "`Storable` can only be derived for structs with named fields"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct Generic<E> {
+                    #[key(0)]
+                    a: Item<u64, E>,
+                }
+            )),
+            "`Storable` can't be derived for generic structs"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct Missing {
+                    a: Item<u64, E>,
+                }
+            )),
+            "every field of a `Storable` struct needs a `#[key(n)]` attribute"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct Duplicate {
+                    #[key(1)]
+                    a: Item<u64, E>,
+                    #[key(1)]
+                    b: Item<u64, E>,
+                }
+            )),
+            "duplicate key, already used by `a`"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct TwoKeys {
+                    #[key(1)]
+                    #[key(2)]
+                    a: Item<u64, E>,
+                }
+            )),
+            "a field can only have one key"
+        );
+        assert_eq!(
+            storable_error(syn::parse_quote!(
+                struct OutOfRange {
+                    #[key(256)]
+                    a: Item<u64, E>,
+                }
+            )),
+            "number too large to fit in target type"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use storey::containers::map::key::{
+    DynamicKey, EnumKeyDecodeError, FixedSizeKey, StructKeyDecodeError,
+};
+use storey::containers::map::{Key, OwnedKey};
+use storey::containers::{Item, IterableAccessor as _, Map};
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+#[derive(Debug, Clone, PartialEq, Key, OwnedKey)]
+struct Fixed {
+    a: u32,
+    b: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Key, OwnedKey)]
+struct Named {
+    owner: String,
+    id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Key, OwnedKey)]
+struct Reordered {
+    #[key(order = 2)]
+    name: String,
+    #[key(order = 0)]
+    id: u32,
+    #[key(order = 1)]
+    flag: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Key, OwnedKey)]
+struct Tuple(String, i16, String);
+
+#[derive(Debug, Clone, PartialEq, Key)]
+struct Skipping {
+    id: u32,
+    #[key(skip)]
+    #[allow(dead_code)]
+    cached_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Key, OwnedKey)]
+struct Wrapper(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Key, OwnedKey)]
+enum Status {
+    Active,
+    Frozen,
+    Closed,
+}
+
+fn kind_of<K: Key>(_: &K) -> &'static str {
+    std::any::type_name::<K::Kind>()
+}
+
+#[test]
+fn kinds() {
+    let fixed = Fixed { a: 1, b: 2 };
+    assert_eq!(kind_of(&fixed), std::any::type_name::<FixedSizeKey<5>>());
+    assert_eq!(
+        kind_of(&Wrapper(1)),
+        std::any::type_name::<FixedSizeKey<8>>()
+    );
+
+    let named = Named {
+        owner: "foo".to_string(),
+        id: 1,
+    };
+    assert_eq!(kind_of(&named), std::any::type_name::<DynamicKey>());
+}
+
+#[test]
+fn roundtrip() {
+    let fixed = Fixed { a: 1, b: 2 };
+    assert_eq!(fixed.encode(), [0, 0, 0, 1, 2]);
+    assert_eq!(Fixed::from_bytes(&fixed.encode()), Ok(fixed));
+
+    let named = Named {
+        owner: "foo".to_string(),
+        id: 7,
+    };
+    assert_eq!(named.encode(), [3, 102, 111, 111, 0, 0, 0, 0, 0, 0, 0, 7]);
+    assert_eq!(Named::from_bytes(&named.encode()), Ok(named));
+
+    let tuple = Tuple("ab".to_string(), -1, String::new());
+    assert_eq!(tuple.encode(), [2, 97, 98, 0x7f, 0xff]);
+    assert_eq!(Tuple::from_bytes(&tuple.encode()), Ok(tuple));
+
+    let wrapper = Wrapper(42);
+    assert_eq!(wrapper.encode(), 42u64.to_be_bytes());
+    assert_eq!(Wrapper::from_bytes(&wrapper.encode()), Ok(wrapper));
+}
+
+#[test]
+fn long_fields() {
+    // a non-last field of 255 bytes or more gets an escaped varint length prefix
+    let named = Named {
+        owner: "a".repeat(300),
+        id: 7,
+    };
+    let encoded = named.encode();
+    assert_eq!(encoded[..3], [0xFF, 0xAC, 0x02]);
+    assert_eq!(encoded.len(), 3 + 300 + 8);
+    assert_eq!(Named::from_bytes(&encoded), Ok(named));
+
+    // `-7838` and `-7839` are encoded as `ab` and `aa`
+    let tuple = Tuple("a".repeat(300), -7838, "b".to_string());
+    assert_eq!(Tuple::from_bytes(&tuple.encode()), Ok(tuple.clone()));
+
+    // with a truncated prefix, these two used to share an encoding
+    let other = Tuple("a".repeat(44), -7839, "a".repeat(254) + "abb");
+    assert_ne!(tuple.encode(), other.encode());
+    assert_eq!(Tuple::from_bytes(&other.encode()), Ok(other));
+}
+
+#[test]
+fn reordering() {
+    let key = Reordered {
+        name: "foo".to_string(),
+        id: 5,
+        flag: 1,
+    };
+
+    // laid out by `order`, not by declaration
+    assert_eq!(key.encode(), [0, 0, 0, 5, 1, 102, 111, 111]);
+    assert_eq!(Reordered::from_bytes(&key.encode()), Ok(key));
+
+    // which also determines iteration order
+    let mut storage = TestStorage::new();
+    let map = Map::<Reordered, Item<u64, TestEncoding>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    let keys = [(2, 0, "a"), (1, 1, "a"), (1, 0, "b")].map(|(id, flag, name)| Reordered {
+        name: name.to_string(),
+        id,
+        flag,
+    });
+    for key in &keys {
+        access.entry_mut(key).set(&1).unwrap();
+    }
+
+    let found = access.keys().map(|res| res.unwrap().0).collect::<Vec<_>>();
+    assert_eq!(found, [keys[2].clone(), keys[1].clone(), keys[0].clone()]);
+}
+
+#[test]
+fn skipping() {
+    let key = Skipping {
+        id: 5,
+        cached_name: "foo".to_string(),
+    };
+    assert_eq!(kind_of(&key), std::any::type_name::<FixedSizeKey<4>>());
+    assert_eq!(key.encode(), [0, 0, 0, 5]);
+
+    let mut storage = TestStorage::new();
+    let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+    map.access(&mut storage).entry_mut(&5).set(&1337).unwrap();
+
+    // a skipped field doesn't affect the lookup
+    let other = Skipping {
+        id: 5,
+        cached_name: "bar".to_string(),
+    };
+    assert_eq!(key.encode(), other.encode());
+    assert_eq!(map.access(&storage).entry(&5).get().unwrap(), Some(1337));
+}
+
+#[test]
+fn decode_errors() {
+    assert_eq!(
+        Named::from_bytes(&[5, 102, 111]),
+        Err(StructKeyDecodeError::KeyTooShort)
+    );
+    assert_eq!(
+        Named::from_bytes(&[1, 0xff, 0, 0, 0, 0, 0, 0, 0, 7]),
+        Err(StructKeyDecodeError::Field("owner"))
+    );
+    assert_eq!(
+        Tuple::from_bytes(&[0, 0x80, 0, 0xff]),
+        Err(StructKeyDecodeError::Field("2"))
+    );
+}
+
+#[test]
+fn multi_field_map_key() {
+    let mut storage = TestStorage::new();
+    let map = Map::<Named, Item<u64, TestEncoding>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    let key = |owner: &str, id| Named {
+        owner: owner.to_string(),
+        id,
+    };
+
+    access.entry_mut(&key("alice", 1)).set(&10).unwrap();
+    access.entry_mut(&key("alice", 2)).set(&20).unwrap();
+    access.entry_mut(&key("bob", 1)).set(&30).unwrap();
+
+    assert_eq!(access.entry(&key("alice", 2)).get().unwrap(), Some(20));
+    assert_eq!(access.entry(&key("bob", 2)).get().unwrap(), None);
+
+    // the keys are decoded back from storage; the length-prefixed owner sorts by length first
+    let pairs = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        pairs,
+        [
+            ((key("bob", 1), ()), 30),
+            ((key("alice", 1), ()), 10),
+            ((key("alice", 2), ()), 20),
+        ]
+    );
+}
+
+#[test]
+fn fieldless_enum() {
+    assert_eq!(
+        kind_of(&Status::Active),
+        std::any::type_name::<FixedSizeKey<1>>()
+    );
+
+    for (status, index) in [
+        (Status::Active, 0),
+        (Status::Frozen, 1),
+        (Status::Closed, 2),
+    ] {
+        assert_eq!(status.encode(), [index]);
+        assert_eq!(Status::from_bytes(&[index]), Ok(status));
+    }
+
+    assert_eq!(
+        Status::from_bytes(&[3]),
+        Err(EnumKeyDecodeError::InvalidVariant(3))
+    );
+    assert_eq!(
+        Status::from_bytes(&[]),
+        Err(EnumKeyDecodeError::InvalidLength)
+    );
+    assert_eq!(
+        Status::from_bytes(&[0, 0]),
+        Err(EnumKeyDecodeError::InvalidLength)
+    );
+
+    // as a fixed-size key, the enum can be followed by other members of a composite key
+    let mut storage = TestStorage::new();
+    let map = Map::<Status, Map<String, Item<u64, TestEncoding>>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    access
+        .entry_mut(&Status::Frozen)
+        .entry_mut("bob")
+        .set(&2)
+        .unwrap();
+    access
+        .entry_mut(&Status::Active)
+        .entry_mut("alice")
+        .set(&1)
+        .unwrap();
+
+    let keys = access.keys().map(|res| res.unwrap().0).collect::<Vec<_>>();
+    assert_eq!(keys, [Status::Active, Status::Frozen]);
+}
@@ -0,0 +1,182 @@
+use storey::containers::{Column, Item, IterableAccessor as _, Map, Storable};
+use storey::storage::StorageBackend as _;
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+#[derive(Storable)]
+struct Contract {
+    /// The contract's configuration.
+    #[key(0)]
+    config: Item<u64, TestEncoding>,
+    #[key(2)]
+    history: Column<u64, TestEncoding>,
+    #[key(1)]
+    accounts: Map<String, Account>,
+}
+
+#[derive(Storable)]
+struct Account {
+    #[key(0)]
+    balance: Item<u64, TestEncoding>,
+    #[key(1)]
+    nonce: Item<u64, TestEncoding>,
+}
+
+#[test]
+fn root_layout() {
+    let mut storage = TestStorage::new();
+    let mut access = Contract::access(&mut storage);
+
+    access.config_mut().set(&42).unwrap();
+    access.history_mut().push(&1).unwrap();
+    access.history_mut().push(&2).unwrap();
+
+    assert_eq!(access.config().get().unwrap(), Some(42));
+    assert_eq!(access.history().len().unwrap(), 2);
+    assert_eq!(access.history().get(2).unwrap(), Some(2));
+
+    // fields are stored under their keys, at the root of the storage
+    assert_eq!(storage.get(&[0]), Some(42u64.to_le_bytes().to_vec()));
+}
+
+#[test]
+fn nested_layout() {
+    let mut storage = TestStorage::new();
+    let mut access = Contract::access(&mut storage);
+
+    let mut accounts = access.accounts_mut();
+    accounts.entry_mut("alice").balance_mut().set(&100).unwrap();
+    accounts.entry_mut("alice").nonce_mut().set(&1).unwrap();
+    accounts.entry_mut("bob").balance_mut().set(&5).unwrap();
+
+    let accounts = access.accounts();
+    assert_eq!(accounts.entry("alice").balance().get().unwrap(), Some(100));
+    assert_eq!(accounts.entry("alice").nonce().get().unwrap(), Some(1));
+    assert_eq!(accounts.entry("bob").balance().get().unwrap(), Some(5));
+    assert_eq!(accounts.entry("bob").nonce().get().unwrap(), None);
+
+    // iterating over a map of layouts yields a map key per stored item, with `()` for the
+    // layout; string keys are length-prefixed, so shorter ones come first
+    let keys = accounts.keys().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        keys,
+        [
+            ("bob".to_string(), ()),
+            ("alice".to_string(), ()),
+            ("alice".to_string(), ())
+        ]
+    );
+
+    assert_eq!(
+        storage.get(&[&[1, 5][..], b"alice", &[0]].concat()),
+        Some(100u64.to_le_bytes().to_vec())
+    );
+}
+
+#[derive(Storable)]
+struct Root {
+    #[key(0)]
+    module: Module,
+    #[key(1)]
+    other: Module,
+}
+
+#[derive(Storable)]
+struct Module {
+    #[key(0)]
+    settings: Settings,
+    #[key(1)]
+    counter: Item<u64, TestEncoding>,
+}
+
+#[derive(Storable)]
+struct Settings {
+    #[key(0)]
+    item: Item<u64, TestEncoding>,
+}
+
+#[test]
+fn nested_layouts() {
+    let mut storage = TestStorage::new();
+    let mut access = Root::access(&mut storage);
+
+    access
+        .module_mut()
+        .settings_mut()
+        .item_mut()
+        .set(&1)
+        .unwrap();
+    access.module_mut().counter_mut().set(&2).unwrap();
+    access
+        .other_mut()
+        .settings_mut()
+        .item_mut()
+        .set(&3)
+        .unwrap();
+
+    assert_eq!(access.module().settings().item().get().unwrap(), Some(1));
+    assert_eq!(access.module().counter().get().unwrap(), Some(2));
+    assert_eq!(access.other().settings().item().get().unwrap(), Some(3));
+    assert_eq!(access.other().counter().get().unwrap(), None);
+
+    // each level of nesting adds the field's key to the path
+    assert_eq!(storage.get(&[0, 0, 0]), Some(1u64.to_le_bytes().to_vec()));
+    assert_eq!(storage.get(&[0, 1]), Some(2u64.to_le_bytes().to_vec()));
+    assert_eq!(storage.get(&[1, 0, 0]), Some(3u64.to_le_bytes().to_vec()));
+}
+
+mod state {
+    use super::*;
+
+    #[derive(Storable)]
+    pub(crate) struct Internal {
+        #[key(0)]
+        value: Item<u64, TestEncoding>,
+    }
+
+    #[derive(Storable)]
+    pub(super) struct Scoped {
+        #[key(0)]
+        value: Item<u64, TestEncoding>,
+    }
+}
+
+#[test]
+fn restricted_visibility() {
+    // the accessor and its methods share the visibility of the layout struct
+    let mut storage = TestStorage::new();
+    let mut access: state::InternalAccess<_> = state::Internal::access(&mut storage);
+    access.value_mut().set(&1).unwrap();
+    assert_eq!(access.value().get().unwrap(), Some(1));
+
+    let mut storage = TestStorage::new();
+    let mut access: state::ScopedAccess<_> = state::Scoped::access(&mut storage);
+    access.value_mut().set(&2).unwrap();
+    assert_eq!(access.value().get().unwrap(), Some(2));
+}
+
+#[derive(Storable)]
+#[storable(wide_keys)]
+struct Wide {
+    #[key(1)]
+    small: Item<u64, TestEncoding>,
+    #[key(300)]
+    large: Item<u64, TestEncoding>,
+}
+
+#[test]
+fn wide_keys() {
+    let mut storage = TestStorage::new();
+    let mut access = Wide::access(&mut storage);
+
+    access.small_mut().set(&1).unwrap();
+    access.large_mut().set(&2).unwrap();
+
+    assert_eq!(access.small().get().unwrap(), Some(1));
+    assert_eq!(access.large().get().unwrap(), Some(2));
+
+    // keys are two big-endian bytes
+    assert_eq!(storage.get(&[0, 1]), Some(1u64.to_le_bytes().to_vec()));
+    assert_eq!(storage.get(&[1, 44]), Some(2u64.to_le_bytes().to_vec()));
+}
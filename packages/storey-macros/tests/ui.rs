@@ -0,0 +1,7 @@
+// Compile-fail tests checking that invalid derive input produces readable errors.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
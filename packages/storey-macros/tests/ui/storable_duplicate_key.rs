@@ -0,0 +1,12 @@
+use mocks::encoding::TestEncoding;
+use storey::containers::{Item, Map, Storable};
+
+#[derive(Storable)]
+pub struct Contract {
+    #[key(0)]
+    config: Item<u64, TestEncoding>,
+    #[key(0)]
+    balances: Map<String, Item<u64, TestEncoding>>,
+}
+
+fn main() {}
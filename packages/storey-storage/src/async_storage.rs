@@ -0,0 +1,54 @@
+use alloc::vec::Vec;
+use core::future::Future;
+
+/// An asynchronous read interface for binary key-value storage.
+///
+/// This mirrors [`Storage`](crate::Storage) for backends that can only be reached
+/// asynchronously, like a networked KV store used by an off-chain indexer.
+///
+/// The returned futures carry no `Send` bound, so the traits can be implemented for
+/// single-threaded runtimes too. Unlike `Storage`, these traits are not dyn-compatible.
+///
+/// # Iteration
+///
+/// There is no async counterpart to [`IterableStorage`](crate::IterableStorage) yet. The
+/// intended design is an `AsyncIterableStorage` trait whose `keys`/`values`/`pairs` return
+/// an async iterator (a type with `fn next(&mut self) -> impl Future<Output = Option<_>>`,
+/// since `core` has no `Stream` trait), with prefixing and bound translation for branches
+/// done the same way as in the sync traits. Backends would be free to fetch entries in
+/// batches behind that interface. Container iteration would then be exposed through an
+/// async twin of `IterableAccessor`.
+pub trait AsyncStorage {
+    /// Get the value of the key.
+    fn get(&self, key: &[u8]) -> impl Future<Output = Option<Vec<u8>>>;
+
+    /// Check if the key exists.
+    fn has(&self, key: &[u8]) -> impl Future<Output = bool> {
+        async { self.get(key).await.is_some() }
+    }
+
+    /// Get the value of the key in the metadata namespace.
+    fn get_meta(&self, key: &[u8]) -> impl Future<Output = Option<Vec<u8>>>;
+
+    /// Check if the key exists in the metadata namespace.
+    fn has_meta(&self, key: &[u8]) -> impl Future<Output = bool> {
+        async { self.get_meta(key).await.is_some() }
+    }
+}
+
+/// An asynchronous write interface for binary key-value storage.
+///
+/// This mirrors [`StorageMut`](crate::StorageMut). See [`AsyncStorage`] for details.
+pub trait AsyncStorageMut: AsyncStorage {
+    /// Set the value of the key.
+    fn set(&mut self, key: &[u8], value: &[u8]) -> impl Future<Output = ()>;
+
+    /// Remove the key.
+    fn remove(&mut self, key: &[u8]) -> impl Future<Output = ()>;
+
+    /// Set the value of the key in the metadata namespace.
+    fn set_meta(&mut self, key: &[u8], value: &[u8]) -> impl Future<Output = ()>;
+
+    /// Remove the key in the metadata namespace.
+    fn remove_meta(&mut self, key: &[u8]) -> impl Future<Output = ()>;
+}
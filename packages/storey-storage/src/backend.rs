@@ -1,4 +1,7 @@
-use super::storage::{Storage, StorageMut};
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use super::storage::{IterableMetaStorage, IterableStorage, Storage, StorageMut};
 
 /// A trait for immutably accessing a storage backend.
 ///
@@ -13,6 +16,14 @@ pub trait StorageBackend {
     fn has(&self, key: &[u8]) -> bool {
         self.get(key).is_some()
     }
+
+    /// Get the values associated with several keys, in the same order as `keys`.
+    ///
+    /// The default implementation calls [`get`](Self::get) for each key. Backends that can
+    /// satisfy several lookups at once should override it.
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
 }
 
 /// A trait for mutably accessing a storage backend.
@@ -26,6 +37,21 @@ pub trait StorageBackendMut {
 
     /// Remove the value associated with the given key.
     fn remove(&mut self, key: &[u8]);
+
+    /// Remove every entry from the storage backend, metadata included.
+    ///
+    /// This is mostly useful for tearing down test storages. The default implementation
+    /// collects all keys and removes them one by one. Backends that can do better (e.g. by
+    /// swapping out their underlying collection) should override it.
+    fn clear_all(&mut self)
+    where
+        Self: IterableStorage + Sized,
+    {
+        let keys: Vec<_> = self.keys(Bound::Unbounded, Bound::Unbounded).collect();
+        for key in keys {
+            StorageBackendMut::remove(self, &key);
+        }
+    }
 }
 
 impl<B> Storage for B
@@ -40,6 +66,10 @@ where
         StorageBackend::has(self, key)
     }
 
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        StorageBackend::get_many(self, keys)
+    }
+
     fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
         StorageBackend::get(self, &meta_key(key))
     }
@@ -51,7 +81,7 @@ where
 
 impl<B> StorageMut for B
 where
-    B: StorageBackendMut,
+    B: StorageBackend + StorageBackendMut,
 {
     fn set(&mut self, key: &[u8], value: &[u8]) {
         StorageBackendMut::set(self, key, value)
@@ -70,6 +100,50 @@ where
     }
 }
 
+impl<B> IterableMetaStorage for B
+where
+    B: StorageBackend + IterableStorage,
+{
+    type MetaKeysIterator<'a> = MetaKeysIter<B::KeysIterator<'a>> where Self: 'a;
+
+    fn meta_keys<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::MetaKeysIterator<'a> {
+        let start = match start {
+            Bound::Unbounded => Bound::Included(meta_key(&[])),
+            bound => bound.map(meta_key),
+        };
+        let end = end.map(meta_key);
+
+        MetaKeysIter {
+            inner: self.keys(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+        }
+    }
+}
+
+/// An iterator over the metadata keys of a storage backend.
+///
+/// This is returned by the [`IterableMetaStorage`] implementation for storage backends.
+pub struct MetaKeysIter<I> {
+    inner: I,
+}
+
+impl<I> Iterator for MetaKeysIter<I>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|key| key[1..].to_vec())
+    }
+}
+
 fn meta_key(key: &[u8]) -> Vec<u8> {
     let mut meta_key = Vec::with_capacity(key.len() + 1);
     meta_key.push(255);
@@ -0,0 +1,327 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter::Peekable;
+use core::ops::Bound;
+
+use super::backend::{StorageBackend, StorageBackendMut};
+use super::storage::{IterableStorage, RevIterableStorage};
+
+/// A storage backend wrapper that stages writes in memory on top of another backend.
+///
+/// Writes go to an in-memory overlay, leaving the wrapped backend untouched. Reads and
+/// iteration see the overlay merged with the wrapped backend, so staged writes shadow the
+/// original values and staged removals hide them. Once done, the staged writes can either be
+/// applied to the wrapped backend with [`commit`](Self::commit) or dropped with
+/// [`rollback`](Self::rollback).
+///
+/// This is meant for tests and speculative execution.
+///
+/// # Example
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use storey_storage::{StorageBackend, StorageBackendMut};
+/// # #[derive(Default)]
+/// # struct Backend(BTreeMap<Vec<u8>, Vec<u8>>);
+/// # impl StorageBackend for Backend {
+/// #     fn get(&self, key: &[u8]) -> Option<Vec<u8>> { self.0.get(key).cloned() }
+/// # }
+/// # impl StorageBackendMut for Backend {
+/// #     fn set(&mut self, key: &[u8], value: &[u8]) { self.0.insert(key.to_vec(), value.to_vec()); }
+/// #     fn remove(&mut self, key: &[u8]) { self.0.remove(key); }
+/// # }
+/// use storey_storage::CachedStorage;
+///
+/// let mut backend = Backend::default();
+/// backend.set(b"foo", b"bar");
+///
+/// let mut storage = CachedStorage::new(backend);
+/// storage.remove(b"foo");
+/// storage.set(b"baz", b"qux");
+/// assert_eq!(storage.get(b"foo"), None);
+///
+/// let backend = storage.rollback();
+/// assert_eq!(backend.get(b"foo"), Some(b"bar".to_vec()));
+/// assert_eq!(backend.get(b"baz"), None);
+///
+/// let mut storage = CachedStorage::new(backend);
+/// storage.set(b"baz", b"qux");
+///
+/// let backend = storage.commit();
+/// assert_eq!(backend.get(b"baz"), Some(b"qux".to_vec()));
+/// ```
+pub struct CachedStorage<B> {
+    inner: B,
+    /// Staged writes. `None` marks a removed key.
+    overlay: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<B> CachedStorage<B> {
+    /// Wrap the given backend, with no writes staged.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            overlay: BTreeMap::new(),
+        }
+    }
+
+    /// The wrapped backend, without the staged writes.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Apply the staged writes to the wrapped backend and return it.
+    pub fn commit(mut self) -> B
+    where
+        B: StorageBackendMut,
+    {
+        for (key, value) in self.overlay {
+            match value {
+                Some(value) => self.inner.set(&key, &value),
+                None => self.inner.remove(&key),
+            }
+        }
+
+        self.inner
+    }
+
+    /// Drop the staged writes and return the wrapped backend.
+    pub fn rollback(self) -> B {
+        self.inner
+    }
+
+    /// The keys whose staged value differs from the one in the wrapped backend, in
+    /// lexicographical order.
+    ///
+    /// Staged writes that set a key to the value it already has, or remove a key that
+    /// doesn't exist, are not differences.
+    pub fn diff(&self) -> Vec<Vec<u8>>
+    where
+        B: StorageBackend,
+    {
+        self.overlay
+            .iter()
+            .filter(|(key, value)| self.inner.get(key) != **value)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn overlay_range<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> OverlayIter<'a> {
+        // `BTreeMap::range` panics on an empty or inverted range, so check for that first.
+        let empty = match (start, end) {
+            (Bound::Included(s), Bound::Included(e)) => s > e,
+            (Bound::Included(s), Bound::Excluded(e))
+            | (Bound::Excluded(s), Bound::Included(e))
+            | (Bound::Excluded(s), Bound::Excluded(e)) => s >= e,
+            _ => false,
+        };
+
+        if empty {
+            Box::new(core::iter::empty())
+        } else {
+            Box::new(self.overlay.range::<[u8], _>((start, end)))
+        }
+    }
+}
+
+impl<B> StorageBackend for CachedStorage<B>
+where
+    B: StorageBackend,
+{
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.overlay.get(key) {
+            Some(value) => value.clone(),
+            None => self.inner.get(key),
+        }
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        match self.overlay.get(key) {
+            Some(value) => value.is_some(),
+            None => self.inner.has(key),
+        }
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        // keys missing from the overlay are fetched from the backend in one batch
+        let misses: Vec<&[u8]> = keys
+            .iter()
+            .copied()
+            .filter(|key| !self.overlay.contains_key(*key))
+            .collect();
+        let mut fetched = self.inner.get_many(&misses).into_iter();
+
+        keys.iter()
+            .map(|key| match self.overlay.get(*key) {
+                Some(value) => value.clone(),
+                None => fetched.next().flatten(),
+            })
+            .collect()
+    }
+}
+
+impl<B> StorageBackendMut for CachedStorage<B> {
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.overlay.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.overlay.insert(key.to_vec(), None);
+    }
+}
+
+impl<B> IterableStorage for CachedStorage<B>
+where
+    B: IterableStorage,
+{
+    type KeysIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a> where Self: 'a;
+    type ValuesIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a> where Self: 'a;
+    type PairsIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> where Self: 'a;
+
+    fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
+        Box::new(Merge::keys(
+            self.inner.keys(start, end),
+            self.overlay_range(start, end),
+        ))
+    }
+
+    fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
+        Box::new(self.pairs(start, end).map(|(_, v)| v))
+    }
+
+    fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        Box::new(Merge::pairs(
+            self.inner.pairs(start, end),
+            self.overlay_range(start, end),
+        ))
+    }
+}
+
+impl<B> RevIterableStorage for CachedStorage<B>
+where
+    B: RevIterableStorage,
+{
+    type RevKeysIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a> where Self: 'a;
+    type RevValuesIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a> where Self: 'a;
+    type RevPairsIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> where Self: 'a;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        Box::new(
+            Merge::keys(
+                self.inner.rev_keys(start, end),
+                Box::new(self.overlay_range(start, end).rev()),
+            )
+            .reversed(),
+        )
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        Box::new(self.rev_pairs(start, end).map(|(_, v)| v))
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        Box::new(
+            Merge::pairs(
+                self.inner.rev_pairs(start, end),
+                Box::new(self.overlay_range(start, end).rev()),
+            )
+            .reversed(),
+        )
+    }
+}
+
+type OverlayIter<'a> = Box<dyn DoubleEndedIterator<Item = (&'a Vec<u8>, &'a Option<Vec<u8>>)> + 'a>;
+
+/// Merges an iterator over the wrapped backend with the staged writes in the same range.
+///
+/// Both sides must be sorted in the same direction. Where both have the same key, the staged
+/// write wins, and a staged removal hides the key altogether.
+struct Merge<'a, I: Iterator> {
+    base: Peekable<I>,
+    overlay: Peekable<OverlayIter<'a>>,
+    reverse: bool,
+    key_of: fn(&I::Item) -> &[u8],
+    from_overlay: fn(&[u8], &[u8]) -> I::Item,
+}
+
+impl<'a, I> Merge<'a, I>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    fn keys(base: I, overlay: OverlayIter<'a>) -> Self {
+        Self {
+            base: base.peekable(),
+            overlay: overlay.peekable(),
+            reverse: false,
+            key_of: |key| key,
+            from_overlay: |key, _| key.to_vec(),
+        }
+    }
+}
+
+impl<'a, I> Merge<'a, I>
+where
+    I: Iterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn pairs(base: I, overlay: OverlayIter<'a>) -> Self {
+        Self {
+            base: base.peekable(),
+            overlay: overlay.peekable(),
+            reverse: false,
+            key_of: |(key, _)| key,
+            from_overlay: |key, value| (key.to_vec(), value.to_vec()),
+        }
+    }
+}
+
+impl<I: Iterator> Merge<'_, I> {
+    /// Expect both sides in descending order.
+    fn reversed(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+}
+
+impl<I: Iterator> Iterator for Merge<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let order = match (self.base.peek(), self.overlay.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(base), Some((key, _))) => {
+                    let order = (self.key_of)(base).cmp(key.as_slice());
+                    if self.reverse {
+                        order.reverse()
+                    } else {
+                        order
+                    }
+                }
+            };
+
+            if order == Ordering::Less {
+                return self.base.next();
+            }
+            if order == Ordering::Equal {
+                // the staged write shadows the original entry
+                self.base.next();
+            }
+
+            let (key, value) = self.overlay.next()?;
+            if let Some(value) = value {
+                return Some((self.from_overlay)(key, value));
+            }
+        }
+    }
+}
@@ -1,5 +1,32 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "async")]
+mod async_storage;
 mod backend;
+mod cache;
+mod metered;
+mod observe;
+mod prefixed;
+mod seek;
 mod storage;
+#[cfg(feature = "wal")]
+mod wal;
+
+#[cfg(feature = "async")]
+pub use async_storage::{AsyncStorage, AsyncStorageMut};
+pub use backend::{MetaKeysIter, StorageBackend, StorageBackendMut};
+pub use cache::CachedStorage;
+pub use metered::{MeteredIter, MeteredStorage, StorageStats};
+pub use observe::ObservedStorage;
+pub use prefixed::{PrefixedKVIter, PrefixedKeysIter, PrefixedView};
+pub use seek::{ScanSeek, SeekKey, Seekable};
+pub use storage::{IterableMetaStorage, IterableStorage, RevIterableStorage, Storage, StorageMut};
+#[cfg(feature = "wal")]
+pub use wal::WalStorage;
 
-pub use backend::{StorageBackend, StorageBackendMut};
-pub use storage::{IterableStorage, RevIterableStorage, Storage, StorageMut};
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::prefixed::{sub_bounds, successor};
+}
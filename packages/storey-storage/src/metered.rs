@@ -0,0 +1,239 @@
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ops::Bound;
+
+use super::backend::{StorageBackend, StorageBackendMut};
+use super::seek::Seekable;
+use super::storage::{IterableStorage, RevIterableStorage};
+
+/// A storage backend wrapper that counts the operations performed on it.
+///
+/// Every read, write and iteration step is tallied in a [`StorageStats`], available through
+/// [`stats`](Self::stats). Keys are the raw backend keys, so metadata operations are counted
+/// like any other.
+///
+/// This is meant for benchmarks, fuzzing and tests, e.g. to catch a container operation
+/// that scans a whole collection when it shouldn't.
+///
+/// # Example
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use storey_storage::{StorageBackend, StorageBackendMut};
+/// # #[derive(Default)]
+/// # struct Backend(BTreeMap<Vec<u8>, Vec<u8>>);
+/// # impl StorageBackend for Backend {
+/// #     fn get(&self, key: &[u8]) -> Option<Vec<u8>> { self.0.get(key).cloned() }
+/// # }
+/// # impl StorageBackendMut for Backend {
+/// #     fn set(&mut self, key: &[u8], value: &[u8]) { self.0.insert(key.to_vec(), value.to_vec()); }
+/// #     fn remove(&mut self, key: &[u8]) { self.0.remove(key); }
+/// # }
+/// use storey_storage::MeteredStorage;
+///
+/// let mut storage = MeteredStorage::new(Backend::default());
+/// storage.set(b"foo", b"bar");
+/// assert_eq!(storage.get(b"foo"), Some(b"bar".to_vec()));
+///
+/// let stats = storage.stats();
+/// assert_eq!(stats.sets, 1);
+/// assert_eq!(stats.bytes_written, 6);
+/// assert_eq!(stats.gets, 1);
+/// assert_eq!(stats.bytes_read, 3);
+/// ```
+pub struct MeteredStorage<B> {
+    inner: B,
+    stats: Cell<StorageStats>,
+}
+
+/// Operation counts collected by a [`MeteredStorage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// The number of `get` and `has` calls. A `get_many` call counts once per key.
+    pub gets: u64,
+    /// The number of `set` calls.
+    pub sets: u64,
+    /// The number of `remove` calls.
+    pub removes: u64,
+    /// The number of entries yielded by iterators.
+    pub iterator_advances: u64,
+    /// The number of bytes returned by reads, counting both keys and values yielded by
+    /// iterators.
+    pub bytes_read: u64,
+    /// The number of bytes passed to `set`, counting both keys and values.
+    pub bytes_written: u64,
+}
+
+impl<B> MeteredStorage<B> {
+    /// Wrap the given backend, with all counters at zero.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            stats: Cell::new(StorageStats::default()),
+        }
+    }
+
+    /// The operations counted so far.
+    pub fn stats(&self) -> StorageStats {
+        self.stats.get()
+    }
+
+    /// Reset all counters to zero.
+    pub fn reset_stats(&self) {
+        self.stats.set(StorageStats::default());
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Unwrap the backend, dropping the counters.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn record(&self, f: impl FnOnce(&mut StorageStats)) {
+        record(&self.stats, f)
+    }
+}
+
+fn record(stats: &Cell<StorageStats>, f: impl FnOnce(&mut StorageStats)) {
+    let mut current = stats.get();
+    f(&mut current);
+    stats.set(current);
+}
+
+impl<B> StorageBackend for MeteredStorage<B>
+where
+    B: StorageBackend,
+{
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        self.record(|stats| {
+            stats.gets += 1;
+            stats.bytes_read += value.as_ref().map_or(0, |v| v.len() as u64);
+        });
+        value
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        self.record(|stats| stats.gets += 1);
+        self.inner.has(key)
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let values = self.inner.get_many(keys);
+        self.record(|stats| {
+            stats.gets += keys.len() as u64;
+            stats.bytes_read += values.iter().flatten().map(|v| v.len() as u64).sum::<u64>();
+        });
+        values
+    }
+}
+
+impl<B> StorageBackendMut for MeteredStorage<B>
+where
+    B: StorageBackendMut,
+{
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.record(|stats| {
+            stats.sets += 1;
+            stats.bytes_written += (key.len() + value.len()) as u64;
+        });
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.record(|stats| stats.removes += 1);
+        self.inner.remove(key);
+    }
+}
+
+impl<B> IterableStorage for MeteredStorage<B>
+where
+    B: IterableStorage,
+{
+    type KeysIterator<'a> = MeteredIter<'a, B::KeysIterator<'a>> where Self: 'a;
+    type ValuesIterator<'a> = MeteredIter<'a, B::ValuesIterator<'a>> where Self: 'a;
+    type PairsIterator<'a> = MeteredIter<'a, B::PairsIterator<'a>> where Self: 'a;
+
+    fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
+        MeteredIter::new(self.inner.keys(start, end), &self.stats, Vec::len)
+    }
+
+    fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
+        MeteredIter::new(self.inner.values(start, end), &self.stats, Vec::len)
+    }
+
+    fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        MeteredIter::new(self.inner.pairs(start, end), &self.stats, pair_len)
+    }
+}
+
+impl<B> RevIterableStorage for MeteredStorage<B>
+where
+    B: RevIterableStorage,
+{
+    type RevKeysIterator<'a> = MeteredIter<'a, B::RevKeysIterator<'a>> where Self: 'a;
+    type RevValuesIterator<'a> = MeteredIter<'a, B::RevValuesIterator<'a>> where Self: 'a;
+    type RevPairsIterator<'a> = MeteredIter<'a, B::RevPairsIterator<'a>> where Self: 'a;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        MeteredIter::new(self.inner.rev_keys(start, end), &self.stats, Vec::len)
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        MeteredIter::new(self.inner.rev_values(start, end), &self.stats, Vec::len)
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        MeteredIter::new(self.inner.rev_pairs(start, end), &self.stats, pair_len)
+    }
+}
+
+fn pair_len((key, value): &(Vec<u8>, Vec<u8>)) -> usize {
+    key.len() + value.len()
+}
+
+/// An iterator that counts the entries it yields.
+///
+/// This is returned by the [`IterableStorage`] and [`RevIterableStorage`] implementations of
+/// [`MeteredStorage`].
+pub struct MeteredIter<'a, I: Iterator> {
+    inner: I,
+    stats: &'a Cell<StorageStats>,
+    size: fn(&I::Item) -> usize,
+}
+
+impl<'a, I: Iterator> MeteredIter<'a, I> {
+    fn new(inner: I, stats: &'a Cell<StorageStats>, size: fn(&I::Item) -> usize) -> Self {
+        Self { inner, stats, size }
+    }
+}
+
+impl<I: Iterator> Iterator for MeteredIter<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        record(self.stats, |stats| {
+            stats.iterator_advances += 1;
+            stats.bytes_read += (self.size)(&item) as u64;
+        });
+        Some(item)
+    }
+}
+
+impl<I: Seekable> Seekable for MeteredIter<'_, I> {
+    fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(key)
+    }
+}
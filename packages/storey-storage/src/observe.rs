@@ -0,0 +1,149 @@
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use super::backend::{StorageBackend, StorageBackendMut};
+use super::storage::{IterableStorage, RevIterableStorage};
+
+/// A storage backend wrapper that reports every write to a callback.
+///
+/// The callback is called with the affected key on every `set` and `remove`, after the
+/// write has been applied to the wrapped backend. Keys are the raw backend keys, so
+/// writes to metadata are reported with their metadata prefix.
+///
+/// This is meant for off-chain services that keep a read cache above storey and need to
+/// evict entries as storey writes through.
+///
+/// # Example
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use storey_storage::{StorageBackend, StorageBackendMut};
+/// # #[derive(Default)]
+/// # struct Backend(BTreeMap<Vec<u8>, Vec<u8>>);
+/// # impl StorageBackend for Backend {
+/// #     fn get(&self, key: &[u8]) -> Option<Vec<u8>> { self.0.get(key).cloned() }
+/// # }
+/// # impl StorageBackendMut for Backend {
+/// #     fn set(&mut self, key: &[u8], value: &[u8]) { self.0.insert(key.to_vec(), value.to_vec()); }
+/// #     fn remove(&mut self, key: &[u8]) { self.0.remove(key); }
+/// # }
+/// use storey_storage::ObservedStorage;
+///
+/// let mut invalidated = Vec::new();
+/// let mut storage = ObservedStorage::new(Backend::default(), |key: &[u8]| {
+///     invalidated.push(key.to_vec())
+/// });
+///
+/// storage.set(b"foo", b"bar");
+/// storage.remove(b"foo");
+/// assert_eq!(storage.get(b"foo"), None);
+///
+/// drop(storage);
+/// assert_eq!(invalidated, vec![b"foo".to_vec(), b"foo".to_vec()]);
+/// ```
+pub struct ObservedStorage<B, F> {
+    inner: B,
+    observer: F,
+}
+
+impl<B, F> ObservedStorage<B, F>
+where
+    F: FnMut(&[u8]),
+{
+    /// Wrap the given backend, calling `observer` with the key of every write.
+    pub fn new(inner: B, observer: F) -> Self {
+        Self { inner, observer }
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Unwrap the backend, dropping the observer.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B, F> StorageBackend for ObservedStorage<B, F>
+where
+    B: StorageBackend,
+{
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.inner.get(key)
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        self.inner.has(key)
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        self.inner.get_many(keys)
+    }
+}
+
+impl<B, F> StorageBackendMut for ObservedStorage<B, F>
+where
+    B: StorageBackendMut,
+    F: FnMut(&[u8]),
+{
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.inner.set(key, value);
+        (self.observer)(key);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.inner.remove(key);
+        (self.observer)(key);
+    }
+}
+
+impl<B, F> IterableStorage for ObservedStorage<B, F>
+where
+    B: IterableStorage,
+{
+    type KeysIterator<'a> = B::KeysIterator<'a> where Self: 'a;
+    type ValuesIterator<'a> = B::ValuesIterator<'a> where Self: 'a;
+    type PairsIterator<'a> = B::PairsIterator<'a> where Self: 'a;
+
+    fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
+        self.inner.keys(start, end)
+    }
+
+    fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
+        self.inner.values(start, end)
+    }
+
+    fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        self.inner.pairs(start, end)
+    }
+}
+
+impl<B, F> RevIterableStorage for ObservedStorage<B, F>
+where
+    B: RevIterableStorage,
+{
+    type RevKeysIterator<'a> = B::RevKeysIterator<'a> where Self: 'a;
+    type RevValuesIterator<'a> = B::RevValuesIterator<'a> where Self: 'a;
+    type RevPairsIterator<'a> = B::RevPairsIterator<'a> where Self: 'a;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        self.inner.rev_keys(start, end)
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        self.inner.rev_values(start, end)
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        self.inner.rev_pairs(start, end)
+    }
+}
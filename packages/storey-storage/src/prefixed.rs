@@ -0,0 +1,244 @@
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use crate::{IterableStorage, RevIterableStorage, Seekable};
+
+/// A read-only view of the entries of an iterable storage whose keys start with a prefix.
+///
+/// Iteration bounds are relative to the prefix, and the prefix is stripped from the keys
+/// yielded. This is a lightweight alternative to a storage branch for code that only needs to
+/// iterate over a sub-namespace. Views can be narrowed further, since they implement
+/// [`IterableStorage`] themselves.
+///
+/// Created by [`IterableStorage::prefix`].
+pub struct PrefixedView<'a, S: ?Sized> {
+    storage: &'a S,
+    prefix: Vec<u8>,
+}
+
+impl<'a, S: ?Sized> PrefixedView<'a, S> {
+    /// Create a view of the entries of `storage` whose keys start with `prefix`.
+    pub fn new(storage: &'a S, prefix: &[u8]) -> Self {
+        Self {
+            storage,
+            prefix: prefix.to_vec(),
+        }
+    }
+}
+
+impl<S: IterableStorage + ?Sized> IterableStorage for PrefixedView<'_, S> {
+    type KeysIterator<'b> = PrefixedKeysIter<S::KeysIterator<'b>> where Self: 'b;
+    type ValuesIterator<'b> = S::ValuesIterator<'b> where Self: 'b;
+    type PairsIterator<'b> = PrefixedKVIter<S::PairsIterator<'b>> where Self: 'b;
+
+    fn keys<'b>(&'b self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        PrefixedKeysIter {
+            inner: self.storage.keys(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+
+    fn values<'b>(&'b self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        self.storage.values(
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+        )
+    }
+
+    fn pairs<'b>(&'b self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        PrefixedKVIter {
+            inner: self.storage.pairs(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+}
+
+impl<S: RevIterableStorage + ?Sized> RevIterableStorage for PrefixedView<'_, S> {
+    type RevKeysIterator<'b> = PrefixedKeysIter<S::RevKeysIterator<'b>> where Self: 'b;
+    type RevValuesIterator<'b> = S::RevValuesIterator<'b> where Self: 'b;
+    type RevPairsIterator<'b> = PrefixedKVIter<S::RevPairsIterator<'b>> where Self: 'b;
+
+    fn rev_keys<'b>(&'b self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        PrefixedKeysIter {
+            inner: self.storage.rev_keys(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+
+    fn rev_values<'b>(
+        &'b self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        self.storage.rev_values(
+            start.as_ref().map(AsRef::as_ref),
+            end.as_ref().map(AsRef::as_ref),
+        )
+    }
+
+    fn rev_pairs<'b>(
+        &'b self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'b> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        PrefixedKVIter {
+            inner: self.storage.rev_pairs(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+}
+
+/// An iterator over the keys of a [`PrefixedView`].
+pub struct PrefixedKeysIter<I> {
+    inner: I,
+    prefix: Vec<u8>,
+}
+
+impl<I> Iterator for PrefixedKeysIter<I>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|key| key[self.prefix.len()..].to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for PrefixedKeysIter<I>
+where
+    I: DoubleEndedIterator<Item = Vec<u8>>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|key| key[self.prefix.len()..].to_vec())
+    }
+}
+
+impl<I> Seekable for PrefixedKeysIter<I>
+where
+    I: Seekable<Item = Vec<u8>>,
+{
+    fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(&[&self.prefix[..], key].concat())
+    }
+}
+
+/// An iterator over the key-value pairs of a [`PrefixedView`].
+pub struct PrefixedKVIter<I> {
+    inner: I,
+    prefix: Vec<u8>,
+}
+
+impl<I> Iterator for PrefixedKVIter<I>
+where
+    I: Iterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(key, value)| (key[self.prefix.len()..].to_vec(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for PrefixedKVIter<I>
+where
+    I: DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(key, value)| (key[self.prefix.len()..].to_vec(), value))
+    }
+}
+
+impl<I> Seekable for PrefixedKVIter<I>
+where
+    I: Seekable<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(&[&self.prefix[..], key].concat())
+    }
+}
+
+/// Translates bounds relative to `prefix` into absolute ones.
+///
+/// An unbounded end is narrowed to the range of keys starting with `prefix`.
+pub fn sub_bounds(
+    prefix: &[u8],
+    start: Bound<&[u8]>,
+    end: Bound<&[u8]>,
+) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    if prefix.is_empty() {
+        (start.map(|s| s.to_vec()), end.map(|s| s.to_vec()))
+    } else {
+        (
+            // concat prefix and start if bounded
+            // return just the prefix if unbounded
+            if let Bound::Unbounded = start {
+                Bound::Included(prefix.to_vec())
+            } else {
+                start.map(|s| [prefix, s].concat())
+            },
+            // the end of the prefix range if unbounded - there's none if the prefix is all
+            // `0xFF` bytes
+            if let Bound::Unbounded = end {
+                successor(prefix).map_or(Bound::Unbounded, Bound::Excluded)
+            } else {
+                end.map(|e| [prefix, e].concat())
+            },
+        )
+    }
+}
+
+/// Returns the smallest byte string that is greater than every string starting with `prefix`,
+/// or `None` if there's no such string.
+pub fn successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut result = prefix.to_vec();
+
+    while let Some(last) = result.pop() {
+        if last < u8::MAX {
+            result.push(last + 1);
+            return Some(result);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+
+/// An iterator over storage entries that can skip ahead to a given key.
+///
+/// This is useful for skip-scan algorithms, e.g. over composite keys, where large runs
+/// of entries can be jumped over rather than visited one by one.
+///
+/// After `seek(key)`, the iterator yields the remaining entries whose keys are greater than
+/// or equal to `key`. Seeking backwards (to a key lower than the current position) has no
+/// effect - entries that have already been yielded are not revisited.
+///
+/// Backends that can reposition a cursor cheaply should implement this for their iterators.
+/// Any other key or key-value pair iterator can be wrapped in [`ScanSeek`], which seeks by
+/// dropping entries one by one.
+pub trait Seekable: Iterator {
+    /// Advance the iterator so that the next entry yielded is the first one with a key
+    /// greater than or equal to `key`.
+    fn seek(&mut self, key: &[u8]);
+}
+
+/// An item yielded by a storage iterator that carries its key.
+pub trait SeekKey {
+    /// The key of the entry.
+    fn seek_key(&self) -> &[u8];
+}
+
+impl SeekKey for Vec<u8> {
+    fn seek_key(&self) -> &[u8] {
+        self
+    }
+}
+
+impl SeekKey for (Vec<u8>, Vec<u8>) {
+    fn seek_key(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A [`Seekable`] adapter for any iterator over keys or key-value pairs.
+///
+/// Seeking drops entries until one with a key greater than or equal to the target is found.
+/// That entry is held back and yielded next. This costs as much as iterating over the
+/// skipped entries, so prefer a backend's native implementation where available.
+///
+/// # Example
+/// ```
+/// use storey_storage::{ScanSeek, Seekable as _};
+///
+/// let keys = vec![vec![1], vec![2], vec![5], vec![7]];
+/// let mut iter = ScanSeek::new(keys.into_iter());
+///
+/// assert_eq!(iter.next(), Some(vec![1]));
+/// iter.seek(&[3]);
+/// assert_eq!(iter.next(), Some(vec![5]));
+/// assert_eq!(iter.next(), Some(vec![7]));
+/// ```
+pub struct ScanSeek<I: Iterator> {
+    inner: I,
+    peeked: Option<I::Item>,
+}
+
+impl<I: Iterator> ScanSeek<I> {
+    /// Wrap the given iterator.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            peeked: None,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for ScanSeek<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.peeked.take().or_else(|| self.inner.next())
+    }
+}
+
+impl<I> Seekable for ScanSeek<I>
+where
+    I: Iterator,
+    I::Item: SeekKey,
+{
+    fn seek(&mut self, key: &[u8]) {
+        while let Some(item) = self.next() {
+            if item.seek_key() >= key {
+                self.peeked = Some(item);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_seek() {
+        let pairs = vec![
+            (vec![0], vec![10]),
+            (vec![1], vec![11]),
+            (vec![1, 0], vec![12]),
+            (vec![2], vec![13]),
+            (vec![4], vec![14]),
+        ];
+        let mut iter = ScanSeek::new(pairs.into_iter());
+
+        iter.seek(&[1, 0]);
+        assert_eq!(iter.next(), Some((vec![1, 0], vec![12])));
+
+        // seeking twice to the same place, or backwards, is a no-op
+        iter.seek(&[3]);
+        iter.seek(&[3]);
+        iter.seek(&[0]);
+        assert_eq!(iter.next(), Some((vec![4], vec![14])));
+
+        iter.seek(&[5]);
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -1,6 +1,13 @@
-use std::ops::Bound;
+use alloc::vec::Vec;
+use core::hash::Hasher;
+use core::ops::Bound;
+
+use crate::PrefixedView;
 
 /// A read interface for binary key-value storage.
+///
+/// This trait is dyn-compatible, so code holding a `&dyn Storage` can still access
+/// containers through it.
 pub trait Storage {
     /// Get the value of the key.
     fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
@@ -10,6 +17,15 @@ pub trait Storage {
         self.get(key).is_some()
     }
 
+    /// Get the values of several keys at once.
+    ///
+    /// The result holds one entry per key, in the same order as `keys`. The default
+    /// implementation calls [`get`](Self::get) for each key. Storages that can batch point
+    /// lookups should override it.
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
     /// Get the value of the key in the metadata namespace.
     fn get_meta(&self, _key: &[u8]) -> Option<Vec<u8>>;
 
@@ -20,7 +36,10 @@ pub trait Storage {
 }
 
 /// A write interface for binary key-value storage.
-pub trait StorageMut {
+///
+/// Anything writable is also readable, which lets a `&mut dyn StorageMut` be used for
+/// container operations that both read and write.
+pub trait StorageMut: Storage {
     /// Set the value of the key.
     fn set(&mut self, key: &[u8], value: &[u8]);
 
@@ -79,6 +98,36 @@ pub trait IterableStorage {
     /// bounded at all, and if so, whether it should be inclusive or exclusive. See the
     /// [`Bound`] documentation for more details.
     fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a>;
+
+    /// Hash all key-value pairs into `hasher` and return the digest.
+    ///
+    /// Pairs are fed in lexicographical order of keys, each key and value preceded by its
+    /// length as a big-endian `u64`. The digest only depends on the contents of the storage,
+    /// not on the order they were written in or on the backend, which makes it suitable for
+    /// comparing the state of two storages, e.g. the same namespace in two different backends.
+    ///
+    /// Keep in mind the digest is only as stable as `hasher`. For example, the output of
+    /// [`DefaultHasher`](std::collections::hash_map::DefaultHasher) may change between Rust
+    /// releases.
+    fn digest<H: Hasher>(&self, mut hasher: H) -> u64 {
+        for (key, value) in self.pairs(Bound::Unbounded, Bound::Unbounded) {
+            hasher.write(&(key.len() as u64).to_be_bytes());
+            hasher.write(&key);
+            hasher.write(&(value.len() as u64).to_be_bytes());
+            hasher.write(&value);
+        }
+
+        hasher.finish()
+    }
+
+    /// Get a view of the entries whose keys start with `prefix`.
+    ///
+    /// Iterating the view walks only those entries, with the prefix stripped from the keys.
+    /// Bounds passed to the view are relative to the prefix. This is cheaper than setting up
+    /// a full storage branch when all that's needed is iteration over a sub-namespace.
+    fn prefix<'a>(&'a self, prefix: &[u8]) -> PrefixedView<'a, Self> {
+        PrefixedView::new(self, prefix)
+    }
 }
 
 impl<T: IterableStorage> IterableStorage for &T {
@@ -171,3 +220,26 @@ pub trait RevIterableStorage {
         end: Bound<&[u8]>,
     ) -> Self::RevPairsIterator<'a>;
 }
+
+/// Iteration interface for the metadata namespace of binary key-value storage.
+///
+/// This is mostly useful for wiping a namespace completely, metadata included.
+pub trait IterableMetaStorage {
+    /// The type of the iterator returned by [`meta_keys`](Self::meta_keys).
+    type MetaKeysIterator<'a>: Iterator<Item = Vec<u8>>
+    where
+        Self: 'a;
+
+    /// Get an iterator over keys in the metadata namespace.
+    ///
+    /// The iterator walks keys in lexicographical order.
+    ///
+    /// The [`Bound`] type is used to specify either end of the range - whether it should be
+    /// bounded at all, and if so, whether it should be inclusive or exclusive. See the
+    /// [`Bound`] documentation for more details.
+    fn meta_keys<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::MetaKeysIterator<'a>;
+}
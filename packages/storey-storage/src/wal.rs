@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read as _, Write as _};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use super::backend::{StorageBackend, StorageBackendMut};
+use super::storage::{IterableStorage, RevIterableStorage};
+
+const TAG_SET: u8 = 0;
+const TAG_REMOVE: u8 = 1;
+
+/// A storage backend backed by a write-ahead log on disk.
+///
+/// Every `set` and `remove` is applied to an in-memory index and recorded in a log.
+/// Recorded operations are only written to disk when [`flush`](Self::flush) is called.
+/// On startup, [`open`](Self::open) replays the log to restore the state.
+///
+/// A record that was only partially written (e.g. because the process crashed mid-flush)
+/// is discarded on replay, along with anything after it.
+///
+/// This is meant for off-chain use, where storey is used to drive a simple persistent store.
+///
+/// # Example
+/// ```
+/// use storey_storage::{StorageBackend as _, StorageBackendMut as _, WalStorage};
+///
+/// let path = std::env::temp_dir().join("storey-wal-doctest.log");
+/// # let _ = std::fs::remove_file(&path);
+///
+/// let mut storage = WalStorage::open(&path).unwrap();
+/// storage.set(b"foo", b"bar");
+/// storage.flush().unwrap();
+/// drop(storage);
+///
+/// let storage = WalStorage::open(&path).unwrap();
+/// assert_eq!(storage.get(b"foo"), Some(b"bar".to_vec()));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct WalStorage {
+    path: PathBuf,
+    index: BTreeMap<Vec<u8>, Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl WalStorage {
+    /// Open the log at the given path, creating it if it doesn't exist, and replay it.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut storage = Self {
+            path: path.as_ref().to_path_buf(),
+            index: BTreeMap::new(),
+            pending: Vec::new(),
+        };
+
+        storage.replay()?;
+
+        Ok(storage)
+    }
+
+    /// Rebuild the in-memory state from the log on disk.
+    ///
+    /// Any operations that haven't been flushed yet are discarded.
+    pub fn replay(&mut self) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+
+        let mut log = Vec::new();
+        file.read_to_end(&mut log)?;
+
+        let mut index = BTreeMap::new();
+        let mut cursor = &log[..];
+        let mut valid_len = 0;
+
+        while let Some((tag, key, value)) = read_record(&mut cursor) {
+            match (tag, value) {
+                (TAG_SET, Some(value)) => {
+                    index.insert(key.to_vec(), value.to_vec());
+                }
+                (TAG_REMOVE, None) => {
+                    index.remove(key);
+                }
+                _ => break,
+            }
+            valid_len = log.len() - cursor.len();
+        }
+
+        // Drop a torn record at the end of the log so that new records are appended
+        // after the last valid one.
+        if valid_len < log.len() {
+            file.set_len(valid_len as u64)?;
+            file.sync_all()?;
+        }
+
+        self.index = index;
+        self.pending.clear();
+
+        Ok(())
+    }
+
+    /// Append all pending operations to the log on disk and sync it.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut file: File = OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(&self.pending)?;
+        file.sync_data()?;
+
+        self.pending.clear();
+
+        Ok(())
+    }
+
+    fn record(&mut self, tag: u8, key: &[u8], value: Option<&[u8]>) {
+        self.pending.push(tag);
+        write_chunk(&mut self.pending, key);
+        if let Some(value) = value {
+            write_chunk(&mut self.pending, value);
+        }
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Box<dyn DoubleEndedIterator<Item = (&'a Vec<u8>, &'a Vec<u8>)> + 'a> {
+        // `BTreeMap::range` panics on an empty or inverted range, so check for that first.
+        let empty = match (start, end) {
+            (Bound::Included(s), Bound::Included(e)) => s > e,
+            (Bound::Included(s), Bound::Excluded(e))
+            | (Bound::Excluded(s), Bound::Included(e))
+            | (Bound::Excluded(s), Bound::Excluded(e)) => s >= e,
+            _ => false,
+        };
+
+        if empty {
+            Box::new(std::iter::empty())
+        } else {
+            Box::new(self.index.range::<[u8], _>((start, end)))
+        }
+    }
+}
+
+fn write_chunk(buf: &mut Vec<u8>, chunk: &[u8]) {
+    let len = u32::try_from(chunk.len()).expect("key or value too long for the log");
+    buf.extend_from_slice(&len.to_be_bytes());
+    buf.extend_from_slice(chunk);
+}
+
+fn read_chunk<'a>(cursor: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len_bytes: [u8; 4] = cursor.get(..4)?.try_into().ok()?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    let chunk = cursor.get(4..4 + len)?;
+    *cursor = &cursor[4 + len..];
+
+    Some(chunk)
+}
+
+#[allow(clippy::type_complexity)]
+fn read_record<'a>(cursor: &mut &'a [u8]) -> Option<(u8, &'a [u8], Option<&'a [u8]>)> {
+    let mut rest = *cursor;
+
+    let (&tag, tail) = rest.split_first()?;
+    rest = tail;
+
+    let key = read_chunk(&mut rest)?;
+    let value = match tag {
+        TAG_SET => Some(read_chunk(&mut rest)?),
+        _ => None,
+    };
+
+    *cursor = rest;
+
+    Some((tag, key, value))
+}
+
+impl StorageBackend for WalStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.index.get(key).cloned()
+    }
+}
+
+impl StorageBackendMut for WalStorage {
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.record(TAG_SET, key, Some(value));
+        self.index.insert(key.to_vec(), value.to_vec());
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.record(TAG_REMOVE, key, None);
+        self.index.remove(key);
+    }
+}
+
+impl IterableStorage for WalStorage {
+    type KeysIterator<'a> = Box<dyn DoubleEndedIterator<Item = Vec<u8>> + 'a>;
+    type ValuesIterator<'a> = Box<dyn DoubleEndedIterator<Item = Vec<u8>> + 'a>;
+    type PairsIterator<'a> = Box<dyn DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
+    fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
+        Box::new(self.range(start, end).map(|(k, _)| k.clone()))
+    }
+
+    fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
+        Box::new(self.range(start, end).map(|(_, v)| v.clone()))
+    }
+
+    fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        Box::new(self.range(start, end).map(|(k, v)| (k.clone(), v.clone())))
+    }
+}
+
+impl RevIterableStorage for WalStorage {
+    type RevKeysIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a>;
+    type RevValuesIterator<'a> = Box<dyn Iterator<Item = Vec<u8>> + 'a>;
+    type RevPairsIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        Box::new(self.keys(start, end).rev())
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        Box::new(self.values(start, end).rev())
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        Box::new(self.pairs(start, end).rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempLog(PathBuf);
+
+    impl TempLog {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "storey-wal-{}-{}.log",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn replay_restores_state() {
+        let log = TempLog::new("replay");
+
+        let mut storage = WalStorage::open(&log.0).unwrap();
+        storage.set(&[0], b"foo");
+        storage.set(&[1], b"bar");
+        storage.set(&[2], b"baz");
+        storage.remove(&[1]);
+        storage.set(&[0], b"qux");
+        storage.flush().unwrap();
+        drop(storage);
+
+        let storage = WalStorage::open(&log.0).unwrap();
+        let pairs: Vec<_> = storage.pairs(Bound::Unbounded, Bound::Unbounded).collect();
+        assert_eq!(
+            pairs,
+            vec![(vec![0], b"qux".to_vec()), (vec![2], b"baz".to_vec())]
+        );
+    }
+
+    #[test]
+    fn unflushed_writes_are_lost() {
+        let log = TempLog::new("unflushed");
+
+        let mut storage = WalStorage::open(&log.0).unwrap();
+        storage.set(&[0], b"foo");
+        storage.flush().unwrap();
+        storage.set(&[1], b"bar");
+        assert_eq!(storage.get(&[1]), Some(b"bar".to_vec()));
+
+        storage.replay().unwrap();
+        assert_eq!(storage.get(&[0]), Some(b"foo".to_vec()));
+        assert_eq!(storage.get(&[1]), None);
+    }
+
+    #[test]
+    fn torn_record_is_discarded() {
+        let log = TempLog::new("torn");
+
+        let mut storage = WalStorage::open(&log.0).unwrap();
+        storage.set(&[0], b"foo");
+        storage.set(&[1], b"bar");
+        storage.flush().unwrap();
+        drop(storage);
+
+        // simulate a crash in the middle of writing the last record
+        let len = std::fs::metadata(&log.0).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&log.0).unwrap();
+        file.set_len(len - 2).unwrap();
+        drop(file);
+
+        let mut storage = WalStorage::open(&log.0).unwrap();
+        assert_eq!(storage.get(&[0]), Some(b"foo".to_vec()));
+        assert_eq!(storage.get(&[1]), None);
+
+        // new records are appended after the last valid one
+        storage.set(&[2], b"baz");
+        storage.flush().unwrap();
+        drop(storage);
+
+        let storage = WalStorage::open(&log.0).unwrap();
+        let keys: Vec<_> = storage.keys(Bound::Unbounded, Bound::Unbounded).collect();
+        assert_eq!(keys, vec![vec![0], vec![2]]);
+    }
+
+    #[test]
+    fn empty_range() {
+        let log = TempLog::new("range");
+
+        let mut storage = WalStorage::open(&log.0).unwrap();
+        storage.set(&[1], b"foo");
+
+        let keys: Vec<_> = storage
+            .keys(Bound::Excluded(&[1]), Bound::Excluded(&[1]))
+            .collect();
+        assert!(keys.is_empty());
+
+        let keys: Vec<_> = storage
+            .rev_keys(Bound::Included(&[2]), Bound::Included(&[1]))
+            .collect();
+        assert!(keys.is_empty());
+    }
+}
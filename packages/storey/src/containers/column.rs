@@ -1,17 +1,21 @@
-use std::marker::PhantomData;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::{marker::PhantomData, ops::Bound};
 
 use thiserror::Error;
 
 use crate::encoding::Encoding;
 use crate::encoding::{DecodableWith, EncodableWith};
-use crate::storage::{IterableStorage, StorageBranch};
+use crate::storage::{IterableStorage, RevIterableStorage, StorageBranch, StorageRef};
 use crate::storage::{Storage, StorageMut};
 
-use super::common::TryGetError;
-use super::{BoundFor, BoundedIterableAccessor, IterableAccessor, NonTerminal, Storable};
-
-/// The first (lowest) ID that is pushed to the column.
-const FIRST_ID: u32 = 1;
+use super::common::{
+    check_layout_version, record_layout_version, remove_range, Layout, LayoutError, TryGetError,
+};
+use super::position_index::{IndexError, PositionIndex};
+use super::{
+    BoundFor, BoundedIterableAccessor, IterableAccessor, NonTerminal, RefTarget, Storable,
+};
 
 /// Storage keys for metadata.
 mod meta_keys {
@@ -20,13 +24,29 @@ mod meta_keys {
     /// not reset in case the last element is removed.
     pub const META_LAST_ID: &[u8] = &[0];
     pub const META_LEN: &[u8] = &[1];
+    /// The version of the metadata layout the column was written with.
+    pub const META_VERSION: &[u8] = &[2];
+    /// The depth of the positional index.
+    pub const META_INDEX_DEPTH: &[u8] = &[3];
+    /// The prefix for the nodes of the positional index.
+    pub const META_INDEX_NODES: &[u8] = &[4];
+    /// A copy of the element with the highest ID, prefixed with that ID. Only kept up to date
+    /// by columns with the latest value cache enabled.
+    pub const META_LATEST: &[u8] = &[5];
 }
 
-/// A collection of rows indexed by `u32` keys. This is somewhat similar to a traditional
+/// The index of live IDs, used to look up elements by position.
+const POSITION_INDEX: PositionIndex = PositionIndex {
+    depth_key: meta_keys::META_INDEX_DEPTH,
+    node_prefix: meta_keys::META_INDEX_NODES,
+};
+
+/// A collection of rows indexed by integer keys. This is somewhat similar to a traditional
 /// database table with an auto-incrementing primary key. We often call column keys "IDs"
 /// to differentiate them from other entities.
 ///
-/// The ID is currently encoded as a big-endian `u32` integer.
+/// The ID type `I` is `u32` by default. Long-lived append-only logs can use `u64` instead.
+/// Either way, the ID is encoded as a big-endian integer.
 ///
 /// # Example
 /// ```
@@ -45,15 +65,17 @@ mod meta_keys {
 /// assert_eq!(access.get(2).unwrap(), Some(42));
 /// assert_eq!(access.get(3).unwrap(), None);
 /// ```
-pub struct Column<T, E> {
+pub struct Column<T, E, I = u32> {
     prefix: u8,
-    phantom: PhantomData<(T, E)>,
+    cache_latest: bool,
+    phantom: PhantomData<(T, E, I)>,
 }
 
-impl<T, E> Column<T, E>
+impl<T, E, I> Column<T, E, I>
 where
     E: Encoding,
     T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
 {
     /// Create a new column associated with the given storage prefix.
     ///
@@ -64,6 +86,47 @@ where
     pub const fn new(prefix: u8) -> Self {
         Self {
             prefix,
+            cache_latest: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Enable the latest value cache for this column.
+    ///
+    /// With the cache enabled, a copy of the element with the highest ID is kept in the
+    /// metadata namespace, so [`latest`](ColumnAccess::latest) can read it directly. Every
+    /// write has to keep the copy up to date, which costs an extra metadata write per
+    /// [`push`](ColumnAccess::push) and an extra read per [`set`](ColumnAccess::set). This
+    /// pays off for things like append-only config histories where the newest entry is read
+    /// far more often than the column is written to.
+    ///
+    /// The cache is only maintained by accessors acquired through this definition. Don't
+    /// write to the same column through a definition without the cache enabled (or as a
+    /// nested container, which never maintains it), or [`latest`](ColumnAccess::latest)
+    /// might return a stale value.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// const HISTORY: Column<u64, TestEncoding> = Column::new(0).with_latest_cache();
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let mut access = HISTORY.access(&mut storage);
+    ///
+    /// access.push(&1).unwrap();
+    /// access.push(&2).unwrap();
+    /// assert_eq!(access.latest().unwrap(), Some(2));
+    ///
+    /// access.remove(2).unwrap();
+    /// assert_eq!(access.latest().unwrap(), Some(1));
+    /// ```
+    pub const fn with_latest_cache(self) -> Self {
+        Self {
+            prefix: self.prefix,
+            cache_latest: true,
             phantom: PhantomData,
         }
     }
@@ -86,26 +149,107 @@ where
     /// let column = Column::<u64, TestEncoding>::new(0);
     /// let mut access = column.access(&mut storage);
     /// ```
-    pub fn access<S>(&self, storage: S) -> ColumnAccess<E, T, StorageBranch<S>> {
-        Self::access_impl(StorageBranch::new(storage, vec![self.prefix]))
+    pub fn access<S: StorageRef>(&self, storage: S) -> ColumnAccess<E, T, StorageBranch<S>, I> {
+        self.access_branch(StorageBranch::new(storage, vec![self.prefix]))
+    }
+
+    /// Acquire a read-only accessor to the column.
+    ///
+    /// This is [`access`](Self::access) restricted to a shared reference. Calling a
+    /// mutating method on the result is a compile error pointing at that method, and
+    /// passing anything but a reference is caught at the call site.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    ///
+    /// let storage = TestStorage::new();
+    /// let access = column.access_ref(&storage);
+    ///
+    /// assert_eq!(access.len().unwrap(), 0);
+    /// ```
+    pub fn access_ref<'s, S>(&self, storage: &'s S) -> ColumnAccess<E, T, StorageBranch<&'s S>, I> {
+        self.access(storage)
+    }
+
+    /// Acquire a mutable accessor to the column.
+    ///
+    /// This is [`access`](Self::access) restricted to an exclusive reference.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let mut access = column.access_mut(&mut storage);
+    ///
+    /// access.push(&42).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(42));
+    /// ```
+    pub fn access_mut<'s, S>(
+        &self,
+        storage: &'s mut S,
+    ) -> ColumnAccess<E, T, StorageBranch<&'s mut S>, I> {
+        self.access(storage)
+    }
+
+    /// Acquire an accessor for this column from an already constructed storage branch.
+    ///
+    /// The branch is used as the column's namespace as-is. The column's own prefix is not
+    /// applied, so it's up to the caller to make sure the branch points at the right place.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    /// use storey::storage::StorageBranch;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    ///
+    /// let branch = StorageBranch::new(&mut storage, vec![0]);
+    /// column.access_branch(branch).push(&1337).unwrap();
+    ///
+    /// assert_eq!(column.access(&storage).get(1).unwrap(), Some(1337));
+    /// ```
+    pub fn access_branch<S>(
+        &self,
+        branch: StorageBranch<S>,
+    ) -> ColumnAccess<E, T, StorageBranch<S>, I> {
+        ColumnAccess {
+            storage: branch,
+            cache_latest: self.cache_latest,
+            phantom: PhantomData,
+        }
     }
 }
 
-impl<T, E> Storable for Column<T, E>
+impl<T, E, I> Storable for Column<T, E, I>
 where
     E: Encoding,
     T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
 {
     type Kind = NonTerminal;
-    type Accessor<S> = ColumnAccess<E, T, S>;
-    type Key = u32;
+    type Accessor<S> = ColumnAccess<E, T, S, I>;
+    type Key = I;
     type KeyDecodeError = ColumnIdDecodeError;
     type Value = T;
     type ValueDecodeError = E::DecodeError;
 
-    fn access_impl<S>(storage: S) -> ColumnAccess<E, T, S> {
+    fn access_impl<S>(storage: S) -> ColumnAccess<E, T, S, I> {
         ColumnAccess {
             storage,
+            cache_latest: false,
             phantom: PhantomData,
         }
     }
@@ -122,24 +266,98 @@ where
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
-#[error("invalid key length, expected 4 bytes of big-endian u32")]
+#[error("invalid key length for a big-endian column ID")]
 pub struct ColumnIdDecodeError;
 
+/// An unsigned integer type that can be used for [`Column`] IDs.
+///
+/// This is implemented for `u32` (the default) and `u64`. IDs are encoded as big-endian
+/// integers, so that the storage order matches the numeric order.
+///
+/// The positional index kept by the column covers IDs up to `2^63`. Pushing past that
+/// fails with an inconsistent state error.
+pub trait ColumnId: Copy + Eq + sealed::ColumnIdSeal {}
+
+impl ColumnId for u32 {}
+impl ColumnId for u64 {}
+
+mod sealed {
+    use alloc::vec::Vec;
+
+    pub trait ColumnIdSeal: Sized {
+        /// The first (lowest) ID that is pushed to a column.
+        const FIRST: Self;
+
+        fn to_be_vec(self) -> Vec<u8>;
+        fn from_be_slice(bytes: &[u8]) -> Option<Self>;
+        fn checked_next(self) -> Option<Self>;
+        fn to_u64(self) -> u64;
+        fn from_u64(id: u64) -> Option<Self>;
+    }
+
+    macro_rules! impl_column_id_seal {
+        ($($t:ty),*) => {
+            $(
+                impl ColumnIdSeal for $t {
+                    const FIRST: Self = 1;
+
+                    fn to_be_vec(self) -> Vec<u8> {
+                        self.to_be_bytes().to_vec()
+                    }
+
+                    fn from_be_slice(bytes: &[u8]) -> Option<Self> {
+                        bytes.try_into().ok().map(<$t>::from_be_bytes)
+                    }
+
+                    fn checked_next(self) -> Option<Self> {
+                        self.checked_add(1)
+                    }
+
+                    fn to_u64(self) -> u64 {
+                        self as u64
+                    }
+
+                    fn from_u64(id: u64) -> Option<Self> {
+                        id.try_into().ok()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_column_id_seal!(u32, u64);
+}
+
 /// An accessor for a `Column`.
 ///
 /// This type provides methods for interacting with the column in storage.
-pub struct ColumnAccess<E, T, S> {
+pub struct ColumnAccess<E, T, S, I = u32> {
     storage: S,
-    phantom: PhantomData<(E, T)>,
+    cache_latest: bool,
+    phantom: PhantomData<(E, T, I)>,
+}
+
+impl<E, T, S, I> Clone for ColumnAccess<E, T, S, I>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            cache_latest: self.cache_latest,
+            phantom: PhantomData,
+        }
+    }
 }
 
-impl<E, T, S> IterableAccessor for ColumnAccess<E, T, S>
+impl<E, T, S, I> IterableAccessor for ColumnAccess<E, T, S, I>
 where
     E: Encoding,
     T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
     S: IterableStorage,
 {
-    type Storable = Column<T, E>;
+    type Storable = Column<T, E, I>;
     type Storage = S;
 
     fn storage(&self) -> &Self::Storage {
@@ -147,24 +365,44 @@ where
     }
 }
 
-impl<E, T, S> BoundedIterableAccessor for ColumnAccess<E, T, S>
+impl<E, T, S, I> BoundedIterableAccessor for ColumnAccess<E, T, S, I>
 where
     E: Encoding,
     T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
     S: IterableStorage,
 {
 }
 
-impl<T, E> BoundFor<Column<T, E>> for u32 {
+impl<T, E, I: ColumnId> BoundFor<Column<T, E, I>> for I {
     fn into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
+        encode_id(self)
+    }
+}
+
+impl<T, E, I> RefTarget for Column<T, E, I>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
+{
+    type Id = I;
+    type Target = T;
+    type ResolveError = E::DecodeError;
+
+    fn resolve_id<S: Storage>(
+        access: &ColumnAccess<E, T, S, I>,
+        id: &I,
+    ) -> Result<Option<T>, E::DecodeError> {
+        access.get(*id)
     }
 }
 
-impl<E, T, S> ColumnAccess<E, T, S>
+impl<E, T, S, I> ColumnAccess<E, T, S, I>
 where
     E: Encoding,
     T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
     S: Storage,
 {
     /// Get the value associated with the given ID.
@@ -185,7 +423,7 @@ where
     /// assert_eq!(access.get(1).unwrap(), Some(1337));
     /// assert_eq!(access.get(2).unwrap(), None);
     /// ```
-    pub fn get(&self, id: u32) -> Result<Option<T>, E::DecodeError> {
+    pub fn get(&self, id: I) -> Result<Option<T>, E::DecodeError> {
         self.storage
             .get(&encode_id(id))
             .map(|bytes| T::decode(&bytes))
@@ -215,7 +453,7 @@ where
     /// assert_eq!(access.try_get(1).unwrap(), 1337);
     /// assert!(access.try_get(2).is_err());
     /// ```
-    pub fn try_get(&self, id: u32) -> Result<T, TryGetError<E::DecodeError>> {
+    pub fn try_get(&self, id: I) -> Result<T, TryGetError<E::DecodeError>> {
         self.get(id)?.ok_or(TryGetError::Empty)
     }
 
@@ -237,10 +475,32 @@ where
     /// access.push(&1337).unwrap();
     /// assert_eq!(access.get_or(1, 42).unwrap(), 1337);
     /// ```
-    pub fn get_or(&self, id: u32, default: T) -> Result<T, E::DecodeError> {
+    pub fn get_or(&self, id: I, default: T) -> Result<T, E::DecodeError> {
         self.get(id).map(|value| value.unwrap_or(default))
     }
 
+    /// Check if there's a value stored under the given ID.
+    ///
+    /// This doesn't fetch or decode the value.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1337).unwrap();
+    /// assert!(access.contains(1));
+    /// assert!(!access.contains(2));
+    /// ```
+    pub fn contains(&self, id: I) -> bool {
+        self.storage.has(&encode_id(id))
+    }
+
     /// Get the length of the column. This is the number of elements actually stored,
     /// taking the possibility of removed elements into account.
     ///
@@ -261,18 +521,9 @@ where
     /// assert_eq!(access.len().unwrap(), 1);
     /// ```
     pub fn len(&self) -> Result<u32, LenError> {
-        // TODO: bounds check + error handlinge
+        self.layout()?;
 
-        self.storage
-            .get_meta(meta_keys::META_LEN)
-            .map(|bytes| {
-                if bytes.len() != 4 {
-                    Err(LenError::InconsistentState)
-                } else {
-                    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-                }
-            })
-            .unwrap_or(Ok(0))
+        read_len(&self.storage)
     }
 
     /// Check if the column is empty.
@@ -296,32 +547,17 @@ where
     pub fn is_empty(&self) -> Result<bool, LenError> {
         self.len().map(|len| len == 0)
     }
-}
-
-fn decode_id(id: &[u8]) -> Result<u32, ColumnIdDecodeError> {
-    if id.len() != 4 {
-        return Err(ColumnIdDecodeError);
-    }
-
-    let row_key = u32::from_be_bytes([id[0], id[1], id[2], id[3]]);
-
-    Ok(row_key)
-}
-
-fn encode_id(id: u32) -> [u8; 4] {
-    id.to_be_bytes()
-}
 
-impl<E, T, S> ColumnAccess<E, T, S>
-where
-    E: Encoding,
-    T: EncodableWith<E> + DecodableWith<E>,
-    S: StorageMut + Storage,
-{
-    /// Append a new value to the end of the column.
+    /// Get the value at the given (zero-based) position among the elements currently
+    /// stored, in ascending ID order.
     ///
-    /// Returns the ID of the newly inserted value. If the column is empty, the first
-    /// ID will be `1`.
+    /// Removed elements don't count, so this is not the same as looking up by ID.
+    /// Returns `Ok(None)` if the position is out of bounds.
+    ///
+    /// The column maintains a positional index in its metadata, so this takes
+    /// `O(log(last_id))` storage reads rather than a scan. Columns written before the
+    /// layout was versioned don't have the index until their next write. Until then, this
+    /// falls back to probing IDs in ascending order.
     ///
     /// # Example
     /// ```
@@ -329,43 +565,45 @@ where
     /// # use mocks::backend::TestStorage;
     /// use storey::containers::Column;
     ///
-    /// const COLUMN_KEY: u8 = 0;
-    ///
     /// let mut storage = TestStorage::new();
-    /// let column = Column::<u64, TestEncoding>::new(COLUMN_KEY);
+    /// let column = Column::<u64, TestEncoding>::new(0);
     /// let mut access = column.access(&mut storage);
     ///
-    /// assert_eq!(access.push(&1337).unwrap(), 1);
-    /// assert_eq!(access.push(&42).unwrap(), 2);
+    /// access.push(&1337).unwrap();
+    /// access.push(&42).unwrap();
+    /// access.push(&9001).unwrap();
+    /// access.remove(2).unwrap();
+    ///
+    /// assert_eq!(access.get_by_position(0).unwrap(), Some(1337));
+    /// assert_eq!(access.get_by_position(1).unwrap(), Some(9001));
+    /// assert_eq!(access.get_by_position(2).unwrap(), None);
     /// ```
-    pub fn push(&mut self, value: &T) -> Result<u32, PushError<E::EncodeError>> {
-        let bytes = value.encode()?;
-
-        let id = match self
-            .storage
-            .get_meta(meta_keys::META_LAST_ID)
-            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-        {
-            Some(last_id) => last_id.checked_add(1).ok_or(PushError::IdOverflow)?,
-            None => FIRST_ID,
+    pub fn get_by_position(
+        &self,
+        position: u32,
+    ) -> Result<Option<T>, PositionError<E::DecodeError>> {
+        let id = match self.layout().map_err(PositionError::from_layout)? {
+            Layout::Legacy => self
+                .probe_ids()
+                .map_err(|_| PositionError::InconsistentState)?
+                .nth(position as usize),
+            _ => POSITION_INDEX
+                .select(&self.storage, position)
+                .map_err(|_| PositionError::InconsistentState)?
+                .map(|id| I::from_u64(id).ok_or(PositionError::InconsistentState))
+                .transpose()?,
         };
 
-        self.storage.set(&encode_id(id), &bytes);
-
-        self.storage
-            .set_meta(meta_keys::META_LAST_ID, &(id).to_be_bytes());
-        let len = self
-            .storage
-            .get_meta(meta_keys::META_LEN)
-            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-            .unwrap_or(0);
-        self.storage
-            .set_meta(meta_keys::META_LEN, &(len + 1).to_be_bytes());
-
-        Ok(id)
+        match id {
+            Some(id) => self
+                .get(id)?
+                .map(Some)
+                .ok_or(PositionError::InconsistentState),
+            None => Ok(None),
+        }
     }
 
-    /// Set the value associated with the given ID.
+    /// Get the element with the lowest ID currently stored.
     ///
     /// # Example
     /// ```
@@ -377,26 +615,69 @@ where
     /// let column = Column::<u64, TestEncoding>::new(0);
     /// let mut access = column.access(&mut storage);
     ///
+    /// assert_eq!(access.first().unwrap(), None);
+    ///
     /// access.push(&1337).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    /// access.push(&42).unwrap();
+    /// access.remove(1).unwrap();
     ///
-    /// access.set(1, &9001).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), Some(9001));
+    /// assert_eq!(access.first().unwrap(), Some(42));
     /// ```
-    pub fn set(&mut self, id: u32, value: &T) -> Result<(), SetError<E::EncodeError>> {
-        self.storage.get(&encode_id(id)).ok_or(SetError::NotFound)?;
+    pub fn first(&self) -> Result<Option<T>, PositionError<E::DecodeError>> {
+        self.get_by_position(0)
+    }
 
-        let bytes = value.encode()?;
+    /// Get the element with the highest ID currently stored.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// assert_eq!(access.last().unwrap(), None);
+    ///
+    /// access.push(&1337).unwrap();
+    /// access.push(&42).unwrap();
+    /// access.remove(2).unwrap();
+    ///
+    /// assert_eq!(access.last().unwrap(), Some(1337));
+    /// ```
+    pub fn last(&self) -> Result<Option<T>, PositionError<E::DecodeError>> {
+        if self.layout().map_err(PositionError::from_layout)? == Layout::Legacy {
+            let id = self
+                .probe_ids()
+                .map_err(|_| PositionError::InconsistentState)?
+                .next_back();
+
+            return match id {
+                Some(id) => self
+                    .get(id)?
+                    .map(Some)
+                    .ok_or(PositionError::InconsistentState),
+                None => Ok(None),
+            };
+        }
 
-        self.storage.set(&encode_id(id), &bytes);
+        let count = POSITION_INDEX
+            .count(&self.storage)
+            .map_err(|_| PositionError::InconsistentState)?;
 
-        Ok(())
+        match count.checked_sub(1) {
+            Some(position) => self.get_by_position(position),
+            None => Ok(None),
+        }
     }
 
-    /// Update the value associated with the given ID by applying a function to it.
+    /// Get the element with the highest ID currently stored.
     ///
-    /// The provided function is called with the current value, if it exists, and should return the
-    /// new value. If the function returns `None`, the value is removed.
+    /// If the column has the latest value cache enabled (see
+    /// [`Column::with_latest_cache`]), this is a single metadata read. Otherwise, or if
+    /// the cache hasn't been populated yet, this falls back to [`last`](Self::last).
     ///
     /// # Example
     /// ```
@@ -405,35 +686,32 @@ where
     /// use storey::containers::Column;
     ///
     /// let mut storage = TestStorage::new();
-    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let column = Column::<u64, TestEncoding>::new(0).with_latest_cache();
     /// let mut access = column.access(&mut storage);
     ///
+    /// assert_eq!(access.latest().unwrap(), None);
+    ///
     /// access.push(&1337).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    /// access.push(&42).unwrap();
     ///
-    /// access.update(1, |value| value.map(|v| v + 1)).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), Some(1338));
+    /// assert_eq!(access.latest().unwrap(), Some(42));
     /// ```
-    pub fn update<F>(
-        &mut self,
-        id: u32,
-        f: F,
-    ) -> Result<(), UpdateError<E::DecodeError, E::EncodeError>>
-    where
-        F: FnOnce(Option<T>) -> Option<T>,
-    {
-        let new_value = f(self.get(id).map_err(UpdateError::Decode)?);
-        match new_value {
-            Some(value) => self.set(id, &value).map_err(UpdateError::Set),
-            None => self
-                .remove(id)
-                .map_err(|_| UpdateError::Set(SetError::NotFound)),
+    pub fn latest(&self) -> Result<Option<T>, PositionError<E::DecodeError>> {
+        if self.cache_latest {
+            if let Some(bytes) = self.storage.get_meta(meta_keys::META_LATEST) {
+                let (_, value) =
+                    split_latest::<I>(&bytes).ok_or(PositionError::InconsistentState)?;
+                return Ok(Some(T::decode(value)?));
+            }
         }
+
+        self.last()
     }
 
-    /// Remove the value associated with the given ID.
+    /// Get the ID the next [`push`](Self::push) will assign.
     ///
-    /// This operation leaves behind an empty slot in the column. The ID is not reused.
+    /// That's one past the highest ID ever assigned, or `1` for a column that was never
+    /// pushed to. Removing elements doesn't make their IDs available again.
     ///
     /// # Example
     /// ```
@@ -445,78 +723,1055 @@ where
     /// let column = Column::<u64, TestEncoding>::new(0);
     /// let mut access = column.access(&mut storage);
     ///
+    /// assert_eq!(access.next_id().unwrap(), 1);
+    ///
     /// access.push(&1337).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    /// access.push(&42).unwrap();
+    /// access.remove(2).unwrap();
     ///
-    /// access.remove(1).unwrap();
-    /// assert_eq!(access.get(1).unwrap(), None);
+    /// assert_eq!(access.next_id().unwrap(), 3);
     /// ```
-    pub fn remove(&mut self, id: u32) -> Result<(), RemoveError> {
-        self.storage.remove(&encode_id(id));
+    pub fn next_id(&self) -> Result<I, NextIdError> {
+        self.layout()?;
 
-        let len = self
-            .storage
-            .get_meta(meta_keys::META_LEN)
-            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-            .ok_or(RemoveError::InconsistentState)?;
-        self.storage
-            .set_meta(meta_keys::META_LEN, &(len - 1).to_be_bytes());
-
-        Ok(())
+        match read_last_id::<I, _>(&self.storage).map_err(|_| NextIdError::InconsistentState)? {
+            Some(id) => id.checked_next().ok_or(NextIdError::IdOverflow),
+            None => Ok(I::FIRST),
+        }
     }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
-pub enum PushError<E> {
-    #[error("ID overflow")]
-    IdOverflow,
-    #[error("{0}")]
-    EncodingError(E),
-}
 
-impl<E> From<E> for PushError<E> {
-    fn from(e: E) -> Self {
-        PushError::EncodingError(e)
+    /// Check the layout version the column was written with.
+    fn layout(&self) -> Result<Layout, LayoutError> {
+        check_layout_version(&self.storage, meta_keys::META_VERSION, meta_keys::META_LEN)
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
-pub enum SetError<E> {
-    #[error("not found")]
-    NotFound,
-    #[error("{0}")]
-    EncodingError(E),
-}
+    /// Iterate over the IDs of the stored elements by probing every ID ever assigned.
+    ///
+    /// This is how columns without a positional index (see [`Layout::Legacy`]) are read.
+    fn probe_ids(&self) -> Result<impl DoubleEndedIterator<Item = I> + '_, LenError> {
+        let last_id = read_last_id::<I, _>(&self.storage)?.map_or(0, I::to_u64);
 
-impl<E> From<E> for SetError<E> {
-    fn from(e: E) -> Self {
-        SetError::EncodingError(e)
+        Ok((I::FIRST.to_u64()..=last_id)
+            .filter_map(I::from_u64)
+            .filter(|&id| self.storage.has(&encode_id(id))))
     }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
-pub enum UpdateError<D, E> {
-    #[error("decode error: {0}")]
-    Decode(D),
-    #[error("set error: {0}")]
-    Set(SetError<E>),
-}
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
-pub enum RemoveError {
-    #[error("inconsistent state")]
-    InconsistentState,
+    /// Returns the ID of the element in the latest value cache, if there is one.
+    fn cached_latest_id(&self) -> Option<I> {
+        let bytes = self.storage.get_meta(meta_keys::META_LATEST)?;
+        split_latest(&bytes).map(|(id, _)| id)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+impl<E, T, S, I> ColumnAccess<E, T, S, I>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
+    S: IterableStorage,
+{
+    /// Check if the column is empty by looking for any stored entry.
+    ///
+    /// Unlike [`is_empty`](Self::is_empty), this doesn't rely on the length counter kept in
+    /// metadata, so it gives the right answer even if that counter has drifted. The cost is
+    /// a single step of a key scan rather than a single metadata read, which may be more
+    /// expensive depending on the backend.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// assert!(access.is_empty_scan());
+    ///
+    /// access.push(&1337).unwrap();
+    ///
+    /// assert!(!access.is_empty_scan());
+    /// ```
+    pub fn is_empty_scan(&self) -> bool {
+        self.storage
+            .keys(Bound::Unbounded, Bound::Unbounded)
+            .next()
+            .is_none()
+    }
+
+    /// Get the lowest ID with a value stored under it.
+    ///
+    /// Returns `Ok(None)` if the column is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// assert_eq!(access.first_id().unwrap(), None);
+    ///
+    /// access.push(&1337).unwrap();
+    /// access.push(&42).unwrap();
+    /// access.remove(1).unwrap();
+    ///
+    /// assert_eq!(access.first_id().unwrap(), Some(2));
+    /// ```
+    pub fn first_id(&self) -> Result<Option<I>, ColumnIdDecodeError> {
+        self.storage
+            .keys(Bound::Unbounded, Bound::Unbounded)
+            .next()
+            .map(|key| decode_id(&key))
+            .transpose()
+    }
+}
+
+impl<E, T, S, I> ColumnAccess<E, T, S, I>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
+    S: RevIterableStorage,
+{
+    /// Get the highest ID with a value stored under it.
+    ///
+    /// Returns `Ok(None)` if the column is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// assert_eq!(access.last_id().unwrap(), None);
+    ///
+    /// access.push(&1337).unwrap();
+    /// access.push(&42).unwrap();
+    /// access.remove(2).unwrap();
+    ///
+    /// assert_eq!(access.last_id().unwrap(), Some(1));
+    /// ```
+    pub fn last_id(&self) -> Result<Option<I>, ColumnIdDecodeError> {
+        self.storage
+            .rev_keys(Bound::Unbounded, Bound::Unbounded)
+            .next()
+            .map(|key| decode_id(&key))
+            .transpose()
+    }
+}
+
+/// Read the length counter from the column's metadata.
+///
+/// A missing counter means the column was never pushed to. A counter that isn't exactly
+/// four bytes long is reported as an inconsistent state rather than read partially.
+fn read_len<S: Storage>(storage: &S) -> Result<u32, LenError> {
+    storage
+        .get_meta(meta_keys::META_LEN)
+        .map(|bytes| {
+            bytes
+                .try_into()
+                .map(u32::from_be_bytes)
+                .map_err(|_| LenError::InconsistentState)
+        })
+        .unwrap_or(Ok(0))
+}
+
+/// Read the highest ID ever assigned from the column's metadata.
+///
+/// Like [`read_len`], this reports a malformed value as an inconsistent state.
+fn read_last_id<I: ColumnId, S: Storage>(storage: &S) -> Result<Option<I>, LenError> {
+    storage
+        .get_meta(meta_keys::META_LAST_ID)
+        .map(|bytes| I::from_be_slice(&bytes).ok_or(LenError::InconsistentState))
+        .transpose()
+}
+
+fn decode_id<I: ColumnId>(id: &[u8]) -> Result<I, ColumnIdDecodeError> {
+    I::from_be_slice(id).ok_or(ColumnIdDecodeError)
+}
+
+fn encode_id<I: ColumnId>(id: I) -> Vec<u8> {
+    id.to_be_vec()
+}
+
+/// Split a latest value cache entry into the ID and the encoded value.
+fn split_latest<I: ColumnId>(bytes: &[u8]) -> Option<(I, &[u8])> {
+    let id_len = encode_id(I::FIRST).len();
+    let id = I::from_be_slice(bytes.get(..id_len)?)?;
+
+    Some((id, &bytes[id_len..]))
+}
+
+impl<E, T, S, I> ColumnAccess<E, T, S, I>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
+    S: StorageMut + Storage,
+{
+    /// Append a new value to the end of the column.
+    ///
+    /// Returns the ID of the newly inserted value. If the column is empty, the first
+    /// ID will be `1`.
+    ///
+    /// The length is tracked as a `u32`, so a column can't hold more than `u32::MAX`
+    /// elements at once, even with `u64` IDs. Pushing past that fails with
+    /// `PushError::LenOverflow`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// const COLUMN_KEY: u8 = 0;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(COLUMN_KEY);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// assert_eq!(access.push(&1337).unwrap(), 1);
+    /// assert_eq!(access.push(&42).unwrap(), 2);
+    /// ```
+    pub fn push(&mut self, value: &T) -> Result<I, PushError<E::EncodeError>> {
+        let mut bytes = Vec::new();
+        value.encode_into(&mut bytes)?;
+
+        self.push_encoded(&bytes)
+    }
+
+    fn push_encoded(&mut self, bytes: &[u8]) -> Result<I, PushError<E::EncodeError>> {
+        let layout = self.layout().map_err(PushError::from_layout)?;
+
+        let id =
+            match read_last_id::<I, _>(&self.storage).map_err(|_| PushError::InconsistentState)? {
+                Some(id) => id.checked_next().ok_or(PushError::IdOverflow)?,
+                None => I::FIRST,
+            };
+        let len = read_len(&self.storage)
+            .map_err(|_| PushError::InconsistentState)?
+            .checked_add(1)
+            .ok_or(PushError::LenOverflow)?;
+
+        if layout == Layout::Legacy {
+            self.upgrade_legacy()
+                .map_err(|_| PushError::InconsistentState)?;
+        }
+
+        POSITION_INDEX
+            .insert(&mut self.storage, id.to_u64())
+            .map_err(|_| PushError::InconsistentState)?;
+        self.storage.set(&encode_id(id), bytes);
+
+        if self.cache_latest {
+            self.storage.set_meta(
+                meta_keys::META_LATEST,
+                &[&encode_id(id)[..], bytes].concat(),
+            );
+        }
+
+        self.storage
+            .set_meta(meta_keys::META_LAST_ID, &encode_id(id));
+        self.storage
+            .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+
+        if layout == Layout::Empty {
+            record_layout_version(&mut self.storage, meta_keys::META_VERSION);
+        }
+
+        Ok(id)
+    }
+
+    /// Append several values to the end of the column, returning their IDs.
+    ///
+    /// This is equivalent to calling [`push`](Self::push) for each value, but cheaper for bulk
+    /// imports: the column's metadata (the last ID and the length) is read once and written
+    /// once, rather than once per value. The positional index is still updated per value.
+    ///
+    /// All values are encoded and all IDs are assigned before anything is written, so if a
+    /// value fails to encode or the IDs or the length would overflow, the column is left
+    /// untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1).unwrap();
+    /// assert_eq!(access.extend([2, 3, 4]).unwrap(), vec![2, 3, 4]);
+    /// assert_eq!(access.get(3).unwrap(), Some(3));
+    /// assert_eq!(access.len().unwrap(), 4);
+    /// ```
+    pub fn extend<It>(&mut self, values: It) -> Result<Vec<I>, PushError<E::EncodeError>>
+    where
+        It: IntoIterator<Item = T>,
+    {
+        let layout = self.layout().map_err(PushError::from_layout)?;
+
+        let values = values
+            .into_iter()
+            .map(|value| value.encode())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut last_id =
+            read_last_id::<I, _>(&self.storage).map_err(|_| PushError::InconsistentState)?;
+        let len = read_len(&self.storage).map_err(|_| PushError::InconsistentState)?;
+        let mut ids = Vec::with_capacity(values.len());
+        for _ in &values {
+            let id = match last_id {
+                Some(id) => id.checked_next().ok_or(PushError::IdOverflow)?,
+                None => I::FIRST,
+            };
+            ids.push(id);
+            last_id = Some(id);
+        }
+
+        let len = u32::try_from(ids.len())
+            .ok()
+            .and_then(|count| len.checked_add(count))
+            .ok_or(PushError::LenOverflow)?;
+
+        let (Some(&last_id), Some(last_bytes)) = (ids.last(), values.last()) else {
+            return Ok(ids);
+        };
+
+        if layout == Layout::Legacy {
+            self.upgrade_legacy()
+                .map_err(|_| PushError::InconsistentState)?;
+        }
+
+        for (id, bytes) in ids.iter().zip(&values) {
+            POSITION_INDEX
+                .insert(&mut self.storage, id.to_u64())
+                .map_err(|_| PushError::InconsistentState)?;
+            self.storage.set(&encode_id(*id), bytes);
+        }
+
+        if self.cache_latest {
+            self.storage.set_meta(
+                meta_keys::META_LATEST,
+                &[&encode_id(last_id)[..], last_bytes].concat(),
+            );
+        }
+
+        self.storage
+            .set_meta(meta_keys::META_LAST_ID, &encode_id(last_id));
+        self.storage
+            .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+
+        if layout == Layout::Empty {
+            record_layout_version(&mut self.storage, meta_keys::META_VERSION);
+        }
+
+        Ok(ids)
+    }
+
+    /// Move the ID counter forward, so that the next [`push`](Self::push) assigns `id`.
+    ///
+    /// IDs skipped this way are never assigned by `push`, which makes this useful for
+    /// reserving an ID range, or for lining IDs up with another column during a migration.
+    ///
+    /// The counter only ever moves forward: an `id` lower than
+    /// [`next_id`](Self::next_id) is rejected with `SetNextIdError::Backwards`, since
+    /// reusing IDs could overwrite existing elements. Setting the current next ID is a no-op.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1337).unwrap();
+    /// access.set_next_id(100).unwrap();
+    ///
+    /// assert_eq!(access.push(&42).unwrap(), 100);
+    /// assert!(access.set_next_id(50).is_err());
+    /// ```
+    pub fn set_next_id(&mut self, id: I) -> Result<(), SetNextIdError> {
+        let layout = self.layout()?;
+
+        let next = self.next_id()?;
+        if id.to_u64() < next.to_u64() {
+            return Err(SetNextIdError::Backwards);
+        }
+        if id == next {
+            return Ok(());
+        }
+
+        // `id` is above `next`, so it's at least `2`
+        let last_id = I::from_u64(id.to_u64() - 1).ok_or(SetNextIdError::InconsistentState)?;
+
+        match layout {
+            Layout::Empty => record_layout_version(&mut self.storage, meta_keys::META_VERSION),
+            Layout::Legacy => self
+                .upgrade_legacy()
+                .map_err(|_| SetNextIdError::InconsistentState)?,
+            Layout::Current => {}
+        }
+        self.storage
+            .set_meta(meta_keys::META_LAST_ID, &encode_id(last_id));
+
+        Ok(())
+    }
+
+    /// Set the value associated with the given ID.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1337).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    ///
+    /// access.set(1, &9001).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(9001));
+    /// ```
+    pub fn set(&mut self, id: I, value: &T) -> Result<(), SetError<E::EncodeError>> {
+        self.storage.get(&encode_id(id)).ok_or(SetError::NotFound)?;
+
+        let bytes = value.encode()?;
+
+        self.storage.set(&encode_id(id), &bytes);
+
+        if self.cache_latest && self.cached_latest_id() == Some(id) {
+            self.storage.set_meta(
+                meta_keys::META_LATEST,
+                &[&encode_id(id)[..], &bytes].concat(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Update the value associated with the given ID by applying a function to it.
+    ///
+    /// The provided function is called with the current value, if it exists, and should return the
+    /// new value. If the function returns `None`, the value is removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1337).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    ///
+    /// access.update(1, |value| value.map(|v| v + 1)).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(1338));
+    /// ```
+    pub fn update<F>(
+        &mut self,
+        id: I,
+        f: F,
+    ) -> Result<(), UpdateError<E::DecodeError, E::EncodeError>>
+    where
+        F: FnOnce(Option<T>) -> Option<T>,
+    {
+        let new_value = f(self.get(id).map_err(UpdateError::Decode)?);
+        match new_value {
+            Some(value) => self.set(id, &value).map_err(UpdateError::Set),
+            None => self.remove(id).map(|_| ()).map_err(UpdateError::Remove),
+        }
+    }
+
+    /// Remove the value associated with the given ID.
+    ///
+    /// This operation leaves behind an empty slot in the column. The ID is not reused.
+    ///
+    /// Returns `Ok(true)` if a value was removed, and `Ok(false)` if there was nothing
+    /// stored under the ID.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1337).unwrap();
+    /// assert_eq!(access.get(1).unwrap(), Some(1337));
+    ///
+    /// assert_eq!(access.remove(1).unwrap(), true);
+    /// assert_eq!(access.get(1).unwrap(), None);
+    ///
+    /// assert_eq!(access.remove(1).unwrap(), false);
+    /// ```
+    pub fn remove(&mut self, id: I) -> Result<bool, RemoveError> {
+        let layout = self.layout()?;
+
+        if !self.storage.has(&encode_id(id)) {
+            return Ok(false);
+        }
+
+        let len = read_len(&self.storage)
+            .ok()
+            .and_then(|len| len.checked_sub(1))
+            .ok_or(RemoveError::InconsistentState)?;
+
+        if layout == Layout::Legacy {
+            self.upgrade_legacy()
+                .map_err(|_| RemoveError::InconsistentState)?;
+        }
+
+        POSITION_INDEX
+            .remove(&mut self.storage, id.to_u64())
+            .map_err(|_| RemoveError::InconsistentState)?;
+
+        self.storage.remove(&encode_id(id));
+
+        self.storage
+            .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+
+        if self.cache_latest && self.cached_latest_id() == Some(id) {
+            self.refresh_latest()
+                .map_err(|_| RemoveError::InconsistentState)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Remove the value associated with the given ID and return it, filling the gap with
+    /// the element that currently has the highest ID.
+    ///
+    /// This works like [`Vec::swap_remove`]: it keeps the column compact instead of leaving
+    /// behind an empty slot, at the cost of changing the ID of the moved element.
+    ///
+    /// **IDs are not stable under `swap_remove`.** Unlike with [`remove`](Self::remove),
+    /// the element with the highest ID ends up under `id`. Don't use this if you hand out
+    /// IDs as long-lived references. IDs are still never reused by [`push`](Self::push).
+    ///
+    /// Returns `Ok(None)` and leaves the column untouched if there's no entry under `id`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1).unwrap();
+    /// access.push(&2).unwrap();
+    /// access.push(&3).unwrap();
+    ///
+    /// assert_eq!(access.swap_remove(1).unwrap(), Some(1));
+    /// assert_eq!(access.get(1).unwrap(), Some(3));
+    /// assert_eq!(access.get(3).unwrap(), None);
+    /// assert_eq!(access.len().unwrap(), 2);
+    /// ```
+    pub fn swap_remove(&mut self, id: I) -> Result<Option<T>, SwapRemoveError<E::DecodeError>> {
+        let layout = self.layout().map_err(SwapRemoveError::from_layout)?;
+
+        let Some(bytes) = self.storage.get(&encode_id(id)) else {
+            return Ok(None);
+        };
+        let value = T::decode(&bytes)?;
+
+        let len = read_len(&self.storage)
+            .ok()
+            .and_then(|len| len.checked_sub(1))
+            .ok_or(SwapRemoveError::InconsistentState)?;
+
+        if layout == Layout::Legacy {
+            self.upgrade_legacy()
+                .map_err(|_| SwapRemoveError::InconsistentState)?;
+        }
+
+        let count = POSITION_INDEX
+            .count(&self.storage)
+            .map_err(|_| SwapRemoveError::InconsistentState)?;
+        let last_id = count
+            .checked_sub(1)
+            .and_then(|position| POSITION_INDEX.select(&self.storage, position).transpose())
+            .ok_or(SwapRemoveError::InconsistentState)?
+            .ok()
+            .and_then(I::from_u64)
+            .ok_or(SwapRemoveError::InconsistentState)?;
+
+        if last_id != id {
+            let last = self
+                .storage
+                .get(&encode_id(last_id))
+                .ok_or(SwapRemoveError::InconsistentState)?;
+            self.storage.set(&encode_id(id), &last);
+        }
+
+        self.storage.remove(&encode_id(last_id));
+        POSITION_INDEX
+            .remove(&mut self.storage, last_id.to_u64())
+            .map_err(|_| SwapRemoveError::InconsistentState)?;
+
+        self.storage
+            .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+
+        if self.cache_latest {
+            self.refresh_latest()
+                .map_err(|_| SwapRemoveError::InconsistentState)?;
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Recompute the latest value cache from the positional index.
+    fn refresh_latest(&mut self) -> Result<(), IndexError> {
+        let count = POSITION_INDEX.count(&self.storage)?;
+        let last_id = match count.checked_sub(1) {
+            Some(position) => POSITION_INDEX.select(&self.storage, position)?,
+            None => None,
+        };
+
+        match last_id
+            .map(|id| I::from_u64(id).ok_or(IndexError))
+            .transpose()?
+        {
+            Some(id) => {
+                let bytes = self.storage.get(&encode_id(id)).ok_or(IndexError)?;
+                self.storage.set_meta(
+                    meta_keys::META_LATEST,
+                    &[&encode_id(id)[..], &bytes].concat(),
+                );
+            }
+            None => self.storage.remove_meta(meta_keys::META_LATEST),
+        }
+
+        Ok(())
+    }
+
+    /// Build the positional index of a column written before the layout was versioned
+    /// (see [`Layout::Legacy`]), then record the current layout version.
+    ///
+    /// This probes every ID ever assigned, so it's a one-time cost proportional to the
+    /// highest ID. The first write to such a column does it, once the write's own checks
+    /// have passed.
+    fn upgrade_legacy(&mut self) -> Result<(), IndexError> {
+        let ids = self
+            .probe_ids()
+            .map_err(|_| IndexError)?
+            .collect::<Vec<_>>();
+        for id in ids {
+            POSITION_INDEX.insert(&mut self.storage, id.to_u64())?;
+        }
+
+        if self.cache_latest {
+            self.refresh_latest()?;
+        }
+
+        record_layout_version(&mut self.storage, meta_keys::META_VERSION);
+
+        Ok(())
+    }
+}
+
+impl<E, T, S, I> ColumnAccess<E, T, S, I>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: ColumnId,
+    S: IterableStorage + StorageMut + Storage,
+{
+    /// Remove all elements for which the predicate returns `false`.
+    ///
+    /// The predicate is called with the ID and value of each element, in ascending ID order.
+    /// Like with [`remove`](Self::remove), removed IDs are not reused.
+    ///
+    /// Returns the number of elements removed. If decoding any value fails, nothing is removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1).unwrap();
+    /// access.push(&2).unwrap();
+    /// access.push(&3).unwrap();
+    ///
+    /// assert_eq!(access.retain(|_, value| value % 2 == 1).unwrap(), 1);
+    /// assert_eq!(access.get(2).unwrap(), None);
+    /// assert_eq!(access.len().unwrap(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F) -> Result<u32, RetainError<E::DecodeError>>
+    where
+        F: FnMut(I, &T) -> bool,
+    {
+        self.layout().map_err(RetainError::from_layout)?;
+
+        // collect the IDs first so that we don't mutate the storage while iterating over it
+        let mut to_remove = Vec::new();
+        for (key, value) in self.storage.pairs(Bound::Unbounded, Bound::Unbounded) {
+            let id = decode_id(&key).map_err(|_| RetainError::InconsistentState)?;
+            let value = T::decode(&value)?;
+
+            if !f(id, &value) {
+                to_remove.push(id);
+            }
+        }
+
+        for &id in &to_remove {
+            match self.remove(id) {
+                Ok(true) => {}
+                Ok(false) | Err(RemoveError::InconsistentState) => {
+                    return Err(RetainError::InconsistentState)
+                }
+                Err(RemoveError::IncompatibleLayout(v)) => {
+                    return Err(RetainError::IncompatibleLayout(v))
+                }
+            }
+        }
+
+        Ok(to_remove.len() as u32)
+    }
+
+    /// Replace the entire contents of the column with the given values.
+    ///
+    /// All existing elements are removed and the column's metadata is reset, so the new
+    /// values get fresh IDs starting from `1` again. This is meant for things like contract
+    /// migrations, where a container needs to be wiped and repopulated.
+    ///
+    /// All values are encoded before anything is written, so if any of them fails to encode,
+    /// the column is left untouched. Beyond that, this is not a transaction. On a blockchain,
+    /// the changes are only atomic within the message that makes them (a failing message
+    /// reverts all of its state changes) - there's no rollback across messages.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Column;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let column = Column::<u64, TestEncoding>::new(0);
+    /// let mut access = column.access(&mut storage);
+    ///
+    /// access.push(&1).unwrap();
+    /// access.push(&2).unwrap();
+    /// access.push(&3).unwrap();
+    ///
+    /// access.reset_with([4, 5]).unwrap();
+    ///
+    /// assert_eq!(access.get(1).unwrap(), Some(4));
+    /// assert_eq!(access.get(2).unwrap(), Some(5));
+    /// assert_eq!(access.get(3).unwrap(), None);
+    /// assert_eq!(access.len().unwrap(), 2);
+    /// ```
+    pub fn reset_with<It>(&mut self, values: It) -> Result<(), ResetError<E::EncodeError>>
+    where
+        It: IntoIterator<Item = T>,
+    {
+        let layout = self.layout().map_err(ResetError::from_layout)?;
+
+        let values = values
+            .into_iter()
+            .map(|value| value.encode())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // the positional index has to be emptied entry by entry - legacy columns don't have one
+        if layout == Layout::Current {
+            let ids = self
+                .storage
+                .keys(Bound::Unbounded, Bound::Unbounded)
+                .map(|key| decode_id::<I>(&key))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ResetError::InconsistentState)?;
+            for id in ids {
+                POSITION_INDEX
+                    .remove(&mut self.storage, id.to_u64())
+                    .map_err(|_| ResetError::InconsistentState)?;
+            }
+        }
+
+        remove_range(&mut self.storage, Bound::Unbounded, Bound::Unbounded);
+        self.storage.remove_meta(meta_keys::META_LAST_ID);
+        self.storage.remove_meta(meta_keys::META_LEN);
+        self.storage.remove_meta(meta_keys::META_INDEX_DEPTH);
+        self.storage.remove_meta(meta_keys::META_LATEST);
+
+        for bytes in values {
+            self.push_encoded(&bytes).map_err(|e| match e {
+                PushError::IncompatibleLayout(v) => ResetError::IncompatibleLayout(v),
+                _ => ResetError::InconsistentState,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum PushError<E> {
+    #[error("ID overflow")]
+    IdOverflow,
+    #[error("length overflow")]
+    LenOverflow,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+impl<E> PushError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => PushError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => PushError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl<E> From<E> for PushError<E> {
+    fn from(e: E) -> Self {
+        PushError::EncodingError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum SetError<E> {
+    #[error("not found")]
+    NotFound,
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+impl<E> From<E> for SetError<E> {
+    fn from(e: E) -> Self {
+        SetError::EncodingError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum UpdateError<D, E> {
+    #[error("decode error: {0}")]
+    Decode(D),
+    #[error("set error: {0}")]
+    Set(SetError<E>),
+    #[error("remove error: {0}")]
+    Remove(RemoveError),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum RemoveError {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for RemoveError {
+    fn from(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => RemoveError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => RemoveError::IncompatibleLayout(v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum SwapRemoveError<E> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    DecodeError(E),
+}
+
+impl<E> SwapRemoveError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => SwapRemoveError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => SwapRemoveError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl<E> From<E> for SwapRemoveError<E> {
+    fn from(e: E) -> Self {
+        SwapRemoveError::DecodeError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum RetainError<E> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    DecodeError(E),
+}
+
+impl<E> RetainError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => RetainError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => RetainError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl<E> From<E> for RetainError<E> {
+    fn from(e: E) -> Self {
+        RetainError::DecodeError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum ResetError<E> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+impl<E> ResetError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => ResetError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => ResetError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl<E> From<E> for ResetError<E> {
+    fn from(e: E) -> Self {
+        ResetError::EncodingError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
 pub enum LenError {
     #[error("inconsistent state")]
     InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for LenError {
+    fn from(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => LenError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => LenError::IncompatibleLayout(v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum NextIdError {
+    #[error("ID overflow")]
+    IdOverflow,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for NextIdError {
+    fn from(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => NextIdError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => NextIdError::IncompatibleLayout(v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum SetNextIdError {
+    #[error("the next ID can't be moved backwards")]
+    Backwards,
+    #[error("ID overflow")]
+    IdOverflow,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for SetNextIdError {
+    fn from(e: LayoutError) -> Self {
+        NextIdError::from(e).into()
+    }
+}
+
+impl From<NextIdError> for SetNextIdError {
+    fn from(e: NextIdError) -> Self {
+        match e {
+            NextIdError::IdOverflow => SetNextIdError::IdOverflow,
+            NextIdError::InconsistentState => SetNextIdError::InconsistentState,
+            NextIdError::IncompatibleLayout(v) => SetNextIdError::IncompatibleLayout(v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum PositionError<E> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    DecodeError(E),
+}
+
+impl<E> From<E> for PositionError<E> {
+    fn from(e: E) -> Self {
+        PositionError::DecodeError(e)
+    }
+}
+
+impl<E> PositionError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => PositionError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => PositionError::IncompatibleLayout(v),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::ops::Bound;
+    use core::ops::Bound;
 
     use crate::containers::{BoundedRevIterableAccessor as _, RevIterableAccessor as _};
 
@@ -525,6 +1780,82 @@ mod tests {
     use mocks::backend::TestStorage;
     use mocks::encoding::TestEncoding;
 
+    #[test]
+    fn latest() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0).with_latest_cache();
+        let mut access = column.access(&mut storage);
+
+        fn check(access: &ColumnAccess<TestEncoding, u64, StorageBranch<&mut TestStorage>>) {
+            let scanned = access.rev_values().next().transpose().unwrap();
+            assert_eq!(access.latest().unwrap(), scanned);
+        }
+
+        check(&access);
+
+        for i in 1..=5 {
+            access.push(&(i * 10)).unwrap();
+            check(&access);
+        }
+
+        access.set(5, &500).unwrap();
+        check(&access);
+        access.set(2, &200).unwrap();
+        check(&access);
+
+        access.remove(3).unwrap();
+        check(&access);
+        access.remove(5).unwrap();
+        check(&access);
+        assert_eq!(access.latest(), Ok(Some(40)));
+
+        access.swap_remove(1).unwrap();
+        check(&access);
+        access.swap_remove(4).unwrap();
+        check(&access);
+
+        access.push(&60).unwrap();
+        access.retain(|_, value| *value != 60).unwrap();
+        check(&access);
+
+        access.reset_with([1, 2, 3]).unwrap();
+        check(&access);
+        assert_eq!(access.latest(), Ok(Some(3)));
+
+        access.extend([4, 5]).unwrap();
+        check(&access);
+        assert_eq!(access.latest(), Ok(Some(5)));
+
+        access.retain(|_, _| false).unwrap();
+        check(&access);
+        assert_eq!(access.latest(), Ok(None));
+        assert!(!access.storage.has_meta(meta_keys::META_LATEST));
+    }
+
+    #[test]
+    fn latest_without_cache() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        access.push(&1).unwrap();
+        access.push(&2).unwrap();
+
+        assert_eq!(access.latest(), Ok(Some(2)));
+        assert!(!access.storage.has_meta(meta_keys::META_LATEST));
+
+        // enabling the cache later falls back to a lookup until the next write
+        let column = Column::<u64, TestEncoding>::new(0).with_latest_cache();
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.latest(), Ok(Some(2)));
+
+        access.push(&3).unwrap();
+        assert_eq!(access.latest(), Ok(Some(3)));
+        assert!(access.storage.has_meta(meta_keys::META_LATEST));
+    }
+
     #[test]
     fn basic() {
         let mut storage = TestStorage::new();
@@ -566,11 +1897,16 @@ mod tests {
         assert_eq!(access.len().unwrap(), 2);
 
         // remove first
-        access.remove(10).unwrap();
+        assert!(access.remove(1).unwrap());
+        assert_eq!(access.len().unwrap(), 1);
+
+        // removing a missing or already removed ID changes nothing
+        assert!(!access.remove(10).unwrap());
+        assert!(!access.remove(2).unwrap());
         assert_eq!(access.len().unwrap(), 1);
 
         // remove last
-        access.remove(3).unwrap();
+        assert!(access.remove(3).unwrap());
         assert_eq!(access.len().unwrap(), 0);
 
         // Above removals do not reset the auto-incrementor,
@@ -810,4 +2146,529 @@ mod tests {
             vec![(5, 2), (4, 1), (2, 42)]
         );
     }
+
+    #[test]
+    fn layout_version() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        column.access(&mut storage).push(&1337).unwrap();
+
+        assert_eq!(
+            StorageBranch::new(&storage, vec![0]).get_meta(meta_keys::META_VERSION),
+            Some(vec![crate::containers::common::LAYOUT_VERSION])
+        );
+
+        // seed a layout version from the future
+        StorageBranch::new(&mut storage, vec![0]).set_meta(meta_keys::META_VERSION, &[99]);
+
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.len(), Err(LenError::IncompatibleLayout(99)));
+        assert_eq!(access.push(&42), Err(PushError::IncompatibleLayout(99)));
+        assert_eq!(access.remove(1), Err(RemoveError::IncompatibleLayout(99)));
+    }
+
+    #[test]
+    fn update_remove_error() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        column.access(&mut storage).push(&1337).unwrap();
+
+        // seed a layout version from the future
+        StorageBranch::new(&mut storage, vec![0]).set_meta(meta_keys::META_VERSION, &[99]);
+
+        assert_eq!(
+            column.access(&mut storage).update(1, |_| None),
+            Err(UpdateError::Remove(RemoveError::IncompatibleLayout(99)))
+        );
+        assert_eq!(column.access(&storage).get(1).unwrap(), Some(1337));
+    }
+
+    #[test]
+    fn failed_push_records_no_layout_version() {
+        let mut storage = TestStorage::new();
+
+        // seed a pre-versioning column whose IDs are exhausted
+        let mut branch = StorageBranch::new(&mut storage, vec![0]);
+        branch.set_meta(meta_keys::META_LAST_ID, &u32::MAX.to_be_bytes());
+        branch.set_meta(meta_keys::META_LEN, &1u32.to_be_bytes());
+        assert_eq!(
+            check_layout_version(&branch, meta_keys::META_VERSION, meta_keys::META_LEN),
+            Ok(Layout::Legacy)
+        );
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.push(&1337), Err(PushError::IdOverflow));
+        assert_eq!(access.extend([1337]), Err(PushError::IdOverflow));
+
+        assert_eq!(
+            StorageBranch::new(&storage, vec![0]).get_meta(meta_keys::META_VERSION),
+            None
+        );
+    }
+
+    /// Seed a column the way it was written before the layout was versioned: data and
+    /// the ID and length counters, without a layout version or a positional index.
+    fn seed_legacy(storage: &mut TestStorage, values: &[(u32, u64)], last_id: u32) {
+        let mut branch = StorageBranch::new(storage, vec![0]);
+        branch.set_meta(meta_keys::META_LAST_ID, &last_id.to_be_bytes());
+        branch.set_meta(meta_keys::META_LEN, &(values.len() as u32).to_be_bytes());
+        for (id, value) in values {
+            let bytes = EncodableWith::<TestEncoding>::encode(value).unwrap();
+            branch.set(&id.to_be_bytes(), &bytes);
+        }
+    }
+
+    #[test]
+    fn legacy_layout() {
+        let mut storage = TestStorage::new();
+        seed_legacy(&mut storage, &[(1, 1337), (3, 42)], 3);
+
+        let column = Column::<u64, TestEncoding>::new(0).with_latest_cache();
+
+        // reads fall back to probing IDs until the index is built
+        let access = column.access(&storage);
+        assert_eq!(access.first(), Ok(Some(1337)));
+        assert_eq!(access.last(), Ok(Some(42)));
+        assert_eq!(access.latest(), Ok(Some(42)));
+        assert_eq!(access.get_by_position(1), Ok(Some(42)));
+        assert_eq!(access.get_by_position(2), Ok(None));
+
+        // the first write builds the index
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.remove(3), Ok(true));
+        assert_eq!(access.first(), Ok(Some(1337)));
+        assert_eq!(access.latest(), Ok(Some(1337)));
+
+        assert_eq!(access.push(&7), Ok(4));
+        assert_eq!(access.remove(1), Ok(true));
+        assert_eq!(access.len(), Ok(1));
+        assert_eq!(access.first(), Ok(Some(7)));
+        assert_eq!(access.last(), Ok(Some(7)));
+
+        assert_eq!(
+            StorageBranch::new(&storage, vec![0]).get_meta(meta_keys::META_VERSION),
+            Some(vec![crate::containers::common::LAYOUT_VERSION])
+        );
+    }
+
+    #[test]
+    fn legacy_layout_writes() {
+        let column = Column::<u64, TestEncoding>::new(0);
+
+        let mut storage = TestStorage::new();
+        seed_legacy(&mut storage, &[(1, 1), (2, 2), (3, 3)], 3);
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.swap_remove(1), Ok(Some(1)));
+        assert_eq!(access.get_by_position(0), Ok(Some(3)));
+        assert_eq!(access.last(), Ok(Some(2)));
+
+        let mut storage = TestStorage::new();
+        seed_legacy(&mut storage, &[(1, 1), (2, 2), (3, 3)], 3);
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.retain(|_, value| value % 2 == 1), Ok(1));
+        assert_eq!(access.get_by_position(1), Ok(Some(3)));
+
+        let mut storage = TestStorage::new();
+        seed_legacy(&mut storage, &[(1, 1), (2, 2)], 2);
+        let mut access = column.access(&mut storage);
+        access.reset_with([4, 5]).unwrap();
+        assert_eq!(access.last(), Ok(Some(5)));
+        assert_eq!(access.remove(1), Ok(true));
+        assert_eq!(access.first(), Ok(Some(5)));
+
+        let mut storage = TestStorage::new();
+        seed_legacy(&mut storage, &[(2, 2)], 2);
+        let mut access = column.access(&mut storage);
+        access.set_next_id(10).unwrap();
+        assert_eq!(access.extend([10, 11]), Ok(vec![10, 11]));
+        assert_eq!(access.get_by_position(0), Ok(Some(2)));
+        assert_eq!(access.remove(2), Ok(true));
+        assert_eq!(access.first(), Ok(Some(10)));
+    }
+
+    #[test]
+    fn is_empty_scan() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        assert!(access.is_empty_scan());
+
+        access.push(&1337).unwrap();
+        assert!(!access.is_empty_scan());
+
+        access.remove(1).unwrap();
+        assert!(access.is_empty_scan());
+
+        // corrupt the length counter
+        storage.set_meta(&[0, 1], &5u32.to_be_bytes());
+
+        let access = column.access(&storage);
+        assert!(!access.is_empty().unwrap());
+        assert!(access.is_empty_scan());
+    }
+
+    #[test]
+    fn access_branch() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+
+        let branch = StorageBranch::new(&mut storage, vec![0]);
+        let mut access = column.access_branch(branch);
+        access.push(&1337).unwrap();
+        access.push(&42).unwrap();
+
+        let access = column.access(&storage);
+        assert_eq!(access.len().unwrap(), 2);
+        assert_eq!(access.get(2).unwrap(), Some(42));
+    }
+
+    #[test]
+    fn get_by_position() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        for i in 0..20 {
+            access.push(&i).unwrap();
+        }
+        for id in [1, 5, 6, 7, 20, 13] {
+            access.remove(id).unwrap();
+        }
+        for i in 20..25 {
+            access.push(&i).unwrap();
+        }
+        access.remove(22).unwrap();
+        // removing a missing ID leaves the index alone
+        access.remove(1000).unwrap();
+
+        let expected = access.values().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(expected.len(), 18);
+
+        for (position, value) in expected.iter().enumerate() {
+            assert_eq!(
+                access.get_by_position(position as u32).unwrap(),
+                Some(*value)
+            );
+        }
+        assert_eq!(access.get_by_position(18).unwrap(), None);
+
+        assert_eq!(access.first().unwrap(), Some(1));
+        assert_eq!(access.last().unwrap(), Some(24));
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        for i in 1..=5 {
+            access.push(&(i * 10)).unwrap();
+        }
+        access.remove(5).unwrap();
+
+        // the highest live ID (4) moves into the gap
+        assert_eq!(access.swap_remove(2).unwrap(), Some(20));
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(1, 10), (2, 40), (3, 30)]
+        );
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(access.last().unwrap(), Some(30));
+
+        // removing the last element just removes it
+        assert_eq!(access.swap_remove(3).unwrap(), Some(30));
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(1, 10), (2, 40)]
+        );
+
+        // missing IDs are a no-op
+        assert_eq!(access.swap_remove(3).unwrap(), None);
+        assert_eq!(access.len().unwrap(), 2);
+
+        // IDs are not reused
+        assert_eq!(access.push(&60).unwrap(), 6);
+        assert_eq!(access.get_by_position(2).unwrap(), Some(60));
+    }
+
+    #[test]
+    fn contains_and_id_range() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        assert!(!access.contains(1));
+        assert_eq!(access.first_id().unwrap(), None);
+        assert_eq!(access.last_id().unwrap(), None);
+
+        for i in 0..5 {
+            access.push(&i).unwrap();
+        }
+        access.remove(1).unwrap();
+        access.remove(5).unwrap();
+
+        assert!(!access.contains(1));
+        assert!(access.contains(2));
+        assert!(access.contains(4));
+        assert!(!access.contains(5));
+
+        assert_eq!(access.first_id().unwrap(), Some(2));
+        assert_eq!(access.last_id().unwrap(), Some(4));
+    }
+
+    #[test]
+    fn u64_ids() {
+        let mut storage = TestStorage::new();
+
+        // a `u32` column refuses to go past `u32::MAX`
+        let column = Column::<u64, TestEncoding>::new(0);
+        StorageBranch::new(&mut storage, vec![0])
+            .set_meta(meta_keys::META_LAST_ID, &u32::MAX.to_be_bytes());
+        assert_eq!(
+            column.access(&mut storage).push(&1337),
+            Err(PushError::IdOverflow)
+        );
+
+        let column = Column::<u64, TestEncoding, u64>::new(1);
+        let mut access = column.access(&mut storage);
+
+        assert_eq!(access.push(&1337).unwrap(), 1);
+
+        // pretend we've already pushed `u32::MAX` elements
+        StorageBranch::new(&mut storage, vec![1])
+            .set_meta(meta_keys::META_LAST_ID, &(u32::MAX as u64).to_be_bytes());
+
+        let mut access = column.access(&mut storage);
+        let id = access.push(&42).unwrap();
+        assert_eq!(id, u32::MAX as u64 + 1);
+        assert_eq!(access.push(&9001).unwrap(), u32::MAX as u64 + 2);
+
+        assert_eq!(access.get(1).unwrap(), Some(1337));
+        assert_eq!(access.get(id).unwrap(), Some(42));
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(access.last().unwrap(), Some(9001));
+        assert_eq!(access.get_by_position(1).unwrap(), Some(42));
+        assert_eq!(access.last_id().unwrap(), Some(u32::MAX as u64 + 2));
+
+        let pairs = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                (1, 1337),
+                (u32::MAX as u64 + 1, 42),
+                (u32::MAX as u64 + 2, 9001)
+            ]
+        );
+
+        assert!(access.remove(id).unwrap());
+        assert_eq!(access.get(id).unwrap(), None);
+        assert_eq!(access.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn len_overflow() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding, u64>::new(0);
+        column.access(&mut storage).push(&1337).unwrap();
+
+        // pretend the column already holds `u32::MAX` elements
+        StorageBranch::new(&mut storage, vec![0])
+            .set_meta(meta_keys::META_LEN, &u32::MAX.to_be_bytes());
+
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.push(&42), Err(PushError::LenOverflow));
+        assert_eq!(access.extend([42, 9001]), Err(PushError::LenOverflow));
+
+        assert_eq!(access.get(2), Ok(None));
+        assert_eq!(access.last(), Ok(Some(1337)));
+        assert_eq!(access.len(), Ok(u32::MAX));
+    }
+
+    #[test]
+    fn retain() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        for value in 1..=6 {
+            access.push(&value).unwrap();
+        }
+        access.remove(3).unwrap();
+
+        let mut seen = vec![];
+        let removed = access
+            .retain(|id, value| {
+                seen.push(id);
+                value % 2 == 0
+            })
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(seen, vec![1, 2, 4, 5, 6]);
+
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(2, 2), (4, 4), (6, 6)]
+        );
+        assert_eq!(access.first().unwrap(), Some(2));
+        assert_eq!(access.last().unwrap(), Some(6));
+
+        // nothing to remove
+        assert_eq!(access.retain(|_, _| true).unwrap(), 0);
+        assert_eq!(access.len().unwrap(), 3);
+
+        // remove everything
+        assert_eq!(access.retain(|_, _| false).unwrap(), 3);
+        assert_eq!(access.len().unwrap(), 0);
+        assert!(access.is_empty_scan());
+        assert_eq!(access.push(&7).unwrap(), 7);
+    }
+
+    #[test]
+    fn extend() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        assert_eq!(access.extend([1, 2, 3]).unwrap(), vec![1, 2, 3]);
+        access.remove(3).unwrap();
+        assert_eq!(access.extend([]).unwrap(), vec![]);
+        assert_eq!(access.extend([4, 5]).unwrap(), vec![4, 5]);
+        assert_eq!(access.push(&6).unwrap(), 6);
+
+        assert_eq!(access.len().unwrap(), 5);
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(1, 1), (2, 2), (4, 4), (5, 5), (6, 6)]
+        );
+        assert_eq!(access.get_by_position(2).unwrap(), Some(4));
+        assert_eq!(access.last_id().unwrap(), Some(6));
+
+        // the IDs would overflow, so nothing is written
+        let column = Column::<u64, TestEncoding>::new(1);
+        StorageBranch::new(&mut storage, vec![1])
+            .set_meta(meta_keys::META_LAST_ID, &(u32::MAX - 1).to_be_bytes());
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.extend([1, 2]), Err(PushError::IdOverflow));
+        assert!(access.is_empty_scan());
+        assert_eq!(access.extend([1]).unwrap(), vec![u32::MAX]);
+    }
+
+    #[test]
+    fn next_id() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        assert_eq!(access.next_id(), Ok(1));
+        access.set_next_id(1).unwrap();
+        assert_eq!(access.push(&1).unwrap(), 1);
+        assert_eq!(access.next_id(), Ok(2));
+
+        // reserve IDs 2 to 9
+        access.set_next_id(10).unwrap();
+        assert_eq!(access.next_id(), Ok(10));
+        assert_eq!(access.push(&10).unwrap(), 10);
+        assert_eq!(access.extend([11, 12]).unwrap(), vec![11, 12]);
+
+        // removals don't move the counter, and it never goes back
+        access.remove(12).unwrap();
+        assert_eq!(access.next_id(), Ok(13));
+        assert_eq!(access.set_next_id(12), Err(SetNextIdError::Backwards));
+        assert_eq!(access.set_next_id(1), Err(SetNextIdError::Backwards));
+        access.set_next_id(13).unwrap();
+        assert_eq!(access.push(&13).unwrap(), 13);
+
+        assert_eq!(access.len().unwrap(), 4);
+        assert_eq!(access.get_by_position(1).unwrap(), Some(10));
+
+        // the counter can be pushed right up to the last ID
+        access.set_next_id(u32::MAX).unwrap();
+        assert_eq!(access.push(&0).unwrap(), u32::MAX);
+        assert_eq!(access.next_id(), Err(NextIdError::IdOverflow));
+        assert_eq!(
+            access.set_next_id(u32::MAX),
+            Err(SetNextIdError::IdOverflow)
+        );
+    }
+
+    #[test]
+    fn malformed_metadata() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        column.access(&mut storage).push(&1).unwrap();
+
+        // a truncated length counter
+        StorageBranch::new(&mut storage, vec![0]).set_meta(meta_keys::META_LEN, &[0, 1]);
+
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.len(), Err(LenError::InconsistentState));
+        assert_eq!(access.push(&2), Err(PushError::InconsistentState));
+        assert_eq!(access.extend([2, 3]), Err(PushError::InconsistentState));
+        assert_eq!(access.remove(1), Err(RemoveError::InconsistentState));
+        assert_eq!(
+            access.swap_remove(1),
+            Err(SwapRemoveError::InconsistentState)
+        );
+
+        // nothing was written
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(1, 1)]
+        );
+
+        // a truncated last ID
+        let column = Column::<u64, TestEncoding>::new(1);
+        StorageBranch::new(&mut storage, vec![1]).set_meta(meta_keys::META_LAST_ID, &[0, 1]);
+
+        let mut access = column.access(&mut storage);
+        assert_eq!(access.next_id(), Err(NextIdError::InconsistentState));
+        assert_eq!(access.push(&1), Err(PushError::InconsistentState));
+        assert!(access.is_empty_scan());
+    }
+
+    #[test]
+    fn reset_with() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+
+        for value in 1..=100 {
+            access.push(&value).unwrap();
+        }
+        access.remove(50).unwrap();
+
+        access.reset_with([7, 8, 9]).unwrap();
+
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(1, 7), (2, 8), (3, 9)]
+        );
+        assert_eq!(access.first().unwrap(), Some(7));
+        assert_eq!(access.last().unwrap(), Some(9));
+        assert_eq!(access.get_by_position(3).unwrap(), None);
+        assert_eq!(access.push(&10).unwrap(), 4);
+
+        access.reset_with([]).unwrap();
+        assert_eq!(access.len().unwrap(), 0);
+        assert!(access.is_empty_scan());
+        assert_eq!(access.last().unwrap(), None);
+    }
 }
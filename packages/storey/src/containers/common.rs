@@ -1,3 +1,8 @@
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use crate::storage::{IterableMetaStorage, IterableStorage, Storage, StorageMut};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
 pub enum TryGetError<E> {
     #[error("item is empty")]
@@ -6,4 +11,93 @@ pub enum TryGetError<E> {
     DecodeError(#[from] E),
 }
 
-impl<T: std::fmt::Display> crate::error::StoreyError for TryGetError<T> {}
+impl<T: core::fmt::Display> crate::error::StoreyError for TryGetError<T> {}
+
+/// The version of the metadata layout written by this version of `storey`.
+///
+/// Containers that keep metadata record this version on their first mutation. If a future
+/// release changes a container's layout, it can bump this number and detect (and migrate)
+/// data written by older releases. Data written before versioning was introduced has no
+/// version recorded at all - see [`Layout::Legacy`].
+pub(crate) const LAYOUT_VERSION: u8 = 1;
+
+/// An error returned when a container encounters metadata written with a layout version
+/// this version of `storey` doesn't understand.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub(crate) enum LayoutError {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+/// The state of a container's metadata layout, as found by [`check_layout_version`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Layout {
+    /// Nothing was ever written to the container.
+    Empty,
+    /// The container holds data written before layout versioning was introduced.
+    Legacy,
+    /// The container was written with the current layout.
+    Current,
+}
+
+/// Check the layout version stored under the given metadata key.
+///
+/// If no version is stored, the container was either never written to, or written before
+/// layout versioning was introduced. The two are told apart by `data_key` - a metadata key
+/// that the container always wrote once it held any data.
+pub(crate) fn check_layout_version<S: Storage>(
+    storage: &S,
+    key: &[u8],
+    data_key: &[u8],
+) -> Result<Layout, LayoutError> {
+    match storage.get_meta(key).as_deref() {
+        None if storage.has_meta(data_key) => Ok(Layout::Legacy),
+        None => Ok(Layout::Empty),
+        Some([LAYOUT_VERSION]) => Ok(Layout::Current),
+        Some([version]) => Err(LayoutError::IncompatibleLayout(*version)),
+        Some(_) => Err(LayoutError::InconsistentState),
+    }
+}
+
+/// Record the current layout version under the given metadata key.
+///
+/// Call this only once the write it accompanies can no longer fail, so that a failed write
+/// doesn't leave a version behind for a container that holds no data.
+pub(crate) fn record_layout_version<S: StorageMut>(storage: &mut S, key: &[u8]) {
+    storage.set_meta(key, &[LAYOUT_VERSION]);
+}
+
+/// Remove all keys within the given range, returning the number of keys removed.
+///
+/// This is the building block for clearing a container's namespace. The keys are collected
+/// first so that the storage isn't mutated while iterating over it.
+pub(crate) fn remove_range<S: IterableStorage + StorageMut>(
+    storage: &mut S,
+    start: Bound<&[u8]>,
+    end: Bound<&[u8]>,
+) -> u32 {
+    let keys: Vec<_> = storage.keys(start, end).collect();
+
+    for key in &keys {
+        storage.remove(key);
+    }
+
+    keys.len() as u32
+}
+
+/// Remove every key in the given namespace, metadata included.
+pub(crate) fn clear_namespace<S: IterableStorage + IterableMetaStorage + StorageMut>(
+    storage: &mut S,
+) {
+    remove_range(storage, Bound::Unbounded, Bound::Unbounded);
+
+    let meta_keys: Vec<_> = storage
+        .meta_keys(Bound::Unbounded, Bound::Unbounded)
+        .collect();
+
+    for key in &meta_keys {
+        storage.remove_meta(key);
+    }
+}
@@ -0,0 +1,270 @@
+use alloc::vec;
+use core::fmt::Display;
+use core::marker::PhantomData;
+
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::{Storage, StorageMut};
+use crate::storage::{StorageBranch, StorageRef};
+
+use super::item::ItemKeyDecodeError;
+use super::{Storable, Terminal};
+
+/// A monotonic `u64` counter in the storage.
+///
+/// The count is stored under a single key, encoded with `E`, exactly like an
+/// [`Item<u64, E>`](super::Item). A counter that was never written reads as `0`.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Counter;
+///
+/// let mut storage = TestStorage::new();
+/// let counter = Counter::<TestEncoding>::new(0);
+/// let mut access = counter.access(&mut storage);
+///
+/// assert_eq!(access.increment().unwrap(), 1);
+/// assert_eq!(access.add(5).unwrap(), 6);
+/// assert_eq!(access.get().unwrap(), 6);
+/// ```
+pub struct Counter<E> {
+    key: u8,
+    phantom: PhantomData<E>,
+}
+
+impl<E> Counter<E>
+where
+    E: Encoding,
+    u64: EncodableWith<E> + DecodableWith<E>,
+{
+    /// Create a new counter with the given key.
+    ///
+    /// It is the responsibility of the caller to ensure that the key is unique.
+    pub const fn new(key: u8) -> Self {
+        Self {
+            key,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquire an accessor to the counter.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Counter;
+    ///
+    /// // immutable accessor
+    /// let storage = TestStorage::new();
+    /// let counter = Counter::<TestEncoding>::new(0);
+    /// let access = counter.access(&storage);
+    ///
+    /// // mutable accessor
+    /// let mut storage = TestStorage::new();
+    /// let counter = Counter::<TestEncoding>::new(0);
+    /// let mut access = counter.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(&self, storage: S) -> CounterAccess<E, StorageBranch<S>> {
+        Self::access_impl(StorageBranch::new(storage, vec![self.key]))
+    }
+}
+
+impl<E> Storable for Counter<E>
+where
+    E: Encoding,
+    u64: EncodableWith<E> + DecodableWith<E>,
+{
+    type Kind = Terminal;
+    type Accessor<S> = CounterAccess<E, S>;
+    type Key = ();
+    type KeyDecodeError = ItemKeyDecodeError;
+    type Value = u64;
+    type ValueDecodeError = E::DecodeError;
+
+    fn access_impl<S>(storage: S) -> CounterAccess<E, S> {
+        CounterAccess {
+            storage,
+            phantom: PhantomData,
+        }
+    }
+
+    fn decode_key(key: &[u8]) -> Result<(), ItemKeyDecodeError> {
+        if key.is_empty() {
+            Ok(())
+        } else {
+            Err(ItemKeyDecodeError)
+        }
+    }
+
+    fn decode_value(value: &[u8]) -> Result<u64, E::DecodeError> {
+        u64::decode(value)
+    }
+}
+
+/// An accessor for a `Counter`.
+///
+/// This type provides methods to read and advance the counter.
+pub struct CounterAccess<E, S> {
+    storage: S,
+    phantom: PhantomData<E>,
+}
+
+impl<E, S> CounterAccess<E, S>
+where
+    E: Encoding,
+    u64: EncodableWith<E> + DecodableWith<E>,
+    S: Storage,
+{
+    /// Get the current count. A counter that was never written reads as `0`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Counter;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let counter = Counter::<TestEncoding>::new(0);
+    ///
+    /// assert_eq!(counter.access(&storage).get().unwrap(), 0);
+    /// counter.access(&mut storage).increment().unwrap();
+    /// assert_eq!(counter.access(&storage).get().unwrap(), 1);
+    /// ```
+    pub fn get(&self) -> Result<u64, E::DecodeError> {
+        self.storage
+            .get(&[])
+            .map(|bytes| u64::decode(&bytes))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+impl<E, S> CounterAccess<E, S>
+where
+    E: Encoding,
+    u64: EncodableWith<E> + DecodableWith<E>,
+    S: Storage + StorageMut,
+{
+    /// Increment the counter by one, returning the new count.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Counter;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let counter = Counter::<TestEncoding>::new(0);
+    /// let mut access = counter.access(&mut storage);
+    ///
+    /// assert_eq!(access.increment().unwrap(), 1);
+    /// assert_eq!(access.increment().unwrap(), 2);
+    /// ```
+    pub fn increment(&mut self) -> Result<u64, CounterError<E::DecodeError, E::EncodeError>> {
+        self.add(1)
+    }
+
+    /// Add `n` to the counter, returning the new count.
+    ///
+    /// If the count would overflow, the counter is left untouched and
+    /// [`CounterError::Overflow`] is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Counter, CounterError};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let counter = Counter::<TestEncoding>::new(0);
+    /// let mut access = counter.access(&mut storage);
+    ///
+    /// assert_eq!(access.add(u64::MAX).unwrap(), u64::MAX);
+    /// assert_eq!(access.add(1), Err(CounterError::Overflow));
+    /// assert_eq!(access.get().unwrap(), u64::MAX);
+    /// ```
+    pub fn add(&mut self, n: u64) -> Result<u64, CounterError<E::DecodeError, E::EncodeError>> {
+        let count = self.get().map_err(CounterError::Decode)?;
+        let count = count.checked_add(n).ok_or(CounterError::Overflow)?;
+
+        let bytes = count.encode().map_err(CounterError::Encode)?;
+        self.storage.set(&[], &bytes);
+
+        Ok(count)
+    }
+}
+
+/// An error returned when advancing a [`Counter`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum CounterError<D, E> {
+    /// The stored count couldn't be decoded.
+    #[error("decode error: {0}")]
+    Decode(D),
+    /// The new count couldn't be encoded.
+    #[error("encode error: {0}")]
+    Encode(E),
+    /// The count would overflow a `u64`.
+    #[error("counter overflow")]
+    Overflow,
+}
+
+impl<D: Display, E: Display> crate::error::StoreyError for CounterError<D, E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    use crate::containers::{Item, Map};
+
+    #[test]
+    fn counter() {
+        let mut storage = TestStorage::new();
+
+        let counter = Counter::<TestEncoding>::new(0);
+        let mut access = counter.access(&mut storage);
+
+        assert_eq!(access.get(), Ok(0));
+        assert_eq!(access.increment(), Ok(1));
+        assert_eq!(access.add(0), Ok(1));
+        assert_eq!(access.add(41), Ok(42));
+        assert_eq!(access.increment(), Ok(43));
+
+        // laid out like an item
+        let item = Item::<u64, TestEncoding>::new(0);
+        assert_eq!(item.access(&storage).get(), Ok(Some(43)));
+    }
+
+    #[test]
+    fn overflow() {
+        let mut storage = TestStorage::new();
+
+        let counter = Counter::<TestEncoding>::new(0);
+        let mut access = counter.access(&mut storage);
+
+        access.add(u64::MAX - 1).unwrap();
+        assert_eq!(access.increment(), Ok(u64::MAX));
+        assert_eq!(access.increment(), Err(CounterError::Overflow));
+        assert_eq!(access.add(u64::MAX), Err(CounterError::Overflow));
+        assert_eq!(access.get(), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn in_map() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Counter<TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.entry_mut("foo").increment(), Ok(1));
+        assert_eq!(access.entry_mut("foo").increment(), Ok(2));
+        assert_eq!(access.entry_mut("bar").increment(), Ok(1));
+
+        assert_eq!(access.entry("foo").get(), Ok(2));
+        assert_eq!(access.entry("baz").get(), Ok(0));
+    }
+}
@@ -0,0 +1,601 @@
+use alloc::vec;
+use core::marker::PhantomData;
+
+use thiserror::Error;
+
+use crate::encoding::Encoding;
+use crate::encoding::{DecodableWith, EncodableWith};
+use crate::storage::{Storage, StorageMut};
+use crate::storage::{StorageBranch, StorageRef};
+
+use super::{NonTerminal, Storable};
+
+/// Storage keys for metadata.
+mod meta_keys {
+    /// The index of the first element in the deque.
+    pub const META_HEAD: &[u8] = &[0];
+    /// The index one past the last element in the deque.
+    pub const META_TAIL: &[u8] = &[1];
+}
+
+/// A double-ended queue of values.
+///
+/// The deque keeps track of a `head` and a `tail` index in the metadata namespace. Elements
+/// are stored under their big-endian `u32` index. All operations are `O(1)`.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Deque;
+///
+/// let mut storage = TestStorage::new();
+/// let deque = Deque::<u64, TestEncoding>::new(0);
+/// let mut access = deque.access(&mut storage);
+///
+/// access.push_back(&1337).unwrap();
+/// access.push_back(&42).unwrap();
+///
+/// assert_eq!(access.pop_front().unwrap(), Some(1337));
+/// assert_eq!(access.pop_front().unwrap(), Some(42));
+/// assert_eq!(access.pop_front().unwrap(), None);
+/// ```
+pub struct Deque<T, E> {
+    prefix: u8,
+    phantom: PhantomData<(T, E)>,
+}
+
+impl<T, E> Deque<T, E>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+{
+    /// Create a new deque associated with the given storage prefix.
+    ///
+    /// It is the responsibility of the user to ensure the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    ///
+    /// The key provided here is used as a prefix for all keys the deque itself might generate.
+    pub const fn new(prefix: u8) -> Self {
+        Self {
+            prefix,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquire an accessor for this deque.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// // immutable accessor
+    /// let storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let access = deque.access(&storage);
+    ///
+    /// // mutable accessor
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(&self, storage: S) -> DequeAccess<E, T, StorageBranch<S>> {
+        Self::access_impl(StorageBranch::new(storage, vec![self.prefix]))
+    }
+}
+
+impl<T, E> Storable for Deque<T, E>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+{
+    type Kind = NonTerminal;
+    type Accessor<S> = DequeAccess<E, T, S>;
+    type Key = u32;
+    type KeyDecodeError = DequeIndexDecodeError;
+    type Value = T;
+    type ValueDecodeError = E::DecodeError;
+
+    fn access_impl<S>(storage: S) -> DequeAccess<E, T, S> {
+        DequeAccess {
+            storage,
+            phantom: PhantomData,
+        }
+    }
+
+    fn decode_key(key: &[u8]) -> Result<Self::Key, DequeIndexDecodeError> {
+        let bytes: [u8; 4] = key.try_into().map_err(|_| DequeIndexDecodeError)?;
+
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn decode_value(value: &[u8]) -> Result<Self::Value, Self::ValueDecodeError> {
+        T::decode(value)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+#[error("invalid key length, expected 4 bytes of big-endian u32")]
+pub struct DequeIndexDecodeError;
+
+impl crate::error::StoreyError for DequeIndexDecodeError {}
+
+/// An accessor for a `Deque`.
+///
+/// This type provides methods for interacting with the deque in storage.
+pub struct DequeAccess<E, T, S> {
+    storage: S,
+    phantom: PhantomData<(E, T)>,
+}
+
+impl<E, T, S> DequeAccess<E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage,
+{
+    /// Get the value at the front of the deque without removing it.
+    ///
+    /// Returns `Ok(None)` if the deque is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// assert_eq!(access.front().unwrap(), None);
+    ///
+    /// access.push_back(&1337).unwrap();
+    /// access.push_back(&42).unwrap();
+    ///
+    /// assert_eq!(access.front().unwrap(), Some(1337));
+    /// ```
+    pub fn front(&self) -> Result<Option<T>, GetError<E::DecodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| GetError::InconsistentState)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        self.get_at(head)
+    }
+
+    /// Get the value at the back of the deque without removing it.
+    ///
+    /// Returns `Ok(None)` if the deque is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// assert_eq!(access.back().unwrap(), None);
+    ///
+    /// access.push_back(&1337).unwrap();
+    /// access.push_back(&42).unwrap();
+    ///
+    /// assert_eq!(access.back().unwrap(), Some(42));
+    /// ```
+    pub fn back(&self) -> Result<Option<T>, GetError<E::DecodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| GetError::InconsistentState)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        self.get_at(tail.wrapping_sub(1))
+    }
+
+    /// Get the number of elements in the deque.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// assert_eq!(access.len().unwrap(), 0);
+    ///
+    /// access.push_back(&1337).unwrap();
+    ///
+    /// assert_eq!(access.len().unwrap(), 1);
+    /// ```
+    pub fn len(&self) -> Result<u32, LenError> {
+        let (head, tail) = self.bounds()?;
+
+        Ok(tail.wrapping_sub(head))
+    }
+
+    /// Check if the deque is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// assert_eq!(access.is_empty().unwrap(), true);
+    ///
+    /// access.push_back(&1337).unwrap();
+    ///
+    /// assert_eq!(access.is_empty().unwrap(), false);
+    /// ```
+    pub fn is_empty(&self) -> Result<bool, LenError> {
+        self.len().map(|len| len == 0)
+    }
+
+    fn get_at(&self, index: u32) -> Result<Option<T>, GetError<E::DecodeError>> {
+        let bytes = self
+            .storage
+            .get(&encode_index(index))
+            .ok_or(GetError::InconsistentState)?;
+
+        Ok(Some(T::decode(&bytes)?))
+    }
+
+    /// Read the `(head, tail)` indices from metadata.
+    fn bounds(&self) -> Result<(u32, u32), LenError> {
+        let head = self.read_index(meta_keys::META_HEAD)?;
+        let tail = self.read_index(meta_keys::META_TAIL)?;
+
+        Ok((head, tail))
+    }
+
+    fn read_index(&self, key: &[u8]) -> Result<u32, LenError> {
+        match self.storage.get_meta(key) {
+            Some(bytes) => {
+                let bytes: [u8; 4] = bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| LenError::InconsistentState)?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+impl<E, T, S> DequeAccess<E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: StorageMut + Storage,
+{
+    /// Append a value to the back of the deque.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// access.push_back(&1337).unwrap();
+    /// access.push_back(&42).unwrap();
+    ///
+    /// assert_eq!(access.len().unwrap(), 2);
+    /// assert_eq!(access.front().unwrap(), Some(1337));
+    /// ```
+    pub fn push_back(&mut self, value: &T) -> Result<(), PushError<E::EncodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| PushError::InconsistentState)?;
+
+        if tail.wrapping_sub(head) == u32::MAX {
+            return Err(PushError::Full);
+        }
+
+        let bytes = value.encode()?;
+
+        self.storage.set(&encode_index(tail), &bytes);
+        self.storage
+            .set_meta(meta_keys::META_TAIL, &tail.wrapping_add(1).to_be_bytes());
+
+        Ok(())
+    }
+
+    /// Prepend a value to the front of the deque.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// access.push_front(&1337).unwrap();
+    /// access.push_front(&42).unwrap();
+    ///
+    /// assert_eq!(access.len().unwrap(), 2);
+    /// assert_eq!(access.front().unwrap(), Some(42));
+    /// ```
+    pub fn push_front(&mut self, value: &T) -> Result<(), PushError<E::EncodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| PushError::InconsistentState)?;
+
+        if tail.wrapping_sub(head) == u32::MAX {
+            return Err(PushError::Full);
+        }
+
+        let bytes = value.encode()?;
+        let head = head.wrapping_sub(1);
+
+        self.storage.set(&encode_index(head), &bytes);
+        self.storage
+            .set_meta(meta_keys::META_HEAD, &head.to_be_bytes());
+
+        Ok(())
+    }
+
+    /// Remove and return the value at the front of the deque.
+    ///
+    /// Returns `Ok(None)` if the deque is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// access.push_back(&1337).unwrap();
+    ///
+    /// assert_eq!(access.pop_front().unwrap(), Some(1337));
+    /// assert_eq!(access.pop_front().unwrap(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Result<Option<T>, GetError<E::DecodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| GetError::InconsistentState)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        let value = self.get_at(head)?;
+
+        self.storage.remove(&encode_index(head));
+        self.storage
+            .set_meta(meta_keys::META_HEAD, &head.wrapping_add(1).to_be_bytes());
+
+        Ok(value)
+    }
+
+    /// Remove and return the value at the back of the deque.
+    ///
+    /// Returns `Ok(None)` if the deque is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Deque;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let deque = Deque::<u64, TestEncoding>::new(0);
+    /// let mut access = deque.access(&mut storage);
+    ///
+    /// access.push_back(&1337).unwrap();
+    /// access.push_back(&42).unwrap();
+    ///
+    /// assert_eq!(access.pop_back().unwrap(), Some(42));
+    /// assert_eq!(access.pop_back().unwrap(), Some(1337));
+    /// assert_eq!(access.pop_back().unwrap(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Result<Option<T>, GetError<E::DecodeError>> {
+        let (head, tail) = self.bounds().map_err(|_| GetError::InconsistentState)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        let tail = tail.wrapping_sub(1);
+        let value = self.get_at(tail)?;
+
+        self.storage.remove(&encode_index(tail));
+        self.storage
+            .set_meta(meta_keys::META_TAIL, &tail.to_be_bytes());
+
+        Ok(value)
+    }
+}
+
+fn encode_index(index: u32) -> [u8; 4] {
+    index.to_be_bytes()
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum PushError<E> {
+    #[error("deque is full")]
+    Full,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+impl<E> From<E> for PushError<E> {
+    fn from(e: E) -> Self {
+        PushError::EncodingError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum GetError<E> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("{0}")]
+    DecodeError(E),
+}
+
+impl<E> From<E> for GetError<E> {
+    fn from(e: E) -> Self {
+        GetError::DecodeError(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum LenError {
+    #[error("inconsistent state")]
+    InconsistentState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    #[test]
+    fn fifo() {
+        let mut storage = TestStorage::new();
+
+        let deque = Deque::<u64, TestEncoding>::new(0);
+        let mut access = deque.access(&mut storage);
+
+        access.push_back(&1).unwrap();
+        access.push_back(&2).unwrap();
+        assert_eq!(access.pop_front().unwrap(), Some(1));
+
+        access.push_back(&3).unwrap();
+        assert_eq!(access.len().unwrap(), 2);
+        assert_eq!(access.front().unwrap(), Some(2));
+
+        assert_eq!(access.pop_front().unwrap(), Some(2));
+        assert_eq!(access.pop_front().unwrap(), Some(3));
+        assert_eq!(access.pop_front().unwrap(), None);
+        assert!(access.is_empty().unwrap());
+    }
+
+    #[test]
+    fn both_ends() {
+        let mut storage = TestStorage::new();
+
+        let deque = Deque::<u64, TestEncoding>::new(0);
+        let mut access = deque.access(&mut storage);
+
+        access.push_front(&2).unwrap();
+        access.push_back(&3).unwrap();
+        access.push_front(&1).unwrap();
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(access.front().unwrap(), Some(1));
+        assert_eq!(access.back().unwrap(), Some(3));
+
+        assert_eq!(access.pop_back().unwrap(), Some(3));
+        assert_eq!(access.pop_front().unwrap(), Some(1));
+        assert_eq!(access.front().unwrap(), Some(2));
+        assert_eq!(access.back().unwrap(), Some(2));
+
+        // used as a stack from the back
+        access.push_back(&4).unwrap();
+        assert_eq!(access.pop_back().unwrap(), Some(4));
+        assert_eq!(access.pop_back().unwrap(), Some(2));
+        assert_eq!(access.pop_back().unwrap(), None);
+    }
+
+    #[test]
+    fn empty() {
+        let mut storage = TestStorage::new();
+
+        let deque = Deque::<u64, TestEncoding>::new(0);
+        let mut access = deque.access(&mut storage);
+
+        assert_eq!(access.front().unwrap(), None);
+        assert_eq!(access.back().unwrap(), None);
+        assert_eq!(access.pop_front().unwrap(), None);
+        assert_eq!(access.pop_back().unwrap(), None);
+        assert_eq!(access.len().unwrap(), 0);
+
+        // emptied from the opposite end it was filled from
+        access.push_front(&1).unwrap();
+        assert_eq!(access.pop_back().unwrap(), Some(1));
+        access.push_back(&2).unwrap();
+        assert_eq!(access.pop_front().unwrap(), Some(2));
+
+        assert!(access.is_empty().unwrap());
+        assert_eq!(access.front().unwrap(), None);
+        assert_eq!(access.back().unwrap(), None);
+        assert_eq!(access.pop_back().unwrap(), None);
+    }
+
+    #[test]
+    fn wrap_around() {
+        let mut storage = TestStorage::new();
+
+        let deque = Deque::<u64, TestEncoding>::new(0);
+
+        // pushing to the front of a fresh deque wraps the head below zero
+        let mut access = deque.access(&mut storage);
+        access.push_front(&1).unwrap();
+        access.push_front(&0).unwrap();
+        access.push_back(&2).unwrap();
+        assert_eq!(
+            storage.get_meta(&[0, 0]),
+            Some(u32::MAX.wrapping_sub(1).to_be_bytes().to_vec())
+        );
+
+        let mut access = deque.access(&mut storage);
+        assert_eq!(access.len().unwrap(), 3);
+        assert_eq!(access.pop_front().unwrap(), Some(0));
+        assert_eq!(access.pop_front().unwrap(), Some(1));
+        assert_eq!(access.pop_front().unwrap(), Some(2));
+        assert!(access.is_empty().unwrap());
+
+        // a head that has grown large wraps past the top when pushing to the back
+        let start = u32::MAX - 1;
+        storage.set_meta(&[0, 0], &start.to_be_bytes());
+        storage.set_meta(&[0, 1], &start.to_be_bytes());
+
+        let mut access = deque.access(&mut storage);
+        for i in 0..4 {
+            access.push_back(&i).unwrap();
+        }
+        assert_eq!(access.len().unwrap(), 4);
+        assert_eq!(access.back().unwrap(), Some(3));
+        assert_eq!(access.pop_back().unwrap(), Some(3));
+        assert_eq!(access.pop_front().unwrap(), Some(0));
+        assert_eq!(access.pop_front().unwrap(), Some(1));
+        assert_eq!(access.pop_front().unwrap(), Some(2));
+        assert!(access.is_empty().unwrap());
+    }
+
+    #[test]
+    fn full() {
+        let mut storage = TestStorage::new();
+
+        let deque = Deque::<u64, TestEncoding>::new(0);
+
+        storage.set_meta(&[0, 0], &1u32.to_be_bytes());
+        storage.set_meta(&[0, 1], &0u32.to_be_bytes());
+
+        let mut access = deque.access(&mut storage);
+        assert_eq!(access.len().unwrap(), u32::MAX);
+        assert_eq!(access.push_back(&1), Err(PushError::Full));
+        assert_eq!(access.push_front(&1), Err(PushError::Full));
+    }
+}
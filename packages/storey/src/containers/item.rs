@@ -1,8 +1,13 @@
-use std::marker::PhantomData;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::marker::PhantomData;
 
-use crate::encoding::{DecodableWith, EncodableWith, Encoding};
-use crate::storage::StorageBranch;
+use crate::encoding::{DecodableWith, EncodableWith, Encoding, SizeLimitError, SizeLimited};
+#[cfg(feature = "async")]
+use crate::storage::{AsyncStorage, AsyncStorageMut};
 use crate::storage::{Storage, StorageMut};
+use crate::storage::{StorageBranch, StorageRef};
 
 use super::common::TryGetError;
 use super::{Storable, Terminal};
@@ -61,9 +66,57 @@ where
     /// let mut storage = TestStorage::new();
     /// let item = Item::<u64, TestEncoding>::new(0);
     /// let mut access = item.access(&mut storage);
-    pub fn access<S>(&self, storage: S) -> ItemAccess<E, T, StorageBranch<S>> {
+    pub fn access<S: StorageRef>(&self, storage: S) -> ItemAccess<E, T, StorageBranch<S>> {
         Self::access_impl(StorageBranch::new(storage, vec![self.key]))
     }
+
+    /// Acquire a read-only accessor to the item.
+    ///
+    /// This is [`access`](Self::access) restricted to a shared reference. Calling a
+    /// mutating method on the result is a compile error pointing at that method, and
+    /// passing anything but a reference is caught at the call site.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// let storage = TestStorage::new();
+    /// let access = item.access_ref(&storage);
+    ///
+    /// assert_eq!(access.get().unwrap(), None);
+    /// ```
+    pub fn access_ref<'s, S>(&self, storage: &'s S) -> ItemAccess<E, T, StorageBranch<&'s S>> {
+        self.access(storage)
+    }
+
+    /// Acquire a mutable accessor to the item.
+    ///
+    /// This is [`access`](Self::access) restricted to an exclusive reference.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let mut access = item.access_mut(&mut storage);
+    ///
+    /// access.set(&42).unwrap();
+    /// assert_eq!(access.get().unwrap(), Some(42));
+    /// ```
+    pub fn access_mut<'s, S>(
+        &self,
+        storage: &'s mut S,
+    ) -> ItemAccess<E, T, StorageBranch<&'s mut S>> {
+        self.access(storage)
+    }
 }
 
 impl<T, E> Storable for Item<T, E>
@@ -110,6 +163,42 @@ pub struct ItemAccess<E, T, S> {
     phantom: PhantomData<(E, T)>,
 }
 
+impl<E, T, S> Clone for ItemAccess<E, T, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, T, S> ItemAccess<E, T, S>
+where
+    S: Storage,
+{
+    /// Check whether the item holds a value, without decoding it.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// assert!(!item.access(&storage).has());
+    /// item.access(&mut storage).set(&42).unwrap();
+    /// assert!(item.access(&storage).has());
+    /// ```
+    pub fn has(&self) -> bool {
+        self.storage.has(&[])
+    }
+}
+
 impl<E, T, S> ItemAccess<E, T, S>
 where
     E: Encoding,
@@ -191,6 +280,8 @@ where
     /// Get the value of the item or a provided default.
     ///
     /// Returns the value of the item if it exists, otherwise returns the provided default.
+    /// If the default is just [`T::default()`](Default::default), use
+    /// [`get_or_default`](Self::get_or_default) instead.
     ///
     /// # Example
     /// ```
@@ -208,6 +299,37 @@ where
     }
 }
 
+impl<E, T, S> ItemAccess<E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E> + Default,
+    S: Storage,
+{
+    /// Get the value of the item or [`T::default()`](Default::default).
+    ///
+    /// This is [`get_or`](Self::get_or) with the default taken from the [`Default`]
+    /// implementation of `T`, which is handy for items like configs that have a fixed
+    /// fallback. Like [`get_or`](Self::get_or), nothing is written to storage.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// assert_eq!(item.access(&storage).get_or_default().unwrap(), 0);
+    ///
+    /// item.access(&mut storage).set(&42).unwrap();
+    /// assert_eq!(item.access(&storage).get_or_default().unwrap(), 42);
+    /// ```
+    pub fn get_or_default(&self) -> Result<T, E::DecodeError> {
+        self.get().map(Option::unwrap_or_default)
+    }
+}
+
 impl<E, T, S> ItemAccess<E, T, S>
 where
     E: Encoding,
@@ -229,7 +351,39 @@ where
     /// assert_eq!(item.access(&storage).get().unwrap(), Some(42));
     /// ```
     pub fn set(&mut self, value: &T) -> Result<(), E::EncodeError> {
-        let bytes = value.encode()?;
+        let mut bytes = Vec::new();
+        value.encode_into(&mut bytes)?;
+        self.storage.set(&[], &bytes);
+        Ok(())
+    }
+
+    /// Set the value of the item, unless it encodes to more than `MAX` bytes.
+    ///
+    /// An oversized value is rejected with [`SizeLimitError::TooLarge`] and the item is left
+    /// untouched. See [`SizeLimited`] for more.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    /// use storey::encoding::SizeLimitError;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// item.access(&mut storage).set_limited::<8>(&42).unwrap();
+    /// assert_eq!(
+    ///     item.access(&mut storage).set_limited::<4>(&43),
+    ///     Err(SizeLimitError::TooLarge { size: 8, max: 4 })
+    /// );
+    /// assert_eq!(item.access(&storage).get().unwrap(), Some(42));
+    /// ```
+    pub fn set_limited<const MAX: usize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SizeLimitError<E::EncodeError>> {
+        let bytes = SizeLimited::<E, MAX>::encode(value)?;
         self.storage.set(&[], &bytes);
         Ok(())
     }
@@ -267,6 +421,46 @@ where
         }
     }
 
+    /// Update the value of the item, returning both the old and the new value.
+    ///
+    /// This works like [`update`](Self::update), except the function `f` is given a
+    /// reference to the current value, and the `(old, new)` pair is returned once the
+    /// new value has been persisted. A `None` on either side means the item was (or is now)
+    /// empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    /// let mut access = item.access(&mut storage);
+    ///
+    /// access.set(&42).unwrap();
+    /// let (old, new) = access.update_returning(|value| value.map(|v| v + 1)).unwrap();
+    ///
+    /// assert_eq!(old, Some(42));
+    /// assert_eq!(new, Some(43));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn update_returning<F>(
+        &mut self,
+        f: F,
+    ) -> Result<(Option<T>, Option<T>), UpdateError<E::DecodeError, E::EncodeError>>
+    where
+        F: FnOnce(Option<&T>) -> Option<T>,
+    {
+        let old_value = self.get().map_err(UpdateError::Decode)?;
+        let new_value = f(old_value.as_ref());
+        match &new_value {
+            Some(value) => self.set(value).map_err(UpdateError::Encode)?,
+            None => self.remove(),
+        }
+        Ok((old_value, new_value))
+    }
+
     /// Remove the value of the item.
     ///
     /// # Example
@@ -285,6 +479,137 @@ where
     pub fn remove(&mut self) {
         self.storage.remove(&[]);
     }
+
+    /// Set the value of the item, returning the previous value.
+    ///
+    /// Returns `Ok(None)` if the item was empty. If the previous value can't be decoded,
+    /// nothing is written.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    /// let mut access = item.access(&mut storage);
+    ///
+    /// assert_eq!(access.replace(&42).unwrap(), None);
+    /// assert_eq!(access.replace(&43).unwrap(), Some(42));
+    /// assert_eq!(access.get().unwrap(), Some(43));
+    /// ```
+    pub fn replace(
+        &mut self,
+        value: &T,
+    ) -> Result<Option<T>, ReplaceError<E::DecodeError, E::EncodeError>> {
+        let old_value = self.get().map_err(ReplaceError::Decode)?;
+        self.set(value).map_err(ReplaceError::Encode)?;
+        Ok(old_value)
+    }
+
+    /// Remove the value of the item, returning it.
+    ///
+    /// Returns `Ok(None)` without touching storage if the item doesn't exist. If the
+    /// stored value can't be decoded, the error is returned and the value is kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    /// let mut access = item.access(&mut storage);
+    ///
+    /// access.set(&42).unwrap();
+    /// assert_eq!(access.take().unwrap(), Some(42));
+    /// assert_eq!(access.take().unwrap(), None);
+    /// ```
+    pub fn take(&mut self) -> Result<Option<T>, E::DecodeError> {
+        let value = self.get()?;
+        if value.is_some() {
+            self.remove();
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<E, T, S> ItemAccess<E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: AsyncStorage,
+{
+    /// Get the value of the item from an asynchronous storage.
+    ///
+    /// This is the async counterpart of [`get`](Self::get).
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::async_backend::{block_on, AsyncTestStorage};
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = AsyncTestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// block_on(async {
+    ///     assert_eq!(item.access(&storage).get_async().await.unwrap(), None);
+    ///     item.access(&mut storage).set_async(&42).await.unwrap();
+    ///     assert_eq!(item.access(&storage).get_async().await.unwrap(), Some(42));
+    /// });
+    /// ```
+    pub async fn get_async(&self) -> Result<Option<T>, E::DecodeError> {
+        self.storage
+            .get(&[])
+            .await
+            .map(|bytes| T::decode(&bytes))
+            .transpose()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<E, T, S> ItemAccess<E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: AsyncStorageMut,
+{
+    /// Set the value of the item in an asynchronous storage.
+    ///
+    /// This is the async counterpart of [`set`](Self::set).
+    pub async fn set_async(&mut self, value: &T) -> Result<(), E::EncodeError> {
+        let mut bytes = Vec::new();
+        value.encode_into(&mut bytes)?;
+        self.storage.set(&[], &bytes).await;
+        Ok(())
+    }
+
+    /// Remove the value of the item from an asynchronous storage.
+    ///
+    /// This is the async counterpart of [`remove`](Self::remove).
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::async_backend::{block_on, AsyncTestStorage};
+    /// use storey::containers::Item;
+    ///
+    /// let mut storage = AsyncTestStorage::new();
+    /// let item = Item::<u64, TestEncoding>::new(0);
+    ///
+    /// block_on(async {
+    ///     item.access(&mut storage).set_async(&42).await.unwrap();
+    ///     item.access(&mut storage).remove_async().await;
+    ///     assert_eq!(item.access(&storage).get_async().await.unwrap(), None);
+    /// });
+    /// ```
+    pub async fn remove_async(&mut self) {
+        self.storage.remove(&[]).await;
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
@@ -295,6 +620,19 @@ pub enum UpdateError<D, E> {
     Encode(E),
 }
 
+/// An error returned by [`ItemAccess::replace`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum ReplaceError<D, E> {
+    /// The previous value couldn't be decoded.
+    #[error("decode error: {0}")]
+    Decode(D),
+    /// The new value couldn't be encoded.
+    #[error("encode error: {0}")]
+    Encode(E),
+}
+
+impl<D: Display, E: Display> crate::error::StoreyError for ReplaceError<D, E> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -318,6 +656,63 @@ mod tests {
         assert_eq!(storage.get(&[1]), None);
     }
 
+    #[test]
+    fn set_limited() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<u64, TestEncoding>::new(0);
+        item.access(&mut storage).set_limited::<8>(&42).unwrap();
+        assert_eq!(
+            item.access(&mut storage).set_limited::<7>(&43),
+            Err(SizeLimitError::TooLarge { size: 8, max: 7 })
+        );
+
+        assert_eq!(item.access(&storage).get().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn has() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<u64, TestEncoding>::new(0);
+        assert!(!item.access(&storage).has());
+
+        item.access(&mut storage).set(&0).unwrap();
+        assert!(item.access(&storage).has());
+        assert!(!Item::<u64, TestEncoding>::new(1).access(&storage).has());
+
+        item.access(&mut storage).remove();
+        assert!(!item.access(&storage).has());
+    }
+
+    #[test]
+    fn take() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<u64, TestEncoding>::new(0);
+        let mut access = item.access(&mut storage);
+
+        assert_eq!(access.take().unwrap(), None);
+
+        access.set(&42).unwrap();
+        assert_eq!(access.take().unwrap(), Some(42));
+        assert!(!access.has());
+        assert_eq!(storage.get(&[0]), None);
+    }
+
+    #[test]
+    fn replace() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<u64, TestEncoding>::new(0);
+        let mut access = item.access(&mut storage);
+
+        assert_eq!(access.replace(&1).unwrap(), None);
+        assert_eq!(access.replace(&2).unwrap(), Some(1));
+        assert_eq!(access.get().unwrap(), Some(2));
+        assert_eq!(storage.get(&[0]), Some(2u64.to_le_bytes().to_vec()));
+    }
+
     #[test]
     fn update() {
         let mut storage = TestStorage::new();
@@ -333,4 +728,27 @@ mod tests {
         item.access(&mut storage).update(|_| None).unwrap();
         assert_eq!(item.access(&storage).get().unwrap(), None);
     }
+
+    #[test]
+    fn update_returning() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<u64, TestEncoding>::new(0);
+        let mut access = item.access(&mut storage);
+
+        assert_eq!(
+            access.update_returning(|_| Some(42)).unwrap(),
+            (None, Some(42))
+        );
+        assert_eq!(
+            access
+                .update_returning(|value| value.map(|v| v + 1))
+                .unwrap(),
+            (Some(42), Some(43))
+        );
+        assert_eq!(access.get().unwrap(), Some(43));
+
+        assert_eq!(access.update_returning(|_| None).unwrap(), (Some(43), None));
+        assert_eq!(access.get().unwrap(), None);
+    }
 }
@@ -0,0 +1,491 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+
+use thiserror::Error;
+
+use crate::containers::common::{check_layout_version, record_layout_version, Layout, LayoutError};
+use crate::containers::{Item, Terminal};
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::{Storage, StorageBranch, StorageMut, StorageRef};
+
+use super::key_encoding::KeyEncodingT;
+use super::{Key, MapAccess, OwnedKey};
+
+/// Storage keys for metadata.
+mod meta_keys {
+    /// The version of the metadata layout the map was written with.
+    pub const META_VERSION: &[u8] = &[0];
+    /// The number of entries in the map.
+    pub const META_LEN: &[u8] = &[1];
+}
+
+/// A map of values that keeps track of how many entries it holds.
+///
+/// The entries are laid out exactly like those of a [`Map`](super::Map) of
+/// [`Item`]s. On top of that, the number of entries is kept in the metadata namespace, so
+/// [`len`](CountedMapAccess::len) doesn't need to iterate over the keys.
+///
+/// Keeping the count accurate means every [`insert`](CountedMapAccess::insert) and
+/// [`remove`](CountedMapAccess::remove) has to check whether the key is already present,
+/// which costs an extra read. Prefer [`Map`](super::Map) unless the count is needed.
+///
+/// Unlike [`Map`](super::Map), the counted map stores values directly rather than doling out
+/// access to nested containers. Read-only access to the underlying map (e.g. for iteration)
+/// is available through [`as_map`](CountedMapAccess::as_map).
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::CountedMap;
+///
+/// let mut storage = TestStorage::new();
+/// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+/// let mut access = map.access(&mut storage);
+///
+/// access.insert("foo", &1337).unwrap();
+/// access.insert("bar", &42).unwrap();
+/// access.insert("foo", &9001).unwrap();
+///
+/// assert_eq!(access.len().unwrap(), 2);
+/// assert_eq!(access.get("foo").unwrap(), Some(9001));
+/// ```
+pub struct CountedMap<K, T, E> {
+    prefix: u8,
+    phantom: PhantomData<(K, T, E)>,
+}
+
+impl<K, T, E> CountedMap<K, T, E>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+{
+    /// Creates a new counted map with the given prefix.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    ///
+    /// The key provided here is used as a prefix for all keys managed by the counted map.
+    pub const fn new(prefix: u8) -> Self {
+        Self {
+            prefix,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquires an accessor for the counted map.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::CountedMap;
+    ///
+    /// // immutable access
+    /// let storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let access = map.access(&storage);
+    ///
+    /// // mutable access
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(&self, storage: S) -> CountedMapAccess<K, T, E, StorageBranch<S>> {
+        CountedMapAccess {
+            storage: StorageBranch::new(storage, vec![self.prefix]),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// An accessor for a counted map.
+///
+/// The accessor provides methods for interacting with the counted map in storage.
+pub struct CountedMapAccess<K, T, E, S> {
+    storage: S,
+    phantom: PhantomData<(K, T, E)>,
+}
+
+impl<K, T, E, S> CountedMapAccess<K, T, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Get the value associated with the given key.
+    ///
+    /// Returns `Ok(None)` if the entry doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::CountedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// assert_eq!(access.get("bar").unwrap(), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Result<Option<T>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.as_map().entry(key).get()
+    }
+
+    /// Check whether there's an entry under the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.storage.has(&Self::encode_entry_key(key))
+    }
+
+    /// Get the number of entries in the map.
+    ///
+    /// This reads a single metadata entry rather than iterating over the map.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::CountedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// assert_eq!(access.len().unwrap(), 0);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// access.insert("bar", &42).unwrap();
+    /// access.remove("foo").unwrap();
+    ///
+    /// assert_eq!(access.len().unwrap(), 1);
+    /// ```
+    pub fn len(&self) -> Result<u32, LenError> {
+        check_layout_version(&self.storage, meta_keys::META_VERSION, meta_keys::META_LEN)?;
+
+        self.read_len().ok_or(LenError::InconsistentState)
+    }
+
+    /// Check whether the map is empty.
+    pub fn is_empty(&self) -> Result<bool, LenError> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Returns a read-only [`MapAccess`] over the entries.
+    ///
+    /// This gives access to everything a [`Map`](super::Map) of [`Item`]s offers for reading,
+    /// like iteration. Writes have to go through the counted map so that the count is kept
+    /// up to date.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{CountedMap, IterableAccessor as _};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// access.insert("bar", &42).unwrap();
+    ///
+    /// assert_eq!(
+    ///     access.as_map().pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+    ///     vec![(("bar".to_string(), ()), 42), (("foo".to_string(), ()), 1337)]
+    /// );
+    /// ```
+    pub fn as_map(&self) -> MapAccess<K, Item<T, E>, StorageBranch<&S>> {
+        MapAccess {
+            storage: StorageBranch::new(&self.storage, vec![]),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Read the entry count. Returns `None` if the stored count is malformed.
+    fn read_len(&self) -> Option<u32> {
+        match self.storage.get_meta(meta_keys::META_LEN) {
+            None => Some(0),
+            Some(bytes) => Some(u32::from_be_bytes(bytes.as_slice().try_into().ok()?)),
+        }
+    }
+
+    fn encode_entry_key<Q>(key: &Q) -> Vec<u8>
+    where
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        MapAccess::<K, Item<T, E>, S>::encode_entry_key(key)
+    }
+}
+
+impl<K, T, E, S> CountedMapAccess<K, T, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage + StorageMut,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Insert a value under the given key, overwriting any existing value.
+    ///
+    /// The entry count goes up only if there was no value under the key yet.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::CountedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// ```
+    pub fn insert<Q>(&mut self, key: &Q, value: &T) -> Result<(), InsertError<E::EncodeError>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let layout =
+            check_layout_version(&self.storage, meta_keys::META_VERSION, meta_keys::META_LEN)
+                .map_err(InsertError::from_layout)?;
+
+        let key = Self::encode_entry_key(key);
+        let bytes = value.encode().map_err(InsertError::EncodingError)?;
+
+        let len = if self.storage.has(&key) {
+            None
+        } else {
+            let len = self.read_len().ok_or(InsertError::InconsistentState)?;
+            Some(len.checked_add(1).ok_or(InsertError::CountOverflow)?)
+        };
+
+        if layout != Layout::Current {
+            record_layout_version(&mut self.storage, meta_keys::META_VERSION);
+        }
+        if let Some(len) = len {
+            self.storage
+                .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+        }
+
+        self.storage.set(&key, &bytes);
+
+        Ok(())
+    }
+
+    /// Remove the entry associated with the given key.
+    ///
+    /// Returns `true` if there was an entry to remove. The entry count goes down only in
+    /// that case.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::CountedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = CountedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// assert_eq!(access.remove("foo").unwrap(), true);
+    /// assert_eq!(access.remove("foo").unwrap(), false);
+    /// assert_eq!(access.get("foo").unwrap(), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Result<bool, RemoveError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let key = Self::encode_entry_key(key);
+
+        if !self.storage.has(&key) {
+            return Ok(false);
+        }
+
+        check_layout_version(&self.storage, meta_keys::META_VERSION, meta_keys::META_LEN)?;
+
+        let len = self.read_len().ok_or(RemoveError::InconsistentState)?;
+        let len = len.checked_sub(1).ok_or(RemoveError::InconsistentState)?;
+        self.storage
+            .set_meta(meta_keys::META_LEN, &len.to_be_bytes());
+        self.storage.remove(&key);
+
+        Ok(true)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum InsertError<E> {
+    #[error("entry count overflow")]
+    CountOverflow,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+impl<E> InsertError<E> {
+    fn from_layout(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => InsertError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => InsertError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl<E: core::fmt::Display> crate::error::StoreyError for InsertError<E> {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum RemoveError {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for RemoveError {
+    fn from(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => RemoveError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => RemoveError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl crate::error::StoreyError for RemoveError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum LenError {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("incompatible layout version: {0}")]
+    IncompatibleLayout(u8),
+}
+
+impl From<LayoutError> for LenError {
+    fn from(e: LayoutError) -> Self {
+        match e {
+            LayoutError::InconsistentState => LenError::InconsistentState,
+            LayoutError::IncompatibleLayout(v) => LenError::IncompatibleLayout(v),
+        }
+    }
+}
+
+impl crate::error::StoreyError for LenError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    use crate::containers::Map;
+
+    #[test]
+    fn len() {
+        let mut storage = TestStorage::new();
+
+        let map = CountedMap::<String, u64, TestEncoding>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.len(), Ok(0));
+        assert_eq!(access.is_empty(), Ok(true));
+
+        access.insert("foo", &1).unwrap();
+        access.insert("bar", &2).unwrap();
+        access.insert("baz", &3).unwrap();
+        // overwriting doesn't change the count
+        access.insert("foo", &4).unwrap();
+        assert_eq!(access.len(), Ok(3));
+
+        assert_eq!(access.remove("bar"), Ok(true));
+        // removing a missing key doesn't change the count
+        assert_eq!(access.remove("bar"), Ok(false));
+        assert_eq!(access.remove("qux"), Ok(false));
+        assert_eq!(access.len(), Ok(2));
+        assert_eq!(access.is_empty(), Ok(false));
+
+        assert!(access.contains_key("foo"));
+        assert!(!access.contains_key("bar"));
+        assert_eq!(access.get("foo"), Ok(Some(4)));
+
+        assert_eq!(access.remove("foo"), Ok(true));
+        assert_eq!(access.remove("baz"), Ok(true));
+        assert_eq!(access.len(), Ok(0));
+    }
+
+    #[test]
+    fn map_layout() {
+        let mut storage = TestStorage::new();
+
+        let counted = CountedMap::<u32, u64, TestEncoding>::new(0);
+        let mut access = counted.access(&mut storage);
+        access.insert(&1, &1337).unwrap();
+        access.insert(&2, &42).unwrap();
+
+        // the entries can be read back through a plain map with the same prefix
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let access = map.access(&storage);
+        assert_eq!(access.entry(&1).get(), Ok(Some(1337)));
+        assert_eq!(access.entry(&2).get(), Ok(Some(42)));
+    }
+
+    #[test]
+    fn incompatible_layout() {
+        let mut storage = TestStorage::new();
+
+        let map = CountedMap::<String, u64, TestEncoding>::new(0);
+        let mut access = map.access(&mut storage);
+        access.insert("foo", &1).unwrap();
+
+        storage.set_meta(&[0, 0], &[2]);
+
+        let mut access = map.access(&mut storage);
+        assert_eq!(access.len(), Err(LenError::IncompatibleLayout(2)));
+        assert_eq!(
+            access.insert("bar", &2),
+            Err(InsertError::IncompatibleLayout(2))
+        );
+        assert_eq!(
+            access.remove("foo"),
+            Err(RemoveError::IncompatibleLayout(2))
+        );
+    }
+
+    #[test]
+    fn failed_insert_records_no_layout_version() {
+        let mut storage = TestStorage::new();
+
+        // seed a saturated count without a layout version
+        storage.set_meta(&[0, 1], &u32::MAX.to_be_bytes());
+
+        let map = CountedMap::<String, u64, TestEncoding>::new(0);
+        let mut access = map.access(&mut storage);
+        assert_eq!(access.insert("foo", &1), Err(InsertError::CountOverflow));
+
+        assert_eq!(access.get("foo"), Ok(None));
+        assert_eq!(storage.get_meta(&[0, 0]), None);
+    }
+}
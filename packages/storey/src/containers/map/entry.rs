@@ -0,0 +1,102 @@
+use crate::containers::ItemAccess;
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::{Storage, StorageBranch, StorageMut};
+
+/// A view into a single entry of a [`Map`](super::Map) of [`Item`](crate::containers::Item)s,
+/// which may or may not hold a value.
+///
+/// This is constructed by [`MapAccess::entry_api`](super::MapAccess::entry_api). The stored
+/// value is read once when the entry is created. Modifications are applied in memory and
+/// written back in a single write by [`or_insert`](Self::or_insert) or
+/// [`or_insert_with`](Self::or_insert_with).
+///
+/// This is similar to `std::collections::hash_map::Entry`.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::{Item, Map};
+///
+/// let mut storage = TestStorage::new();
+/// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+/// let mut access = map.access(&mut storage);
+///
+/// for amount in [10, 5] {
+///     access
+///         .entry_api("alice")
+///         .unwrap()
+///         .and_modify(|balance| *balance += amount)
+///         .or_insert(amount)
+///         .unwrap();
+/// }
+///
+/// assert_eq!(access.entry("alice").get().unwrap(), Some(15));
+/// ```
+pub struct MapEntry<'a, E, T, S> {
+    item: ItemAccess<E, T, StorageBranch<&'a mut S>>,
+    value: Option<T>,
+    modified: bool,
+}
+
+impl<'a, E, T, S> MapEntry<'a, E, T, S>
+where
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage + StorageMut,
+{
+    pub(super) fn new(item: ItemAccess<E, T, StorageBranch<&'a mut S>>, value: Option<T>) -> Self {
+        Self {
+            item,
+            value,
+            modified: false,
+        }
+    }
+
+    /// Returns the current value of the entry, if any, including modifications that
+    /// haven't been written yet.
+    pub fn get(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Apply `f` to the value if there is one. Does nothing for a vacant entry.
+    ///
+    /// The change is written once the entry is finished with
+    /// [`or_insert`](Self::or_insert) or [`or_insert_with`](Self::or_insert_with).
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut T),
+    {
+        if let Some(value) = &mut self.value {
+            f(value);
+            self.modified = true;
+        }
+        self
+    }
+
+    /// Store `default` if the entry is vacant, and return the resulting value.
+    ///
+    /// Storage is only written to if the entry was vacant or modified with
+    /// [`and_modify`](Self::and_modify).
+    pub fn or_insert(self, default: T) -> Result<T, E::EncodeError> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Store the result of `f` if the entry is vacant, and return the resulting value.
+    ///
+    /// Storage is only written to if the entry was vacant or modified with
+    /// [`and_modify`](Self::and_modify).
+    pub fn or_insert_with<F>(mut self, f: F) -> Result<T, E::EncodeError>
+    where
+        F: FnOnce() -> T,
+    {
+        let value = match self.value {
+            Some(value) if !self.modified => return Ok(value),
+            Some(value) => value,
+            None => f(),
+        };
+
+        self.item.set(&value)?;
+        Ok(value)
+    }
+}
@@ -0,0 +1,772 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+
+use thiserror::Error;
+
+use crate::containers::{Item, IterableAccessor as _, NonTerminal, Storable as _, Terminal};
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::{IterableStorage, Storage, StorageBranch, StorageMut, StorageRef};
+
+use super::key_encoding::KeyEncodingT;
+use super::set::{InvalidMember, Set, SetAccess};
+use super::{Key, Map, MapAccess, OwnedKey};
+
+/// A map of values with secondary indexes.
+///
+/// The entries are laid out exactly like those of a [`Map`] of [`Item`]s under `prefix`.
+/// On top of that, every index maps an attribute of the value (the index key) to the
+/// primary keys of the entries with that attribute. Each index lives in its own namespace
+/// under its own prefix, laid out like a `Map<IK, Set<K>>`.
+///
+/// The indexes are described by an [`IndexList`] - a single [`UniqueIndex`] or
+/// [`MultiIndex`], or a tuple of them. [`save`](IndexedMapAccess::save) and
+/// [`remove`](IndexedMapAccess::remove) keep all of them up to date, which costs a read of
+/// the previous value plus a write per index.
+///
+/// Writes have to go through the indexed map for the indexes to stay consistent. Read-only
+/// access to the entries (e.g. for iteration) is available through
+/// [`as_map`](IndexedMapAccess::as_map).
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::map::{IndexedMap, MultiIndex, UniqueIndex};
+///
+/// let indexes = (
+///     UniqueIndex::<u64, String, u64>::new(1, |value| *value),
+///     MultiIndex::<u8, String, u64>::new(2, |value| (value % 2) as u8),
+/// );
+///
+/// let mut storage = TestStorage::new();
+/// let map = IndexedMap::<String, u64, TestEncoding, _>::new(0, indexes);
+/// let mut access = map.access(&mut storage);
+///
+/// access.save("foo", &1337).unwrap();
+/// access.save("bar", &42).unwrap();
+/// access.save("baz", &7).unwrap();
+///
+/// let (by_value, by_parity) = map.indexes();
+///
+/// assert_eq!(
+///     access.get_unique(by_value, &42).unwrap(),
+///     Some(("bar".to_string(), 42))
+/// );
+/// assert_eq!(
+///     access.get_multi(by_parity, &1).unwrap(),
+///     vec![("baz".to_string(), 7), ("foo".to_string(), 1337)]
+/// );
+/// ```
+pub struct IndexedMap<K, T, E, I> {
+    prefix: u8,
+    indexes: I,
+    phantom: PhantomData<(K, T, E)>,
+}
+
+impl<K, T, E, I> IndexedMap<K, T, E, I>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: IndexList<K, T>,
+{
+    /// Creates a new indexed map with the given prefix and indexes.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix, as well as the
+    /// prefixes of the indexes, are unique and do not conflict with other keys in the storage.
+    ///
+    /// The key provided here is used as a prefix for all entries managed by the indexed map.
+    pub const fn new(prefix: u8, indexes: I) -> Self {
+        Self {
+            prefix,
+            indexes,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The indexes of this map, for querying with
+    /// [`get_unique`](IndexedMapAccess::get_unique) and
+    /// [`get_multi`](IndexedMapAccess::get_multi).
+    pub fn indexes(&self) -> &I {
+        &self.indexes
+    }
+
+    /// Acquires an accessor for the indexed map.
+    ///
+    /// Unlike other containers, the accessor is rooted at the top of `storage`, since the
+    /// indexes live under their own prefixes.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::map::{IndexedMap, MultiIndex};
+    ///
+    /// let index = MultiIndex::<u64, String, u64>::new(1, |value| *value);
+    /// let map = IndexedMap::<String, u64, TestEncoding, _>::new(0, index);
+    ///
+    /// // immutable access
+    /// let storage = TestStorage::new();
+    /// let access = map.access(&storage);
+    ///
+    /// // mutable access
+    /// let mut storage = TestStorage::new();
+    /// let mut access = map.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(
+        &self,
+        storage: S,
+    ) -> IndexedMapAccess<'_, K, T, E, I, StorageBranch<S>> {
+        IndexedMapAccess {
+            storage: StorageBranch::new(storage, vec![]),
+            map: self,
+        }
+    }
+}
+
+/// An accessor for an indexed map.
+///
+/// The accessor provides methods for interacting with the indexed map in storage.
+pub struct IndexedMapAccess<'a, K, T, E, I, S> {
+    storage: S,
+    map: &'a IndexedMap<K, T, E, I>,
+}
+
+impl<K, T, E, I, S> IndexedMapAccess<'_, K, T, E, I, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: IndexList<K, T>,
+    S: Storage,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Get the value associated with the given key.
+    ///
+    /// Returns `Ok(None)` if the entry doesn't exist.
+    pub fn get<Q>(&self, key: &Q) -> Result<Option<T>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.as_map().entry(key).get()
+    }
+
+    /// Returns a read-only [`MapAccess`] over the entries.
+    ///
+    /// This gives access to everything a [`Map`] of [`Item`]s offers for reading, like
+    /// iteration. Writes have to go through the indexed map so that the indexes are kept
+    /// up to date.
+    pub fn as_map(&self) -> MapAccess<K, Item<T, E>, StorageBranch<&S>> {
+        MapAccess {
+            storage: StorageBranch::new(&self.storage, vec![self.map.prefix]),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, T, E, I, S> IndexedMapAccess<'_, K, T, E, I, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: IndexList<K, T>,
+    S: Storage + IterableStorage,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Look up the entry with the given key in a unique index.
+    ///
+    /// Returns the primary key along with the value, or `Ok(None)` if no entry has that
+    /// index key.
+    pub fn get_unique<IK, Q>(
+        &self,
+        index: &UniqueIndex<IK, K, T>,
+        key: &Q,
+    ) -> Result<Option<(K, T)>, IndexReadError<E::DecodeError>>
+    where
+        IK: OwnedKey + Borrow<Q>,
+        Q: Key<Kind = IK::Kind> + ?Sized,
+        (IK::Kind, NonTerminal): KeyEncodingT,
+    {
+        let pk = index.access(&self.storage).pk(key)?;
+
+        pk.map(|pk| self.get_indexed(pk)).transpose()
+    }
+
+    /// Look up all entries with the given key in a multi index.
+    ///
+    /// Returns the primary keys along with the values, in primary key order.
+    pub fn get_multi<IK, Q>(
+        &self,
+        index: &MultiIndex<IK, K, T>,
+        key: &Q,
+    ) -> Result<Vec<(K, T)>, IndexReadError<E::DecodeError>>
+    where
+        IK: OwnedKey + Borrow<Q>,
+        Q: Key<Kind = IK::Kind> + ?Sized,
+        (IK::Kind, NonTerminal): KeyEncodingT,
+    {
+        let access = index.access(&self.storage);
+
+        access
+            .pks(key)
+            .keys()
+            .map(|pk| self.get_indexed(pk?))
+            .collect()
+    }
+
+    fn get_indexed(&self, pk: K) -> Result<(K, T), IndexReadError<E::DecodeError>> {
+        match self.get(&pk).map_err(IndexReadError::Decode)? {
+            Some(value) => Ok((pk, value)),
+            None => Err(IndexReadError::InconsistentState),
+        }
+    }
+}
+
+impl<K, T, E, I, S> IndexedMapAccess<'_, K, T, E, I, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    I: IndexList<K, T>,
+    S: Storage + IterableStorage + StorageMut,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Save a value under the given key, overwriting any existing value and updating the
+    /// indexes.
+    ///
+    /// Index entries of the previous value are removed. If a [`UniqueIndex`] already has
+    /// the new index key for a different entry, nothing is written and
+    /// [`SaveError::UniqueViolation`] is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::map::{IndexedMap, SaveError, UniqueIndex};
+    ///
+    /// let index = UniqueIndex::<u64, String, u64>::new(1, |value| *value);
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = IndexedMap::<String, u64, TestEncoding, _>::new(0, index);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.save("foo", &1337).unwrap();
+    /// assert_eq!(access.save("bar", &1337), Err(SaveError::UniqueViolation));
+    ///
+    /// access.save("foo", &42).unwrap();
+    /// access.save("bar", &1337).unwrap();
+    /// assert_eq!(
+    ///     access.get_unique(map.indexes(), &1337).unwrap(),
+    ///     Some(("bar".to_string(), 1337))
+    /// );
+    /// ```
+    pub fn save<Q>(
+        &mut self,
+        key: &Q,
+        value: &T,
+    ) -> Result<(), SaveError<E::DecodeError, E::EncodeError>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let bytes = value.encode().map_err(SaveError::Encode)?;
+        let old = self.get(key).map_err(SaveError::Decode)?;
+
+        let indexes = &self.map.indexes;
+        if !indexes.check(&self.storage, key, value) {
+            return Err(SaveError::UniqueViolation);
+        }
+
+        if let Some(old) = old {
+            indexes.remove(&mut self.storage, key, &old);
+        }
+        indexes.insert(&mut self.storage, key, value);
+
+        let entry_key = MapAccess::<K, Item<T, E>, S>::encode_entry_key(key);
+        StorageBranch::new(&mut self.storage, vec![self.map.prefix]).set(&entry_key, &bytes);
+
+        Ok(())
+    }
+
+    /// Remove the entry associated with the given key, along with its index entries.
+    ///
+    /// Returns `true` if there was an entry to remove.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::map::{IndexedMap, MultiIndex};
+    ///
+    /// let index = MultiIndex::<u64, String, u64>::new(1, |value| *value);
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = IndexedMap::<String, u64, TestEncoding, _>::new(0, index);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.save("foo", &1337).unwrap();
+    /// assert_eq!(access.remove("foo").unwrap(), true);
+    /// assert_eq!(access.remove("foo").unwrap(), false);
+    /// assert_eq!(access.get_multi(map.indexes(), &1337).unwrap(), vec![]);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Result<bool, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let Some(old) = self.get(key)? else {
+            return Ok(false);
+        };
+
+        self.map.indexes.remove(&mut self.storage, key, &old);
+
+        let entry_key = MapAccess::<K, Item<T, E>, S>::encode_entry_key(key);
+        StorageBranch::new(&mut self.storage, vec![self.map.prefix]).remove(&entry_key);
+
+        Ok(true)
+    }
+}
+
+/// A set of secondary indexes of an [`IndexedMap`].
+///
+/// This is implemented for [`UniqueIndex`], [`MultiIndex`] and tuples of up to four index
+/// lists. The `storage` passed to the methods is rooted at the top of the storage the
+/// indexed map is accessed on.
+pub trait IndexList<K: Key, T> {
+    /// Check whether `value` can be indexed under the primary key `pk` without violating
+    /// any constraint.
+    fn check<S, Q>(&self, storage: &S, pk: &Q, value: &T) -> bool
+    where
+        S: Storage + IterableStorage,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized;
+
+    /// Add the index entries of `value`, stored under the primary key `pk`.
+    fn insert<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized;
+
+    /// Remove the index entries of `value`, stored under the primary key `pk`.
+    fn remove<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized;
+}
+
+/// An index that maps every index key to at most one entry.
+///
+/// The index key is computed from the value by the function passed to
+/// [`new`](UniqueIndex::new). Saving an entry whose index key is already taken by another
+/// entry fails with [`SaveError::UniqueViolation`].
+pub struct UniqueIndex<IK, K, T> {
+    prefix: u8,
+    index: fn(&T) -> IK,
+    phantom: PhantomData<K>,
+}
+
+impl<IK, K, T> UniqueIndex<IK, K, T>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    /// Creates a new unique index with the given prefix, computing index keys with `index`.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    pub const fn new(prefix: u8, index: fn(&T) -> IK) -> Self {
+        Self {
+            prefix,
+            index,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquires an accessor for the index.
+    pub fn access<S: StorageRef>(&self, storage: S) -> UniqueIndexAccess<IK, K, StorageBranch<S>> {
+        UniqueIndexAccess {
+            map: Map::<IK, Set<K>>::access_impl(StorageBranch::new(storage, vec![self.prefix])),
+        }
+    }
+}
+
+impl<IK, K, T> IndexList<K, T> for UniqueIndex<IK, K, T>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    fn check<S, Q>(&self, storage: &S, pk: &Q, value: &T) -> bool
+    where
+        S: Storage + IterableStorage,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let access = self.access(storage).map;
+        let key = (self.index)(value);
+
+        // the set under an index key holds at most one primary key
+        access.try_entry(&key).is_none() || access.entry(&key).contains(pk)
+    }
+
+    fn insert<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let mut access = self.access(storage).map;
+        access.entry_mut(&(self.index)(value)).insert(pk);
+    }
+
+    fn remove<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let mut access = self.access(storage).map;
+        access.entry_mut(&(self.index)(value)).remove(pk);
+    }
+}
+
+/// An accessor for a [`UniqueIndex`].
+pub struct UniqueIndexAccess<IK, K, S> {
+    map: MapAccess<IK, Set<K>, S>,
+}
+
+impl<IK, K, S> UniqueIndexAccess<IK, K, S>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    S: IterableStorage,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    /// Get the primary key of the entry with the given index key.
+    pub fn pk<Q>(&self, key: &Q) -> Result<Option<K>, InvalidMember>
+    where
+        IK: Borrow<Q>,
+        Q: Key<Kind = IK::Kind> + ?Sized,
+    {
+        self.map.entry(key).keys().next().transpose()
+    }
+
+    /// Returns the underlying [`MapAccess`], mapping index keys to sets of primary keys.
+    pub fn as_map(&self) -> &MapAccess<IK, Set<K>, S> {
+        &self.map
+    }
+}
+
+/// An index that maps every index key to any number of entries.
+///
+/// The index key is computed from the value by the function passed to
+/// [`new`](MultiIndex::new).
+pub struct MultiIndex<IK, K, T> {
+    prefix: u8,
+    index: fn(&T) -> IK,
+    phantom: PhantomData<K>,
+}
+
+impl<IK, K, T> MultiIndex<IK, K, T>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    /// Creates a new multi index with the given prefix, computing index keys with `index`.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    pub const fn new(prefix: u8, index: fn(&T) -> IK) -> Self {
+        Self {
+            prefix,
+            index,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquires an accessor for the index.
+    pub fn access<S: StorageRef>(&self, storage: S) -> MultiIndexAccess<IK, K, StorageBranch<S>> {
+        MultiIndexAccess {
+            map: Map::<IK, Set<K>>::access_impl(StorageBranch::new(storage, vec![self.prefix])),
+        }
+    }
+}
+
+impl<IK, K, T> IndexList<K, T> for MultiIndex<IK, K, T>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    fn check<S, Q>(&self, _storage: &S, _pk: &Q, _value: &T) -> bool
+    where
+        S: Storage + IterableStorage,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        true
+    }
+
+    fn insert<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let mut access = self.access(storage).map;
+        access.entry_mut(&(self.index)(value)).insert(pk);
+    }
+
+    fn remove<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+    where
+        S: StorageMut,
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let mut access = self.access(storage).map;
+        access.entry_mut(&(self.index)(value)).remove(pk);
+    }
+}
+
+/// An accessor for a [`MultiIndex`].
+pub struct MultiIndexAccess<IK, K, S> {
+    map: MapAccess<IK, Set<K>, S>,
+}
+
+impl<IK, K, S> MultiIndexAccess<IK, K, S>
+where
+    IK: OwnedKey,
+    K: OwnedKey,
+    (IK::Kind, NonTerminal): KeyEncodingT,
+{
+    /// Returns the set of primary keys of the entries with the given index key.
+    pub fn pks<Q>(&self, key: &Q) -> SetAccess<K, StorageBranch<&S>>
+    where
+        IK: Borrow<Q>,
+        Q: Key<Kind = IK::Kind> + ?Sized,
+    {
+        self.map.entry(key)
+    }
+
+    /// Returns the underlying [`MapAccess`], mapping index keys to sets of primary keys.
+    pub fn as_map(&self) -> &MapAccess<IK, Set<K>, S> {
+        &self.map
+    }
+}
+
+macro_rules! impl_index_list {
+    ($($name:ident $idx:tt),+) => {
+        impl<K: Key, T, $($name: IndexList<K, T>),+> IndexList<K, T> for ($($name,)+) {
+            fn check<S, Q>(&self, storage: &S, pk: &Q, value: &T) -> bool
+            where
+                S: Storage + IterableStorage,
+                K: Borrow<Q>,
+                Q: Key<Kind = K::Kind> + ?Sized,
+            {
+                $(self.$idx.check(storage, pk, value))&&+
+            }
+
+            fn insert<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+            where
+                S: StorageMut,
+                K: Borrow<Q>,
+                Q: Key<Kind = K::Kind> + ?Sized,
+            {
+                $(self.$idx.insert(storage, pk, value);)+
+            }
+
+            fn remove<S, Q>(&self, storage: &mut S, pk: &Q, value: &T)
+            where
+                S: StorageMut,
+                K: Borrow<Q>,
+                Q: Key<Kind = K::Kind> + ?Sized,
+            {
+                $(self.$idx.remove(storage, pk, value);)+
+            }
+        }
+    };
+}
+
+impl_index_list!(A 0);
+impl_index_list!(A 0, B 1);
+impl_index_list!(A 0, B 1, C 2);
+impl_index_list!(A 0, B 1, C 2, D 3);
+
+/// An error returned by [`IndexedMapAccess::save`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum SaveError<D, E> {
+    /// The previous value couldn't be decoded, so its index entries couldn't be removed.
+    #[error("decode error: {0}")]
+    Decode(D),
+    /// The new value couldn't be encoded.
+    #[error("encode error: {0}")]
+    Encode(E),
+    /// A unique index already has the index key for a different entry.
+    #[error("unique index violation")]
+    UniqueViolation,
+}
+
+impl<D, E> crate::error::StoreyError for SaveError<D, E>
+where
+    D: core::fmt::Display,
+    E: core::fmt::Display,
+{
+}
+
+/// An error returned when looking up entries through an index.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum IndexReadError<D> {
+    /// A primary key stored in the index couldn't be decoded.
+    #[error("invalid primary key in index")]
+    InvalidPrimaryKey,
+    /// The index points at an entry that doesn't exist.
+    #[error("inconsistent state")]
+    InconsistentState,
+    /// The value couldn't be decoded.
+    #[error("decode error: {0}")]
+    Decode(D),
+}
+
+impl<D> From<InvalidMember> for IndexReadError<D> {
+    fn from(_: InvalidMember) -> Self {
+        IndexReadError::InvalidPrimaryKey
+    }
+}
+
+impl<D: core::fmt::Display> crate::error::StoreyError for IndexReadError<D> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    type Indexes = (UniqueIndex<u64, String, u64>, MultiIndex<u8, String, u64>);
+
+    fn indexed_map() -> IndexedMap<String, u64, TestEncoding, Indexes> {
+        IndexedMap::new(
+            0,
+            (
+                UniqueIndex::new(1, |value| *value),
+                MultiIndex::new(2, |value| (value % 2) as u8),
+            ),
+        )
+    }
+
+    fn pairs(pairs: &[(&str, u64)]) -> Vec<(String, u64)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn overwrite_updates_indexes() {
+        let mut storage = TestStorage::new();
+
+        let map = indexed_map();
+        let (by_value, by_parity) = map.indexes();
+        let mut access = map.access(&mut storage);
+
+        access.save("foo", &1).unwrap();
+        access.save("bar", &3).unwrap();
+        access.save("foo", &2).unwrap();
+
+        assert_eq!(access.get("foo"), Ok(Some(2)));
+        assert_eq!(access.get_unique(by_value, &1), Ok(None));
+        assert_eq!(
+            access.get_unique(by_value, &2),
+            Ok(Some(("foo".to_string(), 2)))
+        );
+        assert_eq!(access.get_multi(by_parity, &0), Ok(pairs(&[("foo", 2)])));
+        assert_eq!(access.get_multi(by_parity, &1), Ok(pairs(&[("bar", 3)])));
+
+        // saving the same value again keeps the index entries
+        access.save("foo", &2).unwrap();
+        assert_eq!(
+            access.get_unique(by_value, &2),
+            Ok(Some(("foo".to_string(), 2)))
+        );
+        assert_eq!(access.get_multi(by_parity, &0), Ok(pairs(&[("foo", 2)])));
+    }
+
+    #[test]
+    fn unique_violation() {
+        let mut storage = TestStorage::new();
+
+        let map = indexed_map();
+        let (by_value, by_parity) = map.indexes();
+        let mut access = map.access(&mut storage);
+
+        access.save("foo", &1).unwrap();
+        access.save("bar", &3).unwrap();
+
+        assert_eq!(access.save("bar", &1), Err(SaveError::UniqueViolation));
+        assert_eq!(access.save("baz", &1), Err(SaveError::UniqueViolation));
+
+        // nothing was written
+        assert_eq!(access.get("bar"), Ok(Some(3)));
+        assert_eq!(access.get("baz"), Ok(None));
+        assert_eq!(
+            access.get_unique(by_value, &1),
+            Ok(Some(("foo".to_string(), 1)))
+        );
+        assert_eq!(
+            access.get_unique(by_value, &3),
+            Ok(Some(("bar".to_string(), 3)))
+        );
+        assert_eq!(
+            access.get_multi(by_parity, &1),
+            Ok(pairs(&[("bar", 3), ("foo", 1)]))
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut storage = TestStorage::new();
+
+        let map = indexed_map();
+        let (by_value, by_parity) = map.indexes();
+        let mut access = map.access(&mut storage);
+
+        access.save("foo", &1).unwrap();
+        access.save("bar", &3).unwrap();
+
+        assert_eq!(access.remove("foo"), Ok(true));
+        assert_eq!(access.remove("foo"), Ok(false));
+
+        assert_eq!(access.get("foo"), Ok(None));
+        assert_eq!(access.get_unique(by_value, &1), Ok(None));
+        assert_eq!(access.get_multi(by_parity, &1), Ok(pairs(&[("bar", 3)])));
+
+        // the value can be indexed again under a different key
+        access.save("baz", &1).unwrap();
+        assert_eq!(
+            access.get_unique(by_value, &1),
+            Ok(Some(("baz".to_string(), 1)))
+        );
+    }
+
+    #[test]
+    fn layout() {
+        let mut storage = TestStorage::new();
+
+        let map = indexed_map();
+        map.access(&mut storage).save("foo", &3).unwrap();
+
+        // the entries and indexes can be read back through plain containers
+        let entries = Map::<String, Item<u64, TestEncoding>>::new(0);
+        assert_eq!(entries.access(&storage).entry("foo").get(), Ok(Some(3)));
+
+        let by_value = Map::<u64, Set<String>>::new(1);
+        assert!(by_value.access(&storage).entry(&3).contains("foo"));
+
+        let by_parity = Map::<u8, Set<String>>::new(2);
+        assert!(by_parity.access(&storage).entry(&1).contains("foo"));
+    }
+}
@@ -1,3 +1,11 @@
+#[cfg(feature = "derive")]
+pub use storey_macros::{Key, OwnedKey};
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 /// A key that can be used with a [`Map`](super::Map).
 pub trait Key {
     /// The kind of key, meaning either fixed size or dynamic size.
@@ -10,7 +18,9 @@ pub trait Key {
 /// An owned key that can be used with a [`Map`](super::Map).
 pub trait OwnedKey: Key {
     /// The error type that can occur when decoding the key.
-    type Error;
+    ///
+    /// It's reported as part of container key decoding errors, so it needs to be displayable.
+    type Error: core::fmt::Display;
 
     /// Decode the key from a byte slice.
     fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
@@ -56,7 +66,7 @@ impl OwnedKey for String {
     where
         Self: Sized,
     {
-        std::str::from_utf8(bytes)
+        core::str::from_utf8(bytes)
             .map(String::from)
             .map_err(|_| InvalidUtf8)
     }
@@ -69,7 +79,7 @@ impl OwnedKey for Box<str> {
     where
         Self: Sized,
     {
-        std::str::from_utf8(bytes)
+        core::str::from_utf8(bytes)
             .map(Box::from)
             .map_err(|_| InvalidUtf8)
     }
@@ -108,7 +118,7 @@ impl<const N: usize> Key for [u8; N] {
 }
 
 impl OwnedKey for Vec<u8> {
-    type Error = ();
+    type Error = core::convert::Infallible;
 
     fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
     where
@@ -119,7 +129,7 @@ impl OwnedKey for Vec<u8> {
 }
 
 impl OwnedKey for Box<[u8]> {
-    type Error = ();
+    type Error = core::convert::Infallible;
 
     fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
     where
@@ -129,6 +139,57 @@ impl OwnedKey for Box<[u8]> {
     }
 }
 
+/// Paths are encoded as the bytes of the underlying OS string, so keys are ordered by
+/// byte order rather than by path components.
+///
+/// On Unix, paths are arbitrary byte sequences and round-trip losslessly. On other
+/// platforms, the encoding is only portable for valid UTF-8 paths - paths that aren't
+/// valid Unicode are still encoded, but fail to decode with [`InvalidUtf8`].
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+impl Key for std::path::Path {
+    type Kind = DynamicKey;
+
+    fn encode(&self) -> Vec<u8> {
+        self.as_os_str().as_encoded_bytes().to_vec()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Key for std::path::PathBuf {
+    type Kind = DynamicKey;
+
+    fn encode(&self) -> Vec<u8> {
+        self.as_path().encode()
+    }
+}
+
+#[cfg(feature = "std")]
+impl OwnedKey for std::path::PathBuf {
+    type Error = InvalidUtf8;
+
+    #[cfg(unix)]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        Ok(std::ffi::OsStr::from_bytes(bytes).into())
+    }
+
+    #[cfg(not(unix))]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        core::str::from_utf8(bytes)
+            .map(Self::from)
+            .map_err(|_| InvalidUtf8)
+    }
+}
+
 /// An error type for decoding arrays.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
 pub enum ArrayDecodeError {
@@ -155,6 +216,205 @@ impl<const N: usize> OwnedKey for [u8; N] {
     }
 }
 
+/// An error type for decoding [`Result`] keys.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum ResultKeyDecodeError<A, B> {
+    #[error("empty key, expected a tag byte")]
+    EmptyKey,
+    #[error("invalid tag byte: {0}")]
+    InvalidTag(u8),
+    #[error("failed to decode `Ok` key: {0}")]
+    Ok(A),
+    #[error("failed to decode `Err` key: {0}")]
+    Err(B),
+}
+
+impl<A: core::fmt::Display, B: core::fmt::Display> crate::error::StoreyError
+    for ResultKeyDecodeError<A, B>
+{
+}
+
+/// A [`Result`] key is encoded as a tag byte (`0` for `Ok`, `1` for `Err`) followed by the
+/// encoding of the inner key. This means all `Ok` keys sort before all `Err` keys.
+impl<A, B> Key for Result<A, B>
+where
+    A: Key,
+    B: Key,
+{
+    type Kind = DynamicKey;
+
+    fn encode(&self) -> Vec<u8> {
+        let (tag, inner) = match self {
+            Ok(a) => (0, a.encode()),
+            Err(b) => (1, b.encode()),
+        };
+
+        let mut result = Vec::with_capacity(inner.len() + 1);
+        result.push(tag);
+        result.extend_from_slice(&inner);
+        result
+    }
+}
+
+impl<A, B> OwnedKey for Result<A, B>
+where
+    A: OwnedKey,
+    B: OwnedKey,
+{
+    type Error = ResultKeyDecodeError<A::Error, B::Error>;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        match bytes.split_first() {
+            Some((0, rest)) => A::from_bytes(rest)
+                .map(Ok)
+                .map_err(ResultKeyDecodeError::Ok),
+            Some((1, rest)) => B::from_bytes(rest)
+                .map(Err)
+                .map_err(ResultKeyDecodeError::Err),
+            Some((tag, _)) => Err(ResultKeyDecodeError::InvalidTag(*tag)),
+            None => Err(ResultKeyDecodeError::EmptyKey),
+        }
+    }
+}
+
+/// An error type for decoding [`Option`] keys.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum OptionKeyDecodeError<A> {
+    #[error("empty key, expected a tag byte")]
+    EmptyKey,
+    #[error("invalid tag byte: {0}")]
+    InvalidTag(u8),
+    #[error("unexpected bytes after `None` tag")]
+    TrailingBytes,
+    #[error("failed to decode `Some` key: {0}")]
+    Some(A),
+}
+
+impl<A: core::fmt::Display> crate::error::StoreyError for OptionKeyDecodeError<A> {}
+
+/// An [`Option`] key is encoded as a tag byte (`0` for `None`, `1` for `Some`), followed by
+/// the encoding of the inner key for `Some`. This means `None` sorts before all `Some` keys.
+impl<A> Key for Option<A>
+where
+    A: Key,
+{
+    type Kind = DynamicKey;
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            None => vec![0],
+            Some(a) => {
+                let inner = a.encode();
+
+                let mut result = Vec::with_capacity(inner.len() + 1);
+                result.push(1);
+                result.extend_from_slice(&inner);
+                result
+            }
+        }
+    }
+}
+
+impl<A> OwnedKey for Option<A>
+where
+    A: OwnedKey,
+{
+    type Error = OptionKeyDecodeError<A::Error>;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        match bytes.split_first() {
+            Some((0, [])) => Ok(None),
+            Some((0, _)) => Err(OptionKeyDecodeError::TrailingBytes),
+            Some((1, rest)) => A::from_bytes(rest)
+                .map(Some)
+                .map_err(OptionKeyDecodeError::Some),
+            Some((tag, _)) => Err(OptionKeyDecodeError::InvalidTag(*tag)),
+            None => Err(OptionKeyDecodeError::EmptyKey),
+        }
+    }
+}
+
+/// A [`Reverse`](std::cmp::Reverse) key is encoded as the inner key with every byte
+/// complemented, so keys sort in descending order of the inner key and plain range
+/// iteration walks them from the largest to the smallest.
+///
+/// This is only implemented for fixed-size keys. With keys of varying length, a key that's
+/// a prefix of another would still sort first, so complementing the bytes wouldn't reverse
+/// the order.
+impl<A, const N: usize> Key for core::cmp::Reverse<A>
+where
+    A: Key<Kind = FixedSizeKey<N>>,
+{
+    type Kind = FixedSizeKey<N>;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut result = self.0.encode();
+        for byte in &mut result {
+            *byte = !*byte;
+        }
+        result
+    }
+}
+
+impl<A, const N: usize> OwnedKey for core::cmp::Reverse<A>
+where
+    A: OwnedKey<Kind = FixedSizeKey<N>>,
+{
+    type Error = A::Error;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let inner: Vec<u8> = bytes.iter().map(|byte| !byte).collect();
+        A::from_bytes(&inner).map(core::cmp::Reverse)
+    }
+}
+
+/// An error type for decoding keys implemented with `#[derive(OwnedKey)]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum StructKeyDecodeError {
+    #[error("key too short")]
+    KeyTooShort,
+    #[error("failed to decode key field `{0}`")]
+    Field(&'static str),
+}
+
+impl crate::error::StoreyError for StructKeyDecodeError {}
+
+/// An error type for decoding enum keys implemented with `#[derive(OwnedKey)]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum EnumKeyDecodeError {
+    #[error("invalid key length, expected 1 byte")]
+    InvalidLength,
+    #[error("invalid variant index: {0}")]
+    InvalidVariant(u8),
+}
+
+impl crate::error::StoreyError for EnumKeyDecodeError {}
+
+/// Support code for the `Key` and `OwnedKey` derive macros. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    use alloc::vec::Vec;
+
+    use super::Key;
+
+    pub fn push_member<K: Key>(result: &mut Vec<u8>, member: &K) {
+        super::push_member(result, member)
+    }
+
+    pub fn split_member<K: Key>(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+        super::split_member::<K>(bytes)
+    }
+}
+
 /// A trait specifying the kind of key.
 ///
 /// There are two kinds of keys: fixed-size keys and dynamic keys, which are
@@ -174,10 +434,209 @@ impl<const L: usize> KeyKind for FixedSizeKey<L> {}
 impl KeyKind for DynamicKey {}
 
 mod sealed {
-    pub trait KeyKindSeal {}
+    pub trait KeyKindSeal {
+        /// The size of the key in bytes, if it's fixed.
+        const FIXED_SIZE: Option<usize>;
+    }
+
+    impl<const L: usize> KeyKindSeal for super::FixedSizeKey<L> {
+        const FIXED_SIZE: Option<usize> = Some(L);
+    }
+    impl KeyKindSeal for super::DynamicKey {
+        const FIXED_SIZE: Option<usize> = None;
+    }
+}
+
+/// A trait computing the [`KeyKind`] of a composite (tuple) key from the kinds of its members.
+///
+/// A tuple of fixed-size keys is a fixed-size key itself. If any member is dynamic, so is
+/// the tuple.
+///
+/// Fixed-size tuples are supported for members of 1, 2, 4, 8 or 16 bytes (the sizes of
+/// the numeric keys), so any pair or triple of numeric keys works. Stable Rust can't add up
+/// arbitrary const generics.
+pub trait CompositeKind {
+    /// The kind of the composite key.
+    type Kind: KeyKind;
+}
+
+impl CompositeKind for (DynamicKey, DynamicKey) {
+    type Kind = DynamicKey;
+}
+
+impl<const L: usize> CompositeKind for (DynamicKey, FixedSizeKey<L>) {
+    type Kind = DynamicKey;
+}
+
+impl<const L: usize> CompositeKind for (FixedSizeKey<L>, DynamicKey) {
+    type Kind = DynamicKey;
+}
+
+macro_rules! impl_composite_kind_for_fixed {
+    ($($a:literal),*) => {
+        impl_composite_kind_for_fixed!(@outer [$($a),*] [$($a),*]);
+    };
+    (@outer [$($a:literal),*] $b:tt) => {
+        $(impl_composite_kind_for_fixed!(@inner $a $b);)*
+    };
+    (@inner $a:literal [$($b:literal),*]) => {
+        $(
+            impl CompositeKind for (FixedSizeKey<$a>, FixedSizeKey<$b>) {
+                type Kind = FixedSizeKey<{ $a + $b }>;
+            }
+        )*
+    };
+}
+
+impl_composite_kind_for_fixed!(1, 2, 4, 8, 16);
 
-    impl<const L: usize> KeyKindSeal for super::FixedSizeKey<L> {}
-    impl KeyKindSeal for super::DynamicKey {}
+// Sums of two of the sizes above that aren't themselves in the list. These let a fixed-size
+// pair act as the first part of a fixed-size triple.
+impl_composite_kind_for_fixed!(@outer [3, 5, 6, 9, 10, 12, 17, 18, 20, 24, 32] [1, 2, 4, 8, 16]);
+
+/// An error type for decoding tuple keys.
+///
+/// The third type parameter is only meaningful for triples.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum TupleKeyDecodeError<A, B, C = core::convert::Infallible> {
+    #[error("key too short")]
+    KeyTooShort,
+    #[error("failed to decode first key member: {0}")]
+    First(A),
+    #[error("failed to decode second key member: {0}")]
+    Second(B),
+    #[error("failed to decode third key member: {0}")]
+    Third(C),
+}
+
+impl<A, B, C> crate::error::StoreyError for TupleKeyDecodeError<A, B, C>
+where
+    A: core::fmt::Display,
+    B: core::fmt::Display,
+    C: core::fmt::Display,
+{
+}
+
+/// Append a tuple member that isn't the last one. Dynamically sized members are
+/// length-prefixed the same way as map keys, so that we know where they end.
+fn push_member<K: Key>(result: &mut Vec<u8>, member: &K) {
+    let encoded = member.encode();
+    if <K::Kind as sealed::KeyKindSeal>::FIXED_SIZE.is_none() {
+        super::push_len_prefix(result, encoded.len());
+    }
+    result.extend_from_slice(&encoded);
+}
+
+/// Split off the encoding of a tuple member that isn't the last one.
+///
+/// Returns `None` if the key is too short or the length prefix is invalid.
+fn split_member<K: Key>(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, bytes) = match <K::Kind as sealed::KeyKindSeal>::FIXED_SIZE {
+        Some(size) => (size, bytes),
+        None => super::decode_len_prefix(bytes)?,
+    };
+
+    if bytes.len() < len {
+        return None;
+    }
+    Some(bytes.split_at(len))
+}
+
+/// A tuple key is encoded as the concatenation of its members' encodings. Every dynamically
+/// sized member other than the last one is length-prefixed so that we know where it ends.
+/// The prefix is the same as for map keys: a single byte for members shorter than 255
+/// bytes, and an escape byte followed by a varint for longer ones.
+///
+/// # Ordering
+///
+/// Keys are ordered by their encoding, so tuples are ordered member by member, as long as
+/// the members before the last one are fixed-size. For example, `(u32, String)` keys are
+/// ordered by the number first and then lexicographically by the string.
+///
+/// A length-prefixed member is ordered by its length first, and only then by its content.
+/// With `(String, u32)` keys, `("b", 5)` comes before `("aa", 1)`. The last member is
+/// never length-prefixed, so it always keeps its own ordering.
+impl<A, B> Key for (A, B)
+where
+    A: Key,
+    B: Key,
+    (A::Kind, B::Kind): CompositeKind,
+{
+    type Kind = <(A::Kind, B::Kind) as CompositeKind>::Kind;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        push_member(&mut result, &self.0);
+        result.extend_from_slice(&self.1.encode());
+        result
+    }
+}
+
+impl<A, B> OwnedKey for (A, B)
+where
+    A: OwnedKey,
+    B: OwnedKey,
+    (A::Kind, B::Kind): CompositeKind,
+{
+    type Error = TupleKeyDecodeError<A::Error, B::Error>;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let (first, rest) = split_member::<A>(bytes).ok_or(TupleKeyDecodeError::KeyTooShort)?;
+
+        let first = A::from_bytes(first).map_err(TupleKeyDecodeError::First)?;
+        let second = B::from_bytes(rest).map_err(TupleKeyDecodeError::Second)?;
+
+        Ok((first, second))
+    }
+}
+
+/// A triple key is encoded the same way as a pair - see the implementation for `(A, B)`
+/// for the ordering semantics.
+impl<A, B, C> Key for (A, B, C)
+where
+    A: Key,
+    B: Key,
+    C: Key,
+    (A::Kind, B::Kind): CompositeKind,
+    (<(A::Kind, B::Kind) as CompositeKind>::Kind, C::Kind): CompositeKind,
+{
+    type Kind = <(<(A::Kind, B::Kind) as CompositeKind>::Kind, C::Kind) as CompositeKind>::Kind;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        push_member(&mut result, &self.0);
+        push_member(&mut result, &self.1);
+        result.extend_from_slice(&self.2.encode());
+        result
+    }
+}
+
+impl<A, B, C> OwnedKey for (A, B, C)
+where
+    A: OwnedKey,
+    B: OwnedKey,
+    C: OwnedKey,
+    (A::Kind, B::Kind): CompositeKind,
+    (<(A::Kind, B::Kind) as CompositeKind>::Kind, C::Kind): CompositeKind,
+{
+    type Error = TupleKeyDecodeError<A::Error, B::Error, C::Error>;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let (first, rest) = split_member::<A>(bytes).ok_or(TupleKeyDecodeError::KeyTooShort)?;
+        let (second, rest) = split_member::<B>(rest).ok_or(TupleKeyDecodeError::KeyTooShort)?;
+
+        let first = A::from_bytes(first).map_err(TupleKeyDecodeError::First)?;
+        let second = B::from_bytes(second).map_err(TupleKeyDecodeError::Second)?;
+        let third = C::from_bytes(rest).map_err(TupleKeyDecodeError::Third)?;
+
+        Ok((first, second, third))
+    }
 }
 
 /// An error type for decoding numeric keys.
@@ -207,11 +666,11 @@ macro_rules! impl_key_for_numeric {
                 where
                     Self: Sized,
                 {
-                    if bytes.len() != std::mem::size_of::<Self>() {
+                    if bytes.len() != core::mem::size_of::<Self>() {
                         return Err(NumericKeyDecodeError::InvalidLength);
                     }
 
-                    let mut buf = [0; std::mem::size_of::<Self>()];
+                    let mut buf = [0; core::mem::size_of::<Self>()];
                     buf.copy_from_slice(bytes);
                     Ok(Self::from_be_bytes(buf))
                 }
@@ -240,11 +699,11 @@ macro_rules! impl_key_for_signed {
                 where
                     Self: Sized,
                 {
-                    if bytes.len() != std::mem::size_of::<Self>() {
+                    if bytes.len() != core::mem::size_of::<Self>() {
                         return Err(NumericKeyDecodeError::InvalidLength);
                     }
 
-                    let mut buf = [0; std::mem::size_of::<Self>()];
+                    let mut buf = [0; core::mem::size_of::<Self>()];
                     buf.copy_from_slice(bytes);
                     Ok((Self::from_be_bytes(buf) as $ut ^ <$t>::MIN as $ut) as _)
                 }
@@ -255,6 +714,132 @@ macro_rules! impl_key_for_signed {
 
 impl_key_for_signed!(i8 : u8, i16 : u16, i32 : u32, i64 : u64, i128 : u128);
 
+macro_rules! impl_key_for_float {
+    ($($t:ty : $ut:ty),*) => {
+        $(
+            /// Floats are encoded so that the byte order matches [`total_cmp`](<$t>::total_cmp):
+            /// the sign bit is flipped for non-negative values, and all bits are flipped for
+            /// negative ones.
+            ///
+            /// This means `-0.0` sorts before (and is a different key than) `0.0`. NaNs are
+            /// stored as-is rather than canonicalized - ones with the sign bit unset sort after
+            /// positive infinity, and ones with it set sort before negative infinity.
+            impl Key for $t {
+                type Kind = FixedSizeKey<{ core::mem::size_of::<$t>() }>;
+
+                fn encode(&self) -> Vec<u8> {
+                    let bits = self.to_bits();
+                    let mask = if bits >> (<$ut>::BITS - 1) == 1 {
+                        <$ut>::MAX
+                    } else {
+                        1 << (<$ut>::BITS - 1)
+                    };
+                    (bits ^ mask).to_be_bytes().to_vec()
+                }
+            }
+
+            impl OwnedKey for $t {
+                type Error = NumericKeyDecodeError;
+
+                fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+                where
+                    Self: Sized,
+                {
+                    if bytes.len() != core::mem::size_of::<Self>() {
+                        return Err(NumericKeyDecodeError::InvalidLength);
+                    }
+
+                    let mut buf = [0; core::mem::size_of::<Self>()];
+                    buf.copy_from_slice(bytes);
+                    let bits = <$ut>::from_be_bytes(buf);
+                    // the encoded sign bit is set for non-negative values
+                    let mask = if bits >> (<$ut>::BITS - 1) == 1 {
+                        1 << (<$ut>::BITS - 1)
+                    } else {
+                        <$ut>::MAX
+                    };
+                    Ok(<$t>::from_bits(bits ^ mask))
+                }
+            }
+        )*
+    };
+}
+
+impl_key_for_float!(f32 : u32, f64 : u64);
+
+/// An error type for decoding [`bool`] keys.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum BoolKeyDecodeError {
+    #[error("invalid key length, expected 1 byte")]
+    InvalidLength,
+    #[error("invalid bool byte: {0}")]
+    InvalidValue(u8),
+}
+
+impl crate::error::StoreyError for BoolKeyDecodeError {}
+
+/// A [`bool`] key is encoded as a single byte, `0` for `false` and `1` for `true`.
+impl Key for bool {
+    type Kind = FixedSizeKey<1>;
+
+    fn encode(&self) -> Vec<u8> {
+        vec![u8::from(*self)]
+    }
+}
+
+impl OwnedKey for bool {
+    type Error = BoolKeyDecodeError;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        match bytes {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [byte] => Err(BoolKeyDecodeError::InvalidValue(*byte)),
+            _ => Err(BoolKeyDecodeError::InvalidLength),
+        }
+    }
+}
+
+/// An error type for decoding [`char`] keys.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum CharKeyDecodeError {
+    #[error("invalid key length, expected 4 bytes")]
+    InvalidLength,
+    #[error("invalid code point: {0:#x}")]
+    InvalidCodePoint(u32),
+}
+
+impl crate::error::StoreyError for CharKeyDecodeError {}
+
+/// A [`char`] key is encoded as its code point, a big-endian `u32`, so keys are ordered by
+/// code point.
+impl Key for char {
+    type Kind = FixedSizeKey<4>;
+
+    fn encode(&self) -> Vec<u8> {
+        u32::from(*self).to_be_bytes().to_vec()
+    }
+}
+
+impl OwnedKey for char {
+    type Error = CharKeyDecodeError;
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 4] = bytes
+            .try_into()
+            .map_err(|_| CharKeyDecodeError::InvalidLength)?;
+        let code_point = u32::from_be_bytes(bytes);
+
+        char::from_u32(code_point).ok_or(CharKeyDecodeError::InvalidCodePoint(code_point))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +859,178 @@ mod tests {
         assert_eq!(&data[..], &decoded);
     }
 
+    #[test]
+    fn float_ordering() {
+        let data = [
+            f64::NEG_INFINITY,
+            f64::MIN,
+            -3333.5,
+            -1.0,
+            -f64::MIN_POSITIVE,
+            -0.0,
+            0.0,
+            f64::MIN_POSITIVE,
+            1.0,
+            3333.5,
+            f64::MAX,
+            f64::INFINITY,
+        ];
+
+        let mut encoded = data.iter().map(|&x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+
+        let decoded = encoded
+            .iter()
+            .map(|x| f64::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            data.map(f64::to_bits),
+            <[f64; 12]>::try_from(decoded).unwrap().map(f64::to_bits)
+        );
+
+        let data = [-1.5f32, -0.0, 0.0, 0.25, 1.5];
+        let mut encoded = data.iter().map(|&x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+        let decoded = encoded
+            .iter()
+            .map(|x| f32::from_bytes(x).unwrap().to_bits())
+            .collect::<Vec<_>>();
+        assert_eq!(data.map(f32::to_bits).to_vec(), decoded);
+    }
+
+    #[test]
+    fn float_nan() {
+        let nan = f64::NAN.encode();
+        let neg_nan = (-f64::NAN).encode();
+
+        assert!(nan > f64::INFINITY.encode());
+        assert!(neg_nan < f64::NEG_INFINITY.encode());
+
+        assert!(f64::from_bytes(&nan).unwrap().is_nan());
+        assert_eq!(f64::from_bytes(&nan).unwrap().to_bits(), f64::NAN.to_bits());
+        assert_eq!(
+            f64::from_bytes(&neg_nan).unwrap().to_bits(),
+            (-f64::NAN).to_bits()
+        );
+
+        assert_eq!(
+            f32::from_bytes(&[0; 3]),
+            Err(NumericKeyDecodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn bool_key_roundtrip() {
+        assert_eq!(false.encode(), [0]);
+        assert_eq!(true.encode(), [1]);
+        assert_eq!(bool::from_bytes(&false.encode()), Ok(false));
+        assert_eq!(bool::from_bytes(&true.encode()), Ok(true));
+
+        assert_eq!(
+            bool::from_bytes(&[2]),
+            Err(BoolKeyDecodeError::InvalidValue(2))
+        );
+        assert_eq!(
+            bool::from_bytes(&[]),
+            Err(BoolKeyDecodeError::InvalidLength)
+        );
+        assert_eq!(
+            bool::from_bytes(&[0, 1]),
+            Err(BoolKeyDecodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn char_key_roundtrip() {
+        for c in ['\0', 'a', 'z', 'é', '\u{d7ff}', '\u{e000}', '🦀', char::MAX] {
+            assert_eq!(c.encode(), u32::from(c).to_be_bytes());
+            assert_eq!(char::from_bytes(&c.encode()), Ok(c));
+        }
+
+        // ordered by code point
+        assert!('a'.encode() < 'b'.encode());
+        assert!('z'.encode() < 'é'.encode());
+
+        // surrogates and values past the last code point aren't chars
+        assert_eq!(
+            char::from_bytes(&0xd800u32.to_be_bytes()),
+            Err(CharKeyDecodeError::InvalidCodePoint(0xd800))
+        );
+        assert_eq!(
+            char::from_bytes(&0x110000u32.to_be_bytes()),
+            Err(CharKeyDecodeError::InvalidCodePoint(0x110000))
+        );
+        assert_eq!(
+            char::from_bytes(&[0, 0, 97]),
+            Err(CharKeyDecodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn option_key_ordering() {
+        let data = [None, Some(0u32), Some(1), Some(5555), Some(u32::MAX)];
+
+        let mut encoded = data.iter().map(|x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+
+        let decoded = encoded
+            .iter()
+            .map(|x| Option::<u32>::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(&data[..], &decoded);
+
+        // `None` sorts before any `Some`, even one with an empty inner key
+        assert!(None::<String>.encode() < Some(String::new()).encode());
+        assert_eq!(
+            Option::<String>::from_bytes(&Some(String::new()).encode()),
+            Ok(Some(String::new()))
+        );
+
+        assert_eq!(
+            Option::<u32>::from_bytes(&[]),
+            Err(OptionKeyDecodeError::EmptyKey)
+        );
+        assert_eq!(
+            Option::<u32>::from_bytes(&[0, 1]),
+            Err(OptionKeyDecodeError::TrailingBytes)
+        );
+        assert_eq!(
+            Option::<u32>::from_bytes(&[2]),
+            Err(OptionKeyDecodeError::InvalidTag(2))
+        );
+        assert_eq!(
+            Option::<u32>::from_bytes(&[1, 0]),
+            Err(OptionKeyDecodeError::Some(
+                NumericKeyDecodeError::InvalidLength
+            ))
+        );
+    }
+
+    #[test]
+    fn reverse_key_ordering() {
+        use core::cmp::Reverse;
+
+        let data = [55555555, 3333, 1, 0, -1, -3333, -555555555].map(Reverse);
+
+        let mut encoded = data.iter().map(|x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+
+        let decoded = encoded
+            .iter()
+            .map(|x| Reverse::<i32>::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(&data[..], &decoded);
+
+        assert_eq!(Reverse(1u16).encode(), [0xff, 0xfe]);
+        assert_eq!(
+            Reverse::<u16>::from_bytes(&[0xff]),
+            Err(NumericKeyDecodeError::InvalidLength)
+        );
+    }
+
     #[test]
     fn signed_int_encoding() {
         // negative values have the leftmost bit unset
@@ -289,4 +1046,172 @@ mod tests {
         assert_eq!(2i32.encode(), [0b10000000, 0x00, 0x00, 0x02]);
         assert_eq!(i32::MAX.encode(), [0b11111111, 0xff, 0xff, 0xff]);
     }
+
+    #[test]
+    fn path_key_roundtrip() {
+        use std::path::{Path, PathBuf};
+
+        let paths = ["", "/", "foo/bar.txt", "/var/lib/./data/../index"];
+        for path in paths {
+            let encoded = Path::new(path).encode();
+            assert_eq!(encoded, path.as_bytes());
+            assert_eq!(PathBuf::from_bytes(&encoded), Ok(PathBuf::from(path)));
+        }
+
+        // ordered by bytes, not by components
+        assert!(Path::new("a/b").encode() > Path::new("a-b").encode());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_path_key_roundtrip() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt as _;
+        use std::path::PathBuf;
+
+        let path = PathBuf::from(OsStr::from_bytes(&[b'a', 0xff, b'/', 0xfe]));
+        assert_eq!(path.encode(), [b'a', 0xff, b'/', 0xfe]);
+        assert_eq!(PathBuf::from_bytes(&path.encode()), Ok(path));
+    }
+
+    #[test]
+    fn tuple_key_roundtrip() {
+        let pair = (5u32, "foo".to_string());
+        assert_eq!(pair.encode(), [0, 0, 0, 5, 102, 111, 111]);
+        assert_eq!(<(u32, String)>::from_bytes(&pair.encode()), Ok(pair));
+
+        let pair = ("foo".to_string(), 5u8);
+        assert_eq!(pair.encode(), [3, 102, 111, 111, 5]);
+        assert_eq!(<(String, u8)>::from_bytes(&pair.encode()), Ok(pair));
+
+        let triple = ("ab".to_string(), 7u16, "cd".to_string());
+        assert_eq!(triple.encode(), [2, 97, 98, 0, 7, 99, 100]);
+        assert_eq!(
+            <(String, u16, String)>::from_bytes(&triple.encode()),
+            Ok(triple)
+        );
+
+        let triple = (1u8, String::new(), 2u64);
+        assert_eq!(
+            <(u8, String, u64)>::from_bytes(&triple.encode()),
+            Ok(triple)
+        );
+
+        let triple = (1u8, 2u16, -3i32);
+        assert_eq!(triple.encode().len(), 7);
+        assert_eq!(<(u8, u16, i32)>::from_bytes(&triple.encode()), Ok(triple));
+
+        assert_eq!(
+            <(String, u16, String)>::from_bytes(&[2, 97, 98, 0]),
+            Err(TupleKeyDecodeError::KeyTooShort)
+        );
+        assert_eq!(
+            <(u8, u16, u32)>::from_bytes(&[1, 0, 2, 0]),
+            Err(TupleKeyDecodeError::Third(
+                NumericKeyDecodeError::InvalidLength
+            ))
+        );
+    }
+
+    #[test]
+    fn tuple_key_long_members() {
+        // a member of 255 bytes or more gets an escaped varint length prefix
+        let pair = ("a".repeat(300), "b".to_string());
+        let encoded = pair.encode();
+        assert_eq!(encoded[..3], [0xFF, 0xAC, 0x02]);
+        assert_eq!(encoded.len(), 3 + 300 + 1);
+        assert_eq!(<(String, String)>::from_bytes(&encoded), Ok(pair));
+
+        // with a truncated prefix, these two used to share an encoding
+        let long = ("a".repeat(300), "b".to_string());
+        let short = ("a".repeat(44), "a".repeat(256) + "b");
+        assert_ne!(long.encode(), short.encode());
+
+        let triple = ("x".repeat(255), "y".repeat(1000), 7u8);
+        assert_eq!(
+            <(String, String, u8)>::from_bytes(&triple.encode()),
+            Ok(triple)
+        );
+
+        // non-canonical or truncated prefixes are rejected
+        assert_eq!(
+            <(String, String)>::from_bytes(&[0xFF, 0x05, 1, 2, 3, 4, 5]),
+            Err(TupleKeyDecodeError::KeyTooShort)
+        );
+        assert_eq!(
+            <(String, String)>::from_bytes(&[0xFF, 0x80]),
+            Err(TupleKeyDecodeError::KeyTooShort)
+        );
+    }
+
+    #[test]
+    fn tuple_key_ordering() {
+        // fixed-size members first - ordered member by member
+        let data = [
+            (1u32, "b".to_string(), 0u8),
+            (1, "b".to_string(), 1),
+            (1, "bb".to_string(), 0),
+            (2, "a".to_string(), 0),
+        ];
+        let mut encoded = data.iter().rev().map(|x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+        let decoded = encoded
+            .iter()
+            .map(|x| <(u32, String, u8)>::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(&data[..], &decoded);
+
+        // a length-prefixed member is ordered by length first
+        let data = [
+            ("b".to_string(), 5u32),
+            ("aa".to_string(), 1),
+            ("ab".to_string(), 0),
+        ];
+        let mut encoded = data.iter().rev().map(|x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+        let decoded = encoded
+            .iter()
+            .map(|x| <(String, u32)>::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(&data[..], &decoded);
+    }
+
+    #[test]
+    fn result_key_roundtrip() {
+        let keys: [Result<u32, String>; 3] = [Ok(42), Err("foo".to_string()), Err(String::new())];
+
+        for key in keys {
+            assert_eq!(Result::<u32, String>::from_bytes(&key.encode()), Ok(key));
+        }
+
+        assert_eq!(Ok::<u32, String>(42).encode(), [0, 0, 0, 0, 42]);
+        assert_eq!(
+            Err::<u32, String>("foo".to_string()).encode(),
+            [1, 102, 111, 111]
+        );
+        assert_eq!(
+            Result::<u32, String>::from_bytes(&[2, 0]),
+            Err(ResultKeyDecodeError::InvalidTag(2))
+        );
+        assert_eq!(
+            Result::<u32, String>::from_bytes(&[]),
+            Err(ResultKeyDecodeError::EmptyKey)
+        );
+    }
+
+    #[test]
+    fn result_key_ordering() {
+        let data: [Result<u32, u32>; 6] =
+            [Ok(0), Ok(1), Ok(u32::MAX), Err(0), Err(5), Err(u32::MAX)];
+
+        let mut encoded = data.iter().rev().map(|x| x.encode()).collect::<Vec<_>>();
+        encoded.sort();
+
+        let decoded = encoded
+            .iter()
+            .map(|x| Result::<u32, u32>::from_bytes(x).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(&data[..], &decoded);
+    }
 }
@@ -1,28 +1,56 @@
+mod counted;
+mod entry;
+mod indexed;
 pub mod key;
 mod key_encoding;
-
+mod set;
+
+pub use counted::{CountedMap, CountedMapAccess};
+pub use entry::MapEntry;
+pub use indexed::{
+    IndexList, IndexReadError, IndexedMap, IndexedMapAccess, MultiIndex, MultiIndexAccess,
+    SaveError, UniqueIndex, UniqueIndexAccess,
+};
 pub use key::{Key, OwnedKey};
 use key_encoding::KeyEncoding;
 use key_encoding::KeyEncodingT;
+pub use set::{Difference, Intersection, Set, SetAccess, Union};
 
-use std::{borrow::Borrow, marker::PhantomData};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::{borrow::Borrow, marker::PhantomData, ops::Bound};
 
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::successor;
+use crate::storage::IterableMetaStorage;
 use crate::storage::IterableStorage;
+use crate::storage::Storage;
 use crate::storage::StorageBranch;
+use crate::storage::StorageMut;
+use crate::storage::StorageRef;
 
 use self::key::DynamicKey;
 use self::key::FixedSizeKey;
 
+use super::common::{clear_namespace, remove_range};
+use super::decode_pair;
+use super::item::{ItemKeyDecodeError, UpdateError};
+use super::sealed::StorableKindSeal;
 use super::BoundFor;
 use super::BoundedIterableAccessor;
+use super::Item;
 use super::IterableAccessor;
+use super::KVDecodeError;
 use super::NonTerminal;
+use super::RefTarget;
 use super::Storable;
 use super::Terminal;
 
 /// A map that stores values of type `V` under keys of type `K`.
 ///
 /// The subkeys managed by the map are length-prefixed and appended to the map's prefix.
+/// Keys shorter than 255 bytes take a single byte of prefix. Longer keys are supported too -
+/// their length is written as a `0xFF` escape byte followed by an unsigned LEB128 varint.
 ///
 /// A map does not directly manage the storage of its values. Instead, it doles out access to
 /// a collection of other containers.
@@ -65,7 +93,7 @@ impl<K, V> Map<K, V>
 where
     K: OwnedKey,
     V: Storable,
-    <V as Storable>::KeyDecodeError: std::fmt::Display,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
     (K::Kind, V::Kind): KeyEncodingT,
 {
     /// Creates a new map with the given prefix.
@@ -99,22 +127,94 @@ where
     /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
     /// let mut access = map.access(&mut storage);
     /// ```
-    pub fn access<S>(&self, storage: S) -> MapAccess<K, V, StorageBranch<S>> {
+    pub fn access<S: StorageRef>(&self, storage: S) -> MapAccess<K, V, StorageBranch<S>> {
         Self::access_impl(StorageBranch::new(storage, vec![self.prefix]))
     }
+
+    /// Acquire a read-only accessor to the map.
+    ///
+    /// This is [`access`](Self::access) restricted to a shared reference. Calling a
+    /// mutating method on the result is a compile error pointing at that method, and
+    /// passing anything but a reference is caught at the call site.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    ///
+    /// let storage = TestStorage::new();
+    /// let access = map.access_ref(&storage);
+    ///
+    /// assert_eq!(access.entry("foo").get().unwrap(), None);
+    /// ```
+    pub fn access_ref<'s, S>(&self, storage: &'s S) -> MapAccess<K, V, StorageBranch<&'s S>> {
+        self.access(storage)
+    }
+
+    /// Acquire a mutable accessor to the map.
+    ///
+    /// This is [`access`](Self::access) restricted to an exclusive reference.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let mut access = map.access_mut(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&42).unwrap();
+    /// assert_eq!(access.entry("foo").get().unwrap(), Some(42));
+    /// ```
+    pub fn access_mut<'s, S>(
+        &self,
+        storage: &'s mut S,
+    ) -> MapAccess<K, V, StorageBranch<&'s mut S>> {
+        self.access(storage)
+    }
+
+    /// Acquires an accessor for the map from an already constructed storage branch.
+    ///
+    /// The branch is used as the map's namespace as-is. The map's own prefix is not applied,
+    /// so it's up to the caller to make sure the branch points at the right place.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    /// use storey::storage::StorageBranch;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    ///
+    /// let branch = StorageBranch::new(&mut storage, vec![0]);
+    /// map.access_branch(branch).entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// assert_eq!(map.access(&storage).entry("foo").get().unwrap(), Some(1337));
+    /// ```
+    pub fn access_branch<S>(&self, branch: StorageBranch<S>) -> MapAccess<K, V, StorageBranch<S>> {
+        Self::access_impl(branch)
+    }
 }
 
 impl<K, V> Storable for Map<K, V>
 where
     K: OwnedKey,
     V: Storable,
-    <V as Storable>::KeyDecodeError: std::fmt::Display,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
     (K::Kind, V::Kind): KeyEncodingT,
 {
     type Kind = NonTerminal;
     type Accessor<S> = MapAccess<K, V, S>;
     type Key = (K, V::Key);
-    type KeyDecodeError = MapKeyDecodeError<V::KeyDecodeError>;
+    type KeyDecodeError = MapKeyDecodeError<K::Error, V::KeyDecodeError>;
     type Value = V::Value;
     type ValueDecodeError = V::ValueDecodeError;
 
@@ -125,32 +225,30 @@ where
         }
     }
 
-    fn decode_key(key: &[u8]) -> Result<Self::Key, MapKeyDecodeError<V::KeyDecodeError>> {
+    fn decode_key(key: &[u8]) -> Result<Self::Key, MapKeyDecodeError<K::Error, V::KeyDecodeError>> {
         let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
 
         match behavior {
             KeyEncoding::LenPrefix => {
-                let len = *key.first().ok_or(MapKeyDecodeError::EmptyKey)? as usize;
+                let (len, key) = split_len_prefix(key)?;
 
-                if key.len() < len + 1 {
+                if key.len() < len {
                     return Err(MapKeyDecodeError::KeyTooShort(len));
                 }
 
-                let map_key =
-                    K::from_bytes(&key[1..len + 1]).map_err(|_| MapKeyDecodeError::InvalidUtf8)?;
-                let rest = V::decode_key(&key[len + 1..]).map_err(MapKeyDecodeError::Inner)?;
+                let map_key = K::from_bytes(&key[..len]).map_err(MapKeyDecodeError::OuterKey)?;
+                let rest = V::decode_key(&key[len..]).map_err(MapKeyDecodeError::Inner)?;
 
                 Ok((map_key, rest))
             }
             KeyEncoding::UseRest => {
-                let map_key = K::from_bytes(key).map_err(|_| MapKeyDecodeError::InvalidUtf8)?;
+                let map_key = K::from_bytes(key).map_err(MapKeyDecodeError::OuterKey)?;
                 let rest = V::decode_key(&[]).map_err(MapKeyDecodeError::Inner)?;
 
                 Ok((map_key, rest))
             }
             KeyEncoding::UseN(n) => {
-                let map_key =
-                    K::from_bytes(&key[..n]).map_err(|_| MapKeyDecodeError::InvalidUtf8)?;
+                let map_key = K::from_bytes(&key[..n]).map_err(MapKeyDecodeError::OuterKey)?;
                 let rest = V::decode_key(&key[n..]).map_err(MapKeyDecodeError::Inner)?;
 
                 Ok((map_key, rest))
@@ -165,21 +263,29 @@ where
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
 #[error("invalid key length, expected empty key")]
-pub enum MapKeyDecodeError<I: std::fmt::Display> {
-    #[error("empty key, expected length prefix (1 byte)")]
+pub enum MapKeyDecodeError<K: core::fmt::Display, I: core::fmt::Display> {
+    #[error("empty key, expected length prefix")]
     EmptyKey,
 
+    #[error("invalid length prefix")]
+    InvalidLengthPrefix,
+
     #[error("key too short, expected {0} bytes after length prefix")]
     KeyTooShort(usize),
 
-    #[error("invalid UTF8")]
-    InvalidUtf8,
+    #[error("map key decode error: {0}")]
+    OuterKey(K),
 
     #[error("sub key decode error: {0}")]
     Inner(I),
 }
 
-impl<I: std::fmt::Display> crate::error::StoreyError for MapKeyDecodeError<I> {}
+impl<K, I> crate::error::StoreyError for MapKeyDecodeError<K, I>
+where
+    K: core::fmt::Display,
+    I: core::fmt::Display,
+{
+}
 
 /// An accessor for a map.
 ///
@@ -189,6 +295,18 @@ pub struct MapAccess<K: ?Sized, V, S> {
     phantom: PhantomData<(*const K, V)>,
 }
 
+impl<K: ?Sized, V, S> Clone for MapAccess<K, V, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
 impl<K, V, S> MapAccess<K, V, S>
 where
     K: Key,
@@ -227,12 +345,7 @@ where
         K: Borrow<Q>,
         Q: Key<Kind = K::Kind> + ?Sized,
     {
-        let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
-
-        let key = match behavior {
-            KeyEncoding::LenPrefix => len_prefix(key.encode()),
-            _ => key.encode(),
-        };
+        let key = Self::encode_entry_key(key);
 
         V::access_impl(StorageBranch::new(&self.storage, key))
     }
@@ -271,150 +384,1798 @@ where
         K: Borrow<Q>,
         Q: Key<Kind = K::Kind> + ?Sized,
     {
-        let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
+        let key = Self::encode_entry_key(key);
 
-        let key = match behavior {
-            KeyEncoding::LenPrefix => len_prefix(key.encode()),
-            _ => key.encode(),
+        V::access_impl(StorageBranch::new(&mut self.storage, key))
+    }
+
+    /// Returns an immutable accessor for the inner container of this map, or `None` if
+    /// nothing is stored under the given key.
+    ///
+    /// Unlike [`entry`](Self::entry), this lets callers tell an entry that was never written
+    /// apart from one that was written with a default value. It costs one extra storage read:
+    /// for terminal containers (like [`Item`]) that's a [`has`](Storage::has) on the entry
+    /// key, and for non-terminal ones (like a nested [`Map`] or a
+    /// [`Column`](crate::containers::Column)) it's a single step of a key scan under the
+    /// entry, since their data lives under subkeys. Metadata alone (like a column's length)
+    /// doesn't count as stored data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&0).unwrap();
+    ///
+    /// assert_eq!(access.try_entry("foo").unwrap().get().unwrap(), Some(0));
+    /// assert!(access.try_entry("bar").is_none());
+    /// ```
+    ///
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").entry_mut("bar").set(&1337).unwrap();
+    ///
+    /// assert!(access.try_entry("foo").is_some());
+    /// assert!(access.try_entry("baz").is_none());
+    /// ```
+    pub fn try_entry<Q>(&self, key: &Q) -> Option<V::Accessor<StorageBranch<&S>>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+        S: Storage + IterableStorage,
+    {
+        let branch = StorageBranch::new(&self.storage, Self::encode_entry_key(key));
+
+        let exists = if <V::Kind as StorableKindSeal>::TERMINAL {
+            branch.has(&[])
+        } else {
+            branch
+                .keys(Bound::Unbounded, Bound::Unbounded)
+                .next()
+                .is_some()
         };
 
-        V::access_impl(StorageBranch::new(&mut self.storage, key))
+        exists.then(|| V::access_impl(branch))
     }
-}
 
-fn len_prefix<T: AsRef<[u8]>>(bytes: T) -> Vec<u8> {
-    let len = bytes.as_ref().len();
-    let mut result = Vec::with_capacity(len + 1);
-    result.extend_from_slice(&(len as u8).to_be_bytes());
-    result.extend_from_slice(bytes.as_ref());
-    result
+    /// Check if the map is empty by looking for any stored entry.
+    ///
+    /// The map doesn't keep an entry counter, so this is always a single step of a key scan
+    /// rather than a metadata read.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// assert!(access.is_empty_scan());
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// assert!(!access.is_empty_scan());
+    /// ```
+    pub fn is_empty_scan(&self) -> bool
+    where
+        S: IterableStorage,
+    {
+        self.storage
+            .keys(Bound::Unbounded, Bound::Unbounded)
+            .next()
+            .is_none()
+    }
+
+    fn encode_entry_key<Q>(key: &Q) -> Vec<u8>
+    where
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
+
+        match behavior {
+            KeyEncoding::LenPrefix => len_prefix(key.encode()),
+            _ => key.encode(),
+        }
+    }
 }
 
-impl<K, V, S> IterableAccessor for MapAccess<K, V, S>
+impl<K, V, S> MapAccess<K, V, StorageBranch<S>>
 where
-    K: OwnedKey,
+    K: Key,
     V: Storable,
-    <V as Storable>::KeyDecodeError: std::fmt::Display,
-    S: IterableStorage,
     (K::Kind, V::Kind): KeyEncodingT,
 {
-    type Storable = Map<K, V>;
-    type Storage = S;
+    /// Returns an immutable accessor for the inner container of this map, along with
+    /// the raw key the entry is stored under.
+    ///
+    /// The returned key is composed of the map's prefix and the encoded entry key. For a map
+    /// accessed directly on a backend, this is exactly the backend key prefix the entry's
+    /// data lives under, which makes it useful for correlating entries with storage slots
+    /// (e.g. for audit logs).
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let access = map.access(&storage);
+    ///
+    /// let (key, entry) = access.entry_with_key("foo");
+    /// assert_eq!(key, [&[0][..], b"foo"].concat());
+    /// assert_eq!(entry.get().unwrap(), None);
+    /// ```
+    pub fn entry_with_key<Q>(
+        &self,
+        key: &Q,
+    ) -> (Vec<u8>, V::Accessor<StorageBranch<&StorageBranch<S>>>)
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let key = Self::encode_entry_key(key);
+        let raw_key = [self.storage.prefix(), &key[..]].concat();
 
-    fn storage(&self) -> &Self::Storage {
-        &self.storage
+        (
+            raw_key,
+            V::access_impl(StorageBranch::new(&self.storage, key)),
+        )
     }
 }
 
-// The following dance is necessary to make bounded iteration unavailable for maps
-// that have both dynamic keys and "non-terminal" values (i.e. maps of maps, maps of columns, etc).
-//
-// This is because in cases where the key is dynamically size **and** there's another key
-// after it, we have to length-prefix the key. This makes bounded iteration behave differently
-// than in other cases (and rather unintuitively).
+impl<K, V, S> MapAccess<K, V, S>
+where
+    K: Key,
+    V: Storable<Kind = Terminal>,
+    S: Storage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    /// Check if there's an entry stored under the given key.
+    ///
+    /// This doesn't fetch or decode the value. It's only available for maps of terminal
+    /// containers (like [`Item`]), which store their value directly under the entry key.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// assert!(access.contains_key("foo"));
+    /// assert!(!access.contains_key("bar"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.storage.has(&Self::encode_entry_key(key))
+    }
+}
 
-impl<K, V, S> BoundedIterableAccessor for MapAccess<K, V, S>
+impl<K, V, S> MapAccess<K, V, S>
 where
     K: OwnedKey,
     V: Storable,
-    <V as Storable>::KeyDecodeError: std::fmt::Display,
-    S: IterableStorage,
-    (K::Kind, V::Kind): BoundedIterationAllowed + KeyEncodingT,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage + StorageMut,
+    (K::Kind, V::Kind): KeyEncodingT,
+    Self: BoundedIterableAccessor,
 {
-}
-
-trait BoundedIterationAllowed {}
+    /// Removes all entries with keys within the given range, returning the number of
+    /// entries removed.
+    ///
+    /// Either end of the range can be unbounded, inclusive, or exclusive. See [`Bound`] for more.
+    /// The bounds behave exactly like the ones accepted by
+    /// [`bounded_keys`](BoundedIterableAccessor::bounded_keys).
+    ///
+    /// This is available wherever [bounded iteration](BoundedIterableAccessor) is - that is,
+    /// for all maps except ones with dynamically sized keys and non-terminal values.
+    ///
+    /// For maps of non-terminal values (like a [`Column`](crate::containers::Column)), an
+    /// entry is the whole inner container. The bounds select entire entries and the returned
+    /// count is the number of underlying storage keys removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use std::ops::Bound;
+    ///
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// for i in 1..=5 {
+    ///     access.entry_mut(&i).set(&(i as u64)).unwrap();
+    /// }
+    ///
+    /// assert_eq!(access.remove_range(Bound::Included(&2), Bound::Excluded(&4)), 2);
+    /// assert_eq!(
+    ///     access.values().collect::<Result<Vec<_>, _>>().unwrap(),
+    ///     vec![1, 4, 5]
+    /// );
+    /// ```
+    pub fn remove_range<B>(&mut self, start: Bound<B>, end: Bound<B>) -> u32
+    where
+        B: BoundFor<Map<K, V>>,
+    {
+        let mut start = start.map(|b| b.into_bytes());
+        let mut end = end.map(|b| b.into_bytes());
+
+        // Keys of a non-terminal value extend past the map key, so bounds have to cover
+        // (or exclude) everything under it.
+        if let KeyEncoding::UseN(_) = <(K::Kind, V::Kind)>::BEHAVIOR {
+            if let Bound::Excluded(key) = &start {
+                match successor(key) {
+                    Some(next) => start = Bound::Included(next),
+                    None => return 0,
+                }
+            }
+            if let Bound::Included(key) = &end {
+                end = successor(key).map_or(Bound::Unbounded, Bound::Excluded);
+            }
+        }
 
-impl<const L: usize> BoundedIterationAllowed for (FixedSizeKey<L>, Terminal) {}
-impl<const L: usize> BoundedIterationAllowed for (FixedSizeKey<L>, NonTerminal) {}
-impl BoundedIterationAllowed for (DynamicKey, Terminal) {}
+        remove_range(
+            &mut self.storage,
+            start.as_ref().map(|b| b.as_slice()),
+            end.as_ref().map(|b| b.as_slice()),
+        )
+    }
+}
 
-impl<K, V, Q> BoundFor<Map<K, V>> for &Q
+impl<K, V, S> MapAccess<K, V, S>
 where
-    K: Borrow<Q> + OwnedKey,
+    K: Key,
     V: Storable,
-    Q: Key + ?Sized,
-    (K::Kind, V::Kind): KeyEncodingT,
+    S: IterableStorage + IterableMetaStorage + StorageMut,
 {
-    fn into_bytes(self) -> Vec<u8> {
-        let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
-
-        match behavior {
-            KeyEncoding::LenPrefix => len_prefix(self.encode()),
-            _ => self.encode(),
-        }
+    /// Remove all entries from the map.
+    ///
+    /// This wipes the map's entire namespace, including the data and metadata of nested
+    /// containers, so e.g. a map of maps or a map of columns is left completely empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").entry_mut("bar").set(&1337).unwrap();
+    /// access.entry_mut("baz").entry_mut("qux").set(&42).unwrap();
+    ///
+    /// access.clear();
+    /// assert_eq!(access.keys().count(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        clear_namespace(&mut self.storage);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::ops::Bound;
+impl<K, T, E, S> MapAccess<K, Item<T, E>, S>
+where
+    K: Key,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Get the value stored under the given key.
+    ///
+    /// This is a shorthand for `access.entry(key).get()`. Returns `Ok(None)` if there's no
+    /// value stored under the key.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// assert_eq!(access.get("bar").unwrap(), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Result<Option<T>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.entry(key).get()
+    }
+}
+
+impl<K, T, E, S> MapAccess<K, Item<T, E>, S>
+where
+    K: Key,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: Storage + StorageMut,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Set the value stored under the given key.
+    ///
+    /// This is a shorthand for `access.entry_mut(key).set(value)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.set("foo", &1337).unwrap();
+    ///
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// assert_eq!(access.entry("foo").get().unwrap(), Some(1337));
+    /// ```
+    pub fn set<Q>(&mut self, key: &Q, value: &T) -> Result<(), E::EncodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.entry_mut(key).set(value)
+    }
+
+    /// Get the value stored under the given key, inserting (and returning) the default value
+    /// if there's none.
+    ///
+    /// This is similar to `HashMap::entry(key).or_default()`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// assert_eq!(access.entry_or_default_mut("foo").unwrap(), 0);
+    /// assert_eq!(access.entry("foo").get().unwrap(), Some(0));
+    ///
+    /// access.entry_mut("bar").set(&1337).unwrap();
+    /// assert_eq!(access.entry_or_default_mut("bar").unwrap(), 1337);
+    /// ```
+    pub fn entry_or_default_mut<Q>(
+        &mut self,
+        key: &Q,
+    ) -> Result<T, UpdateError<E::DecodeError, E::EncodeError>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+        T: Default,
+    {
+        let mut entry = self.entry_mut(key);
+
+        match entry.get().map_err(UpdateError::Decode)? {
+            Some(value) => Ok(value),
+            None => {
+                let value = T::default();
+                entry.set(&value).map_err(UpdateError::Encode)?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Read the value stored under the given key, apply `f` to it and write the result back,
+    /// all in one call. Returns the new value.
+    ///
+    /// The function is called with `None` if there's no value stored under the key.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// let count = |value: Option<u64>| value.unwrap_or_default() + 1;
+    ///
+    /// assert_eq!(access.modify("foo", count).unwrap(), 1);
+    /// assert_eq!(access.modify("foo", count).unwrap(), 2);
+    /// assert_eq!(access.entry("foo").get().unwrap(), Some(2));
+    /// ```
+    pub fn modify<Q, F>(
+        &mut self,
+        key: &Q,
+        f: F,
+    ) -> Result<T, UpdateError<E::DecodeError, E::EncodeError>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+        F: FnOnce(Option<T>) -> T,
+    {
+        let mut entry = self.entry_mut(key);
+
+        let value = f(entry.get().map_err(UpdateError::Decode)?);
+        entry.set(&value).map_err(UpdateError::Encode)?;
+
+        Ok(value)
+    }
+
+    /// Get a [`MapEntry`] for the given key, for in-place manipulation.
+    ///
+    /// The stored value is read (and decoded) right away. The entry then allows modifying it
+    /// or inserting a default, with at most one write.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// let value = access
+    ///     .entry_api("foo")
+    ///     .unwrap()
+    ///     .and_modify(|v| *v += 1)
+    ///     .or_insert(1)
+    ///     .unwrap();
+    /// assert_eq!(value, 1);
+    ///
+    /// let value = access
+    ///     .entry_api("foo")
+    ///     .unwrap()
+    ///     .and_modify(|v| *v += 1)
+    ///     .or_insert(1)
+    ///     .unwrap();
+    /// assert_eq!(value, 2);
+    /// ```
+    pub fn entry_api<Q>(&mut self, key: &Q) -> Result<MapEntry<'_, E, T, S>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let entry = self.entry_mut(key);
+        let value = entry.get()?;
+
+        Ok(MapEntry::new(entry, value))
+    }
+
+    /// Remove the value stored under the given key and return it.
+    ///
+    /// Returns `Ok(None)` if there's no value stored under the key. If the stored value
+    /// fails to decode, the error is returned and the entry is left in place.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// assert_eq!(access.take("foo").unwrap(), Some(1337));
+    /// assert_eq!(access.take("foo").unwrap(), None);
+    /// assert_eq!(access.entry("foo").get().unwrap(), None);
+    /// ```
+    pub fn take<Q>(&mut self, key: &Q) -> Result<Option<T>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let mut entry = self.entry_mut(key);
+
+        let value = entry.get()?;
+        if value.is_some() {
+            entry.remove();
+        }
+
+        Ok(value)
+    }
+}
+
+impl<K, T, E, S> MapAccess<K, Item<T, E>, S>
+where
+    K: Key,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: IterableStorage + StorageMut,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Replace the entire contents of the map with the given entries.
+    ///
+    /// All existing entries are removed before the new ones are written. This is meant for
+    /// things like contract migrations, where a container needs to be wiped and repopulated.
+    ///
+    /// All values are encoded before anything is written, so if any of them fails to encode,
+    /// the map is left untouched. Beyond that, this is not a transaction. On a blockchain,
+    /// the changes are only atomic within the message that makes them (a failing message
+    /// reverts all of its state changes) - there's no rollback across messages.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1).unwrap();
+    /// access.entry_mut("bar").set(&2).unwrap();
+    ///
+    /// access
+    ///     .reset_with([("bar".to_string(), 3), ("baz".to_string(), 4)])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(access.entry("foo").get().unwrap(), None);
+    /// assert_eq!(access.entry("bar").get().unwrap(), Some(3));
+    /// assert_eq!(access.entry("baz").get().unwrap(), Some(4));
+    /// ```
+    pub fn reset_with<It>(&mut self, entries: It) -> Result<(), E::EncodeError>
+    where
+        It: IntoIterator<Item = (K, T)>,
+    {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| Ok((Self::encode_entry_key(&key), value.encode()?)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        remove_range(&mut self.storage, Bound::Unbounded, Bound::Unbounded);
+
+        for (key, value) in entries {
+            self.storage.set(&key, &value);
+        }
+
+        Ok(())
+    }
+}
+
+impl<K, T, E, S> MapAccess<K, Item<T, E>, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    S: IterableStorage + StorageMut,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    /// Remove all entries for which the predicate returns `false`.
+    ///
+    /// The predicate is called with the key and value of each entry, in storage order.
+    ///
+    /// Returns the number of entries removed. If decoding any entry fails, nothing is removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.set("foo", &1).unwrap();
+    /// access.set("bar", &2).unwrap();
+    /// access.set("baz", &3).unwrap();
+    ///
+    /// assert_eq!(access.retain(|_, value| value % 2 == 1).unwrap(), 1);
+    /// assert_eq!(access.get("bar").unwrap(), None);
+    /// assert_eq!(access.get("baz").unwrap(), Some(3));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn retain<F>(
+        &mut self,
+        mut f: F,
+    ) -> Result<u32, KVDecodeError<MapKeyDecodeError<K::Error, ItemKeyDecodeError>, E::DecodeError>>
+    where
+        F: FnMut(&K, &T) -> bool,
+    {
+        // collect the keys first so that we don't mutate the storage while iterating over it
+        let mut to_remove = Vec::new();
+        for (key, value) in self.storage.pairs(Bound::Unbounded, Bound::Unbounded) {
+            let ((map_key, ()), value) = decode_pair::<Map<K, Item<T, E>>>(&key, &value)?;
+
+            if !f(&map_key, &value) {
+                to_remove.push(key);
+            }
+        }
+
+        for key in &to_remove {
+            self.storage.remove(key);
+        }
+
+        Ok(to_remove.len() as u32)
+    }
+
+    /// Apply `f` to every entry, writing back the value it returns or removing the entry if
+    /// it returns `None`.
+    ///
+    /// All entries are decoded and all new values encoded before anything is written, so if
+    /// any of that fails, the map is left untouched.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.set("foo", &1).unwrap();
+    /// access.set("bar", &2).unwrap();
+    ///
+    /// access
+    ///     .update_all(|key, value| (key != "bar").then_some(value * 10))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(access.get("foo").unwrap(), Some(10));
+    /// assert_eq!(access.get("bar").unwrap(), None);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn update_all<F>(
+        &mut self,
+        mut f: F,
+    ) -> Result<
+        (),
+        UpdateError<
+            KVDecodeError<MapKeyDecodeError<K::Error, ItemKeyDecodeError>, E::DecodeError>,
+            E::EncodeError,
+        >,
+    >
+    where
+        F: FnMut(&K, T) -> Option<T>,
+    {
+        // compute all the changes first so that we don't mutate the storage while iterating
+        // over it
+        let mut changes = Vec::new();
+        for (key, value) in self.storage.pairs(Bound::Unbounded, Bound::Unbounded) {
+            let ((map_key, ()), value) =
+                decode_pair::<Map<K, Item<T, E>>>(&key, &value).map_err(UpdateError::Decode)?;
+
+            let new_value = f(&map_key, value)
+                .map(|value| value.encode())
+                .transpose()
+                .map_err(UpdateError::Encode)?;
+            changes.push((key, new_value));
+        }
+
+        for (key, new_value) in changes {
+            match new_value {
+                Some(value) => self.storage.set(&key, &value),
+                None => self.storage.remove(&key),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<K, V, S> MapAccess<K, V, S>
+where
+    K: OwnedKey,
+    V: Storable<Kind = NonTerminal>,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    /// Iterate over the distinct keys of this map, pairing each with an immutable accessor
+    /// for the inner container stored under it.
+    ///
+    /// Keys are yielded in the order they're stored in. For dynamically sized keys (like
+    /// `String`), this is not lexicographical order - see
+    /// [`BoundedIterableAccessor`] for why.
+    ///
+    /// Only keys under which the inner container has at least one entry are yielded. Each
+    /// key is found with a single seek, so this doesn't walk every entry of every inner
+    /// container.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Column, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Column<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").push(&1337).unwrap();
+    /// access.entry_mut("foo").push(&42).unwrap();
+    /// access.entry_mut("bar").push(&9001).unwrap();
+    ///
+    /// let lens = access
+    ///     .sub_accessors()
+    ///     .map(|res| {
+    ///         let (key, column) = res.unwrap();
+    ///         (key, column.len().unwrap())
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lens, vec![("bar".to_string(), 1), ("foo".to_string(), 2)]);
+    /// ```
+    pub fn sub_accessors(&self) -> SubAccessors<'_, K, V, S> {
+        SubAccessors {
+            storage: &self.storage,
+            start: Some(Bound::Unbounded),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Iterate over the distinct keys of this map.
+    ///
+    /// This is like [`sub_accessors`](Self::sub_accessors), but only yields the keys. Only
+    /// the map key portion of each storage key is decoded - the inner containers' subkeys are
+    /// never looked at. Each key is found with a single seek, so this is cheap even for maps
+    /// of large inner containers.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Column, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Column<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").push(&1337).unwrap();
+    /// access.entry_mut("foo").push(&42).unwrap();
+    /// access.entry_mut("bar").push(&9001).unwrap();
+    ///
+    /// assert_eq!(
+    ///     access.outer_keys().collect::<Result<Vec<_>, _>>().unwrap(),
+    ///     vec!["bar".to_string(), "foo".to_string()]
+    /// );
+    /// ```
+    pub fn outer_keys(&self) -> OuterKeys<'_, K, V, S> {
+        OuterKeys {
+            storage: &self.storage,
+            start: Some(Bound::Unbounded),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Count the distinct keys of this map.
+    ///
+    /// For a map of maps, this is the number of outer keys rather than the total number of
+    /// entries. Only keys under which the inner container has at least one entry are counted.
+    ///
+    /// The map keys aren't decoded. Like [`sub_accessors`](Self::sub_accessors), this takes
+    /// one seek per distinct key rather than walking every entry.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Map<u32, Item<u64, TestEncoding>>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("alice").entry_mut(&1).set(&1337).unwrap();
+    /// access.entry_mut("alice").entry_mut(&2).set(&42).unwrap();
+    /// access.entry_mut("bob").entry_mut(&1).set(&9001).unwrap();
+    ///
+    /// assert_eq!(access.outer_len().unwrap(), 2);
+    /// ```
+    pub fn outer_len(&self) -> Result<u32, MapKeyDecodeError<K::Error, V::KeyDecodeError>> {
+        let mut count = 0;
+        let mut start = Some(Bound::Unbounded);
+
+        while let Some(encoded_key) = next_outer_key::<K, V, S>(&self.storage, &mut start) {
+            encoded_key?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// The iterator returned by [`MapAccess::sub_accessors`].
+pub struct SubAccessors<'a, K, V, S> {
+    storage: &'a S,
+    /// Where to look for the next key. `None` once we're done.
+    start: Option<Bound<Vec<u8>>>,
+    phantom: PhantomData<(K, V)>,
+}
+
+impl<'a, K, V, S> Iterator for SubAccessors<'a, K, V, S>
+where
+    K: OwnedKey,
+    V: Storable<Kind = NonTerminal>,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    type Item = Result<
+        (K, V::Accessor<StorageBranch<&'a S>>),
+        MapKeyDecodeError<K::Error, V::KeyDecodeError>,
+    >;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let encoded_key = match next_outer_key::<K, V, S>(self.storage, &mut self.start)? {
+            Ok(encoded_key) => encoded_key,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(decode_outer_key::<K, V>(&encoded_key).map(|map_key| {
+            (
+                map_key,
+                V::access_impl(StorageBranch::new(self.storage, encoded_key)),
+            )
+        }))
+    }
+}
+
+/// The iterator returned by [`MapAccess::outer_keys`].
+pub struct OuterKeys<'a, K, V, S> {
+    storage: &'a S,
+    /// Where to look for the next key. `None` once we're done.
+    start: Option<Bound<Vec<u8>>>,
+    phantom: PhantomData<(K, V)>,
+}
+
+impl<K, V, S> Iterator for OuterKeys<'_, K, V, S>
+where
+    K: OwnedKey,
+    V: Storable<Kind = NonTerminal>,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    type Item = Result<K, MapKeyDecodeError<K::Error, V::KeyDecodeError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let encoded_key = match next_outer_key::<K, V, S>(self.storage, &mut self.start)? {
+            Ok(encoded_key) => encoded_key,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(decode_outer_key::<K, V>(&encoded_key))
+    }
+}
+
+/// Find the next distinct encoded map key at or after `start`, advancing `start` past it.
+#[allow(clippy::type_complexity)]
+fn next_outer_key<K, V, S>(
+    storage: &S,
+    start: &mut Option<Bound<Vec<u8>>>,
+) -> Option<Result<Vec<u8>, MapKeyDecodeError<K::Error, V::KeyDecodeError>>>
+where
+    K: OwnedKey,
+    V: Storable<Kind = NonTerminal>,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    let bound = start.take()?;
+    let key = storage
+        .keys(bound.as_ref().map(|b| b.as_slice()), Bound::Unbounded)
+        .next()?;
+
+    let encoded_key = match outer_key(&key, <(K::Kind, V::Kind)>::BEHAVIOR) {
+        Ok(encoded_key) => encoded_key,
+        Err(e) => return Some(Err(e)),
+    };
+
+    // The next distinct key is the first one that doesn't start with this one.
+    *start = successor(encoded_key).map(Bound::Included);
+
+    Some(Ok(encoded_key.to_vec()))
+}
+
+/// Decode a map key found by [`next_outer_key`].
+fn decode_outer_key<K, V>(
+    encoded_key: &[u8],
+) -> Result<K, MapKeyDecodeError<K::Error, V::KeyDecodeError>>
+where
+    K: OwnedKey,
+    V: Storable,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    let map_key = match <(K::Kind, V::Kind)>::BEHAVIOR {
+        KeyEncoding::LenPrefix => K::from_bytes(split_len_prefix(encoded_key)?.1),
+        _ => K::from_bytes(encoded_key),
+    };
+
+    map_key.map_err(MapKeyDecodeError::OuterKey)
+}
+
+/// Returns the leading part of a raw key of a map with non-terminal values, i.e. the encoded
+/// map key (including its length prefix, if any) without the inner container's subkey.
+fn outer_key<K: core::fmt::Display, I: core::fmt::Display>(
+    key: &[u8],
+    behavior: KeyEncoding,
+) -> Result<&[u8], MapKeyDecodeError<K, I>> {
+    match behavior {
+        KeyEncoding::LenPrefix => {
+            let (len, rest) = split_len_prefix(key)?;
+            let prefix_len = key.len() - rest.len();
+            key.get(..prefix_len + len)
+                .ok_or(MapKeyDecodeError::KeyTooShort(len))
+        }
+        KeyEncoding::UseN(n) => key.get(..n).ok_or(MapKeyDecodeError::KeyTooShort(n)),
+        KeyEncoding::UseRest => unreachable!("non-terminal values never use the rest"),
+    }
+}
+
+/// The length prefix byte signalling that the actual length follows as a varint.
+const LEN_ESCAPE: u8 = 0xFF;
+
+/// Prefix `bytes` with their length.
+///
+/// Lengths below 255 take a single byte, the same way they did with the plain `u8` length
+/// prefix used before, so existing keys keep their encoding. Longer lengths are written as
+/// [`LEN_ESCAPE`] followed by the full length as an unsigned LEB128 varint.
+fn len_prefix<T: AsRef<[u8]>>(bytes: T) -> Vec<u8> {
+    let bytes = bytes.as_ref();
+    let mut result = Vec::with_capacity(bytes.len() + 3);
+
+    push_len_prefix(&mut result, bytes.len());
+
+    result.extend_from_slice(bytes);
+    result
+}
+
+/// Append the length prefix [`len_prefix`] writes for `len` bytes.
+fn push_len_prefix(result: &mut Vec<u8>, mut len: usize) {
+    if len < LEN_ESCAPE as usize {
+        result.push(len as u8);
+    } else {
+        result.push(LEN_ESCAPE);
+        while len >= 0x80 {
+            result.push(len as u8 | 0x80);
+            len >>= 7;
+        }
+        result.push(len as u8);
+    }
+}
+
+/// Split off a length prefix written by [`len_prefix`], returning the length and the rest of
+/// the key.
+fn split_len_prefix<K: core::fmt::Display, I: core::fmt::Display>(
+    key: &[u8],
+) -> Result<(usize, &[u8]), MapKeyDecodeError<K, I>> {
+    if key.is_empty() {
+        return Err(MapKeyDecodeError::EmptyKey);
+    }
+
+    decode_len_prefix(key).ok_or(MapKeyDecodeError::InvalidLengthPrefix)
+}
+
+/// Decode a length prefix written by [`push_len_prefix`], returning the length and the rest
+/// of the bytes.
+///
+/// Only the encoding [`push_len_prefix`] produces is accepted, so that every length has
+/// exactly one encoding: escaped lengths below 255 and varints with trailing zero bytes are
+/// rejected. Returns `None` for those, and if the bytes end before the prefix does.
+fn decode_len_prefix(bytes: &[u8]) -> Option<(usize, &[u8])> {
+    let (&first, bytes) = bytes.split_first()?;
+    if first != LEN_ESCAPE {
+        return Some((first as usize, bytes));
+    }
+
+    let mut len = 0usize;
+    for (i, &byte) in bytes.iter().enumerate() {
+        let shift = 7 * i as u32;
+        let bits = ((byte & 0x7F) as usize)
+            .checked_shl(shift)
+            .filter(|bits| bits >> shift == (byte & 0x7F) as usize)?;
+        len |= bits;
+
+        if byte & 0x80 == 0 {
+            if (i > 0 && byte == 0) || len < LEN_ESCAPE as usize {
+                return None;
+            }
+            return Some((len, &bytes[i + 1..]));
+        }
+    }
+
+    None
+}
+
+impl<K, V, S> IterableAccessor for MapAccess<K, V, S>
+where
+    K: OwnedKey,
+    V: Storable,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    type Storable = Map<K, V>;
+    type Storage = S;
+
+    fn storage(&self) -> &Self::Storage {
+        &self.storage
+    }
+}
+
+// The following dance is necessary to make bounded iteration unavailable for maps
+// that have both dynamic keys and "non-terminal" values (i.e. maps of maps, maps of columns, etc).
+//
+// This is because in cases where the key is dynamically size **and** there's another key
+// after it, we have to length-prefix the key. This makes bounded iteration behave differently
+// than in other cases (and rather unintuitively).
+
+impl<K, V, S> BoundedIterableAccessor for MapAccess<K, V, S>
+where
+    K: OwnedKey,
+    V: Storable,
+    <V as Storable>::KeyDecodeError: core::fmt::Display,
+    S: IterableStorage,
+    (K::Kind, V::Kind): BoundedIterationAllowed + KeyEncodingT,
+{
+}
+
+trait BoundedIterationAllowed {}
+
+impl<const L: usize> BoundedIterationAllowed for (FixedSizeKey<L>, Terminal) {}
+impl<const L: usize> BoundedIterationAllowed for (FixedSizeKey<L>, NonTerminal) {}
+impl BoundedIterationAllowed for (DynamicKey, Terminal) {}
+
+impl<K, T, E> RefTarget for Map<K, Item<T, E>>
+where
+    K: OwnedKey,
+    E: Encoding,
+    T: EncodableWith<E> + DecodableWith<E>,
+    (K::Kind, Terminal): KeyEncodingT,
+{
+    type Id = K;
+    type Target = T;
+    type ResolveError = E::DecodeError;
+
+    fn resolve_id<S: Storage>(
+        access: &MapAccess<K, Item<T, E>, S>,
+        id: &K,
+    ) -> Result<Option<T>, E::DecodeError> {
+        access.entry(id).get()
+    }
+}
+
+impl<K, V, Q> BoundFor<Map<K, V>> for &Q
+where
+    K: Borrow<Q> + OwnedKey,
+    V: Storable,
+    Q: Key + ?Sized,
+    (K::Kind, V::Kind): KeyEncodingT,
+{
+    fn into_bytes(self) -> Vec<u8> {
+        let behavior = <(K::Kind, V::Kind)>::BEHAVIOR;
+
+        match behavior {
+            KeyEncoding::LenPrefix => len_prefix(self.encode()),
+            _ => self.encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ops::Bound;
+
+    use super::*;
+
+    use crate::containers::Item;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+    use storey_storage::Storage as _;
+
+    #[test]
+    fn map() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+
+        map.access(&mut storage)
+            .entry_mut("foo")
+            .set(&1337)
+            .unwrap();
+
+        assert_eq!(map.access(&storage).entry("foo").get().unwrap(), Some(1337));
+        assert_eq!(
+            storage.get(&[0, 102, 111, 111]),
+            Some(1337u64.to_le_bytes().to_vec())
+        );
+        map.access(&mut storage).entry_mut("foo").remove();
+
+        assert_eq!(map.access(&storage).entry("foo").get().unwrap(), None);
+        assert_eq!(map.access(&storage).entry("bar").get().unwrap(), None);
+    }
+
+    #[test]
+    fn try_entry() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&1).set(&0).unwrap();
+        access.entry_mut(&2).set(&5).unwrap();
+        access.entry_mut(&2).remove();
+
+        assert_eq!(access.try_entry(&1).unwrap().get().unwrap(), Some(0));
+        assert!(access.try_entry(&2).is_none());
+        assert!(access.try_entry(&3).is_none());
+
+        let map = Map::<String, Map<u32, Item<u64, TestEncoding>>>::new(1);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foobar").entry_mut(&1).set(&0).unwrap();
+
+        assert!(access.try_entry("foobar").is_some());
+        assert!(access.try_entry("foo").is_none());
+        assert!(access.try_entry("bar").is_none());
+    }
+
+    #[test]
+    fn bounded_iter_float_keys() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<f64, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        for (i, score) in [2.5, -1.0, 0.0, -7.25, 10.0].into_iter().enumerate() {
+            access.entry_mut(&score).set(&(i as u64)).unwrap();
+        }
+
+        let keys = access
+            .bounded_keys(Bound::Included(&-2.0), Bound::Excluded(&10.0))
+            .map(|k| k.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![-1.0, 0.0, 2.5]);
+
+        let keys = access
+            .bounded_keys(Bound::Unbounded, Bound::Included(&-1.0))
+            .map(|k| k.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![-7.25, -1.0]);
+    }
+
+    #[test]
+    fn bounded_iter_dyn_map_of_item() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut("baz").set(&69).unwrap();
+
+        let items = access
+            .bounded_pairs(Bound::Included("bar"), Bound::Excluded("bazz"))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            items,
+            vec![(("bar".to_string(), ()), 42), (("baz".to_string(), ()), 69)]
+        );
+
+        let items = access
+            .bounded_pairs(Bound::Excluded("bar"), Bound::Included("foo"))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            items,
+            vec![
+                (("baz".to_string(), ()), 69),
+                (("foo".to_string(), ()), 1337)
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_static_map_of_item() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut("baz").set(&69).unwrap();
+
+        let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            items,
+            vec![
+                (("bar".to_string(), ()), 42),
+                (("baz".to_string(), ()), 69),
+                (("foo".to_string(), ()), 1337)
+            ]
+        );
+    }
+
+    #[test]
+    fn bounded_iter_static_map_of_map() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Map<String, Item<u64, TestEncoding>>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&2).entry_mut("bar").set(&1337).unwrap();
+        access.entry_mut(&3).entry_mut("baz").set(&42).unwrap();
+        access.entry_mut(&4).entry_mut("quux").set(&69).unwrap();
+
+        let items = access
+            .bounded_pairs(Bound::Included(&2), Bound::Excluded(&4))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                ((2, ("bar".to_string(), ())), 1337),
+                ((3, ("baz".to_string(), ())), 42)
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+
+        let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            items,
+            vec![
+                (("bar".to_string(), ()), 42),
+                (("foo".to_string(), ()), 1337)
+            ]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+
+        let keys = access.keys().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(keys, vec![("bar".to_string(), ()), ("foo".to_string(), ())])
+    }
+
+    #[test]
+    fn values() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+
+        let values = access.values().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(values, vec![42, 1337])
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut("baz").set(&7).unwrap();
+
+        assert_eq!(
+            access.pairs().next_back(),
+            Some(Ok((("foo".to_string(), ()), 1337)))
+        );
+        assert_eq!(access.keys().next_back(), Some(Ok(("foo".to_string(), ()))));
+
+        // both ends can be consumed from the same iterator
+        let mut values = access.values();
+        assert_eq!(values.next_back(), Some(Ok(1337)));
+        assert_eq!(values.next(), Some(Ok(42)));
+        assert_eq!(values.next_back(), Some(Ok(7)));
+        assert_eq!(values.next(), None);
+        assert_eq!(values.next_back(), None);
+    }
+
+    #[test]
+    fn size_hint() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut("baz").set(&7).unwrap();
+
+        let mut pairs = access.pairs();
+        assert_eq!(pairs.size_hint(), (3, Some(3)));
+        pairs.next();
+        assert_eq!(pairs.size_hint(), (2, Some(2)));
+
+        assert_eq!(access.keys().size_hint(), (3, Some(3)));
+        assert_eq!(access.values().size_hint(), (3, Some(3)));
+    }
+
+    #[test]
+    fn remove_range() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        for i in 1..=8 {
+            access.entry_mut(&i).set(&(i as u64 * 10)).unwrap();
+        }
+
+        // remove a middle range, exclusive end
+        assert_eq!(
+            access.remove_range(Bound::Included(&2), Bound::Excluded(&4)),
+            2
+        );
+        // exclusive start, inclusive end
+        assert_eq!(
+            access.remove_range(Bound::Excluded(&4), Bound::Included(&6)),
+            2
+        );
+        // nothing left in this range
+        assert_eq!(
+            access.remove_range(Bound::Included(&2), Bound::Excluded(&4)),
+            0
+        );
+
+        let keys = access
+            .keys()
+            .map(|k| k.map(|(k, ())| k))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(keys, vec![1, 4, 7, 8]);
+
+        // unbounded
+        assert_eq!(
+            access.remove_range(Bound::Included(&7), Bound::Unbounded),
+            2
+        );
+        assert_eq!(
+            access.remove_range(Bound::<&u32>::Unbounded, Bound::Unbounded),
+            2
+        );
+        assert_eq!(access.keys().count(), 0);
+    }
+
+    #[test]
+    fn remove_range_non_terminal() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Map<String, Item<u64, TestEncoding>>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        for i in 1..=5 {
+            access.entry_mut(&i).entry_mut("a").set(&1).unwrap();
+            access.entry_mut(&i).entry_mut("b").set(&2).unwrap();
+        }
+        access.entry_mut(&u32::MAX).entry_mut("a").set(&3).unwrap();
+
+        // bounds apply to whole inner maps
+        assert_eq!(
+            access.remove_range(Bound::Excluded(&1), Bound::Included(&2)),
+            2
+        );
+        assert_eq!(
+            access.remove_range(Bound::Excluded(&3), Bound::Excluded(&5)),
+            2
+        );
+        assert_eq!(
+            access.remove_range(Bound::Excluded(&u32::MAX), Bound::Unbounded),
+            0
+        );
+
+        let outer_keys = access.outer_keys().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(outer_keys, vec![1, 3, 5, u32::MAX]);
+
+        assert_eq!(
+            access.remove_range(Bound::Included(&5), Bound::Included(&u32::MAX)),
+            3
+        );
+        assert_eq!(access.keys().count(), 4);
+    }
+
+    #[test]
+    fn clone_accessor() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+        access.entry_mut("foo").set(&1337).unwrap();
+        access.entry_mut("bar").set(&42).unwrap();
+
+        fn sum<S: crate::storage::IterableStorage>(
+            access: MapAccess<String, Item<u64, TestEncoding>, S>,
+        ) -> u64 {
+            access.values().map(|v| v.unwrap()).sum()
+        }
+
+        let access = map.access(&storage);
+        let copy = access.clone();
+
+        assert_eq!(sum(copy), 1379);
+        assert_eq!(access.entry("foo").get().unwrap(), Some(1337));
+    }
+
+    #[test]
+    fn entry_with_key() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let access = map.access(&storage);
+        let (key, entry) = access.entry_with_key("foo");
+        assert_eq!(entry.get().unwrap(), None);
+
+        map.access(&mut storage)
+            .entry_mut("foo")
+            .set(&1337)
+            .unwrap();
+        assert_eq!(storage.get(&key), Some(1337u64.to_le_bytes().to_vec()));
+
+        // keys of non-terminal values are length-prefixed
+        let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(1);
+        let (key, _) = map.access(&storage).entry_with_key("foo");
+        assert_eq!(key, [&[1, 3][..], b"foo"].concat());
+
+        map.access(&mut storage)
+            .entry_mut("foo")
+            .entry_mut("bar")
+            .set(&42)
+            .unwrap();
+        assert_eq!(
+            storage.get(&[&key[..], b"bar"].concat()),
+            Some(42u64.to_le_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn first_n_last_n() {
+        use crate::containers::RevIterableAccessor as _;
+
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.first_n(3).unwrap(), vec![]);
+        assert_eq!(access.last_n(3).unwrap(), vec![]);
+
+        for i in 1..=5 {
+            access.entry_mut(&i).set(&(i as u64 * 10)).unwrap();
+        }
+
+        assert_eq!(
+            access.first_n(2).unwrap(),
+            vec![((1, ()), 10), ((2, ()), 20)]
+        );
+        assert_eq!(
+            access.last_n(2).unwrap(),
+            vec![((5, ()), 50), ((4, ()), 40)]
+        );
+        assert_eq!(access.first_n(0).unwrap(), vec![]);
+        assert_eq!(access.last_n(10).unwrap().len(), 5);
+    }
 
-    use super::*;
+    #[test]
+    fn try_partition() {
+        let mut storage = TestStorage::new();
 
-    use crate::containers::Item;
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
 
-    use mocks::backend::TestStorage;
-    use mocks::encoding::TestEncoding;
-    use storey_storage::Storage as _;
+        assert_eq!(access.try_partition(|_| true).unwrap(), (vec![], vec![]));
+
+        for i in 1..=5 {
+            access.entry_mut(&i).set(&(i as u64 * 10)).unwrap();
+        }
+
+        let (even, odd) = access.try_partition(|(_, v)| v % 20 == 0).unwrap();
+        assert_eq!(even, vec![((2, ()), 20), ((4, ()), 40)]);
+        assert_eq!(odd, vec![((1, ()), 10), ((3, ()), 30), ((5, ()), 50)]);
+    }
 
     #[test]
-    fn map() {
+    fn is_empty_scan() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert!(access.is_empty_scan());
+
+        access.entry_mut("foo").entry_mut("bar").set(&1337).unwrap();
+        assert!(!access.is_empty_scan());
+        assert!(!access.entry("foo").is_empty_scan());
+        assert!(access.entry("baz").is_empty_scan());
+
+        access.entry_mut("foo").entry_mut("bar").remove();
+        assert!(access.is_empty_scan());
+    }
+
+    #[test]
+    fn contains_key() {
         let mut storage = TestStorage::new();
 
         let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
 
-        map.access(&mut storage)
-            .entry_mut("foo")
-            .set(&1337)
-            .unwrap();
+        access.entry_mut("foo").set(&1337).unwrap();
+        assert!(access.contains_key("foo"));
+        assert!(!access.contains_key("fo"));
+        assert!(!access.contains_key("fooo"));
 
-        assert_eq!(map.access(&storage).entry("foo").get().unwrap(), Some(1337));
+        access.entry_mut("foo").remove();
+        assert!(!access.contains_key("foo"));
+
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(1);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&7).set(&1337).unwrap();
+        assert!(access.contains_key(&7));
+        assert!(!access.contains_key(&8));
+
+        let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(2);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").entry_mut("bar").set(&1337).unwrap();
+        assert!(access.entry("foo").contains_key("bar"));
+        assert!(!access.entry("foo").contains_key("baz"));
+        assert!(!access.entry("foob").contains_key("ar"));
+    }
+
+    #[test]
+    fn entry_or_default_and_modify() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<u32, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.entry_or_default_mut(&1).unwrap(), 0);
+        assert_eq!(access.entry(&1).get().unwrap(), Some(0));
+
+        access.entry_mut(&2).set(&5).unwrap();
+        assert_eq!(access.entry_or_default_mut(&2).unwrap(), 5);
+
+        assert_eq!(access.modify(&2, |value| value.unwrap() * 2).unwrap(), 10);
+        assert_eq!(access.entry(&2).get().unwrap(), Some(10));
+
+        let mut seen = None;
         assert_eq!(
-            storage.get(&[0, 102, 111, 111]),
-            Some(1337u64.to_le_bytes().to_vec())
+            access
+                .modify(&3, |value| {
+                    seen = Some(value);
+                    7
+                })
+                .unwrap(),
+            7
         );
-        map.access(&mut storage).entry_mut("foo").remove();
+        assert_eq!(seen, Some(None));
+        assert_eq!(access.entry(&3).get().unwrap(), Some(7));
+    }
 
-        assert_eq!(map.access(&storage).entry("foo").get().unwrap(), None);
-        assert_eq!(map.access(&storage).entry("bar").get().unwrap(), None);
+    #[test]
+    fn clear() {
+        use crate::containers::Column;
+
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Column<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+        access.entry_mut("foo").push(&1).unwrap();
+        access.entry_mut("foo").push(&2).unwrap();
+        access.entry_mut("bar").push(&3).unwrap();
+
+        let other = Map::<String, Item<u64, TestEncoding>>::new(1);
+        other.access(&mut storage).entry_mut("foo").set(&4).unwrap();
+
+        let mut access = map.access(&mut storage);
+        access.clear();
+
+        assert_eq!(access.keys().count(), 0);
+        assert_eq!(access.entry("foo").len(), Ok(0));
+
+        // no data or metadata is left under the map's prefix
+        let leftover: Vec<_> = storage
+            .keys(Bound::Unbounded, Bound::Unbounded)
+            .filter(|key| key.first() == Some(&0) || key.starts_with(&[255, 0]))
+            .collect();
+        assert!(leftover.is_empty());
+
+        // other containers are unaffected
+        assert_eq!(other.access(&storage).entry("foo").get(), Ok(Some(4)));
+
+        // the map is still usable afterwards
+        let mut access = map.access(&mut storage);
+        assert_eq!(access.entry_mut("foo").push(&5), Ok(1));
     }
 
     #[test]
-    fn bounded_iter_dyn_map_of_item() {
+    fn entry_api() {
         let mut storage = TestStorage::new();
 
         let map = Map::<String, Item<u64, TestEncoding>>::new(0);
         let mut access = map.access(&mut storage);
 
-        access.entry_mut("foo").set(&1337).unwrap();
-        access.entry_mut("bar").set(&42).unwrap();
-        access.entry_mut("baz").set(&69).unwrap();
+        let entry = access.entry_api("foo").unwrap();
+        assert_eq!(entry.get(), None);
+        assert_eq!(entry.and_modify(|v| *v += 1).or_insert(10), Ok(10));
+
+        let entry = access.entry_api("foo").unwrap().and_modify(|v| *v += 1);
+        assert_eq!(entry.get(), Some(&11));
+        assert_eq!(entry.or_insert_with(|| unreachable!()), Ok(11));
+
+        assert_eq!(access.entry_api("foo").unwrap().or_insert(0), Ok(11));
+        assert_eq!(access.entry("foo").get(), Ok(Some(11)));
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.get("foo"), Ok(None));
+
+        access.set("foo", &1337).unwrap();
+        access.set("bar", &42).unwrap();
+        access.set("foo", &9001).unwrap();
+
+        assert_eq!(access.get("foo"), Ok(Some(9001)));
+        assert_eq!(access.get("bar"), Ok(Some(42)));
+        assert_eq!(access.entry("foo").get(), Ok(Some(9001)));
+
+        // works through an immutable accessor too
+        let access = map.access(&storage);
+        assert_eq!(access.get("bar"), Ok(Some(42)));
+    }
+
+    #[test]
+    fn retain() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.set("foo", &1).unwrap();
+        access.set("bar", &2).unwrap();
+        access.set("baz", &3).unwrap();
+        access.set("qux", &4).unwrap();
 
-        let items = access
-            .bounded_pairs(Bound::Included("bar"), Bound::Excluded("bazz"))
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
         assert_eq!(
-            items,
-            vec![(("bar".to_string(), ()), 42), (("baz".to_string(), ()), 69)]
+            access.retain(|key, value| key != "foo" && value % 2 == 1),
+            Ok(3)
+        );
+        assert_eq!(
+            access.keys().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![("baz".to_string(), ())]
         );
 
-        let items = access
-            .bounded_pairs(Bound::Excluded("bar"), Bound::Included("foo"))
-            .collect::<Result<Vec<_>, _>>()
+        assert_eq!(access.retain(|_, _| true), Ok(0));
+        assert_eq!(access.retain(|_, _| false), Ok(1));
+        assert!(access.is_empty_scan());
+    }
+
+    #[test]
+    fn update_all() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.set("foo", &1).unwrap();
+        access.set("bar", &2).unwrap();
+        access.set("baz", &3).unwrap();
+
+        access
+            .update_all(|key, value| match key.as_str() {
+                "bar" => None,
+                _ => Some(value + 10),
+            })
             .unwrap();
+
         assert_eq!(
-            items,
-            vec![
-                (("baz".to_string(), ()), 69),
-                (("foo".to_string(), ()), 1337)
-            ]
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(("baz".to_string(), ()), 13), (("foo".to_string(), ()), 11)]
         );
+
+        // a fresh accessor sees the changes
+        let access = map.access(&storage);
+        assert_eq!(access.get("foo"), Ok(Some(11)));
+        assert_eq!(access.get("bar"), Ok(None));
     }
 
     #[test]
-    fn iter_static_map_of_item() {
+    fn decode_error_leaves_map_untouched() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.set("foo", &1).unwrap();
+        access.set("bar", &2).unwrap();
+
+        // add an entry whose key isn't valid UTF-8
+        let value = storage.get(&[0, b'f', b'o', b'o']).unwrap();
+        storage.set(&[0, 0xff], &value);
+
+        let mut access = map.access(&mut storage);
+        assert!(matches!(
+            access.update_all(|_, value| Some(value + 1)),
+            Err(UpdateError::Decode(KVDecodeError::Key(_)))
+        ));
+        assert!(matches!(
+            access.retain(|_, _| false),
+            Err(KVDecodeError::Key(_))
+        ));
+
+        // nothing was written
+        assert_eq!(access.get("bar"), Ok(Some(2)));
+    }
+
+    #[test]
+    fn take() {
         let mut storage = TestStorage::new();
 
         let map = Map::<String, Item<u64, TestEncoding>>::new(0);
@@ -422,89 +2183,307 @@ mod tests {
 
         access.entry_mut("foo").set(&1337).unwrap();
         access.entry_mut("bar").set(&42).unwrap();
-        access.entry_mut("baz").set(&69).unwrap();
 
-        let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(access.take("foo"), Ok(Some(1337)));
+        assert_eq!(access.entry("foo").get(), Ok(None));
+        assert_eq!(access.entry("bar").get(), Ok(Some(42)));
+
+        assert_eq!(access.take("foo"), Ok(None));
+        assert_eq!(access.take("baz"), Ok(None));
+        assert_eq!(access.entry("bar").get(), Ok(Some(42)));
+    }
+
+    #[test]
+    fn outer_keys() {
+        use crate::containers::Column;
+
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Column<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.outer_keys().count(), 0);
+
+        for i in 0..10 {
+            access.entry_mut("foo").push(&i).unwrap();
+        }
+        access.entry_mut("bar").push(&1).unwrap();
+        access.entry_mut("baz").push(&1).unwrap();
+        access.entry_mut("baz").remove(1).unwrap();
+
         assert_eq!(
-            items,
-            vec![
-                (("bar".to_string(), ()), 42),
-                (("baz".to_string(), ()), 69),
-                (("foo".to_string(), ()), 1337)
-            ]
+            access.outer_keys().collect::<Result<Vec<_>, _>>(),
+            Ok(vec!["bar".to_string(), "foo".to_string()])
+        );
+
+        let map = Map::<u32, Map<u32, Item<u64, TestEncoding>>>::new(1);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&u32::MAX).entry_mut(&1).set(&1).unwrap();
+        access.entry_mut(&u32::MAX).entry_mut(&2).set(&1).unwrap();
+        access.entry_mut(&7).entry_mut(&1).set(&1).unwrap();
+
+        assert_eq!(
+            access.outer_keys().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![7, u32::MAX])
         );
     }
 
     #[test]
-    fn bounded_iter_static_map_of_map() {
+    fn outer_len() {
         let mut storage = TestStorage::new();
 
-        let map = Map::<u32, Map<String, Item<u64, TestEncoding>>>::new(0);
+        let map = Map::<String, Map<u32, Item<u64, TestEncoding>>>::new(0);
         let mut access = map.access(&mut storage);
 
-        access.entry_mut(&2).entry_mut("bar").set(&1337).unwrap();
-        access.entry_mut(&3).entry_mut("baz").set(&42).unwrap();
-        access.entry_mut(&4).entry_mut("quux").set(&69).unwrap();
+        assert_eq!(access.outer_len(), Ok(0));
 
-        let items = access
-            .bounded_pairs(Bound::Included(&2), Bound::Excluded(&4))
-            .collect::<Result<Vec<_>, _>>()
-            .unwrap();
+        for i in 0..5 {
+            access.entry_mut("foo").entry_mut(&i).set(&1).unwrap();
+        }
+        access.entry_mut("bar").entry_mut(&1).set(&1).unwrap();
+        access.entry_mut("\u{ff}").entry_mut(&1).set(&1).unwrap();
+        access.entry_mut("baz").entry_mut(&1).set(&1).unwrap();
+        access.entry_mut("baz").entry_mut(&1).remove();
+
+        assert_eq!(access.outer_len(), Ok(3));
+
+        let map = Map::<u32, Map<u32, Item<u64, TestEncoding>>>::new(1);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&u32::MAX).entry_mut(&1).set(&1).unwrap();
+        access.entry_mut(&u32::MAX).entry_mut(&2).set(&1).unwrap();
+        access.entry_mut(&0).entry_mut(&1).set(&1).unwrap();
+
+        assert_eq!(access.outer_len(), Ok(2));
+    }
+
+    #[test]
+    fn len_prefix_varint() {
+        for (len, prefix) in [
+            (0, &[0x00][..]),
+            (127, &[0x7F]),
+            (128, &[0x80]),
+            (254, &[0xFE]),
+            (255, &[0xFF, 0xFF, 0x01]),
+            (300, &[0xFF, 0xAC, 0x02]),
+            (16384, &[0xFF, 0x80, 0x80, 0x01]),
+        ] {
+            let bytes = vec![7; len];
+            let encoded = len_prefix(&bytes);
+            assert_eq!(&encoded[..prefix.len()], prefix);
+
+            let (decoded_len, rest) = split_len_prefix::<String, String>(&encoded).unwrap();
+            assert_eq!((decoded_len, rest), (len, &bytes[..]));
+        }
 
         assert_eq!(
-            items,
-            vec![
-                ((2, ("bar".to_string(), ())), 1337),
-                ((3, ("baz".to_string(), ())), 42)
-            ]
+            split_len_prefix::<String, String>(&[]),
+            Err(MapKeyDecodeError::EmptyKey)
+        );
+        // truncated in the middle of the prefix
+        assert_eq!(
+            split_len_prefix::<String, String>(&[0xFF]),
+            Err(MapKeyDecodeError::InvalidLengthPrefix)
+        );
+        assert_eq!(
+            split_len_prefix::<String, String>(&[0xFF, 0x80]),
+            Err(MapKeyDecodeError::InvalidLengthPrefix)
+        );
+        // more than fits in a `usize`
+        assert_eq!(
+            split_len_prefix::<String, String>(&[0xFF; 12]),
+            Err(MapKeyDecodeError::InvalidLengthPrefix)
+        );
+        // non-canonical encodings of 300 and 5
+        assert_eq!(
+            split_len_prefix::<String, String>(&[0xFF, 0xAC, 0x82, 0x00]),
+            Err(MapKeyDecodeError::InvalidLengthPrefix)
+        );
+        assert_eq!(
+            split_len_prefix::<String, String>(&[0xFF, 0x05]),
+            Err(MapKeyDecodeError::InvalidLengthPrefix)
         );
     }
 
     #[test]
-    fn pairs() {
+    fn outer_key_errors() {
+        use super::key::{InvalidUtf8, ResultKeyDecodeError};
+
+        type StringMap = Map<String, Item<u64, TestEncoding>>;
+        assert_eq!(
+            StringMap::decode_key(&[0xFF]),
+            Err(MapKeyDecodeError::OuterKey(InvalidUtf8))
+        );
+
+        type ResultMap = Map<Result<u32, String>, Map<u32, Item<u64, TestEncoding>>>;
+        let err = ResultMap::decode_key(&[3, 2, 0, 0, 0, 0, 0, 0, 1]).unwrap_err();
+        assert_eq!(
+            err,
+            MapKeyDecodeError::OuterKey(ResultKeyDecodeError::InvalidTag(2))
+        );
+        assert_eq!(err.to_string(), "map key decode error: invalid tag byte: 2");
+    }
+
+    #[test]
+    fn long_keys() {
         let mut storage = TestStorage::new();
 
-        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
         let mut access = map.access(&mut storage);
 
-        access.entry_mut("foo").set(&1337).unwrap();
-        access.entry_mut("bar").set(&42).unwrap();
+        // these would collide with a single byte length prefix (300 % 256 == 44)
+        let long = "a".repeat(300);
+        let short = "a".repeat(44);
+        access.entry_mut(&long).entry_mut("foo").set(&1).unwrap();
+        access.entry_mut(&short).entry_mut(&long).set(&2).unwrap();
+
+        assert_eq!(access.entry(&long).entry("foo").get(), Ok(Some(1)));
+        assert_eq!(access.entry(&short).entry(&long).get(), Ok(Some(2)));
+        assert_eq!(access.entry(&short).entry("foo").get(), Ok(None));
 
-        let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
         assert_eq!(
-            items,
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
             vec![
-                (("bar".to_string(), ()), 42),
-                (("foo".to_string(), ()), 1337)
+                ((short.clone(), (long.clone(), ())), 2),
+                ((long.clone(), ("foo".to_string(), ())), 1),
             ]
         );
+        assert_eq!(
+            access.outer_keys().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![short, long])
+        );
     }
 
     #[test]
-    fn keys() {
+    fn tuple_keys() {
         let mut storage = TestStorage::new();
 
-        let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+        let map = Map::<(u32, String, u8), Item<u64, TestEncoding>>::new(0);
         let mut access = map.access(&mut storage);
 
-        access.entry_mut("foo").set(&1337).unwrap();
-        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut(&(5, "foo".into(), 1)).set(&42).unwrap();
+        assert_eq!(access.entry(&(5, "foo".into(), 1)).get().unwrap(), Some(42));
+        assert_eq!(access.entry(&(5, "foo".into(), 2)).get().unwrap(), None);
 
-        let keys = access.keys().collect::<Result<Vec<_>, _>>().unwrap();
-        assert_eq!(keys, vec![("bar".to_string(), ()), ("foo".to_string(), ())])
+        // the string member is length-prefixed, the last member isn't
+        let raw_key = [0, 0, 0, 0, 5, 3, 102, 111, 111, 1];
+        assert_eq!(storage.get(&raw_key), Some(42u64.to_le_bytes().to_vec()));
+
+        assert_eq!(
+            Map::<(u32, String, u8), Item<u64, TestEncoding>>::decode_key(&raw_key[1..]),
+            Ok(((5, "foo".to_string(), 1), ()))
+        );
+
+        // in a nested map, the outer (dynamic) tuple key is length-prefixed as a whole
+        type Inner = Map<(u8, u16, u32), Item<u64, TestEncoding>>;
+        type Outer = Map<(String, u64), Inner>;
+        let key = (("bar".to_string(), 7), ((1, 2, 3), ()));
+        let outer = Key::encode(&key.0);
+        let inner = Key::encode(&(key.1).0);
+        assert_eq!((outer.len(), inner.len()), (1 + 3 + 8, 1 + 2 + 4));
+
+        let raw_key = [&[outer.len() as u8][..], &outer, &inner].concat();
+        assert_eq!(Outer::decode_key(&raw_key), Ok(key));
     }
 
     #[test]
-    fn values() {
+    fn reset_with() {
         let mut storage = TestStorage::new();
 
         let map = Map::<String, Item<u64, TestEncoding>>::new(0);
         let mut access = map.access(&mut storage);
 
-        access.entry_mut("foo").set(&1337).unwrap();
-        access.entry_mut("bar").set(&42).unwrap();
+        access.entry_mut("foo").set(&1).unwrap();
+        access.entry_mut("bar").set(&2).unwrap();
+        access.entry_mut("baz").set(&3).unwrap();
 
-        let values = access.values().collect::<Result<Vec<_>, _>>().unwrap();
-        assert_eq!(values, vec![42, 1337])
+        access
+            .reset_with([("baz".to_string(), 4), ("qux".to_string(), 5)])
+            .unwrap();
+
+        assert_eq!(
+            access.pairs().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![(("baz".to_string(), ()), 4), (("qux".to_string(), ()), 5)]
+        );
+
+        access.reset_with([]).unwrap();
+        assert!(access.is_empty_scan());
+    }
+
+    #[test]
+    fn access_branch() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
+
+        let branch = StorageBranch::new(&mut storage, vec![0]);
+        map.access_branch(branch)
+            .entry_mut("foo")
+            .entry_mut("bar")
+            .set(&1337)
+            .unwrap();
+
+        assert_eq!(
+            map.access(&storage)
+                .entry("foo")
+                .entry("bar")
+                .get()
+                .unwrap(),
+            Some(1337)
+        );
+
+        // a branch pointing elsewhere sees nothing
+        let branch = StorageBranch::new(&storage, vec![1]);
+        assert!(map.access_branch(branch).is_empty_scan());
+    }
+
+    #[test]
+    fn sub_accessors() {
+        use crate::containers::Column;
+
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Column<u64, TestEncoding>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        assert_eq!(access.sub_accessors().count(), 0);
+
+        for (key, n) in [("foo", 3), ("bar", 2), ("baz", 5), ("\u{ff}", 1)] {
+            for i in 0..n {
+                access.entry_mut(key).push(&i).unwrap();
+            }
+        }
+        // a column with all entries removed has nothing stored
+        access.entry_mut("qux").push(&1).unwrap();
+        access.entry_mut("qux").remove(1).unwrap();
+
+        let keys = access
+            .sub_accessors()
+            .map(|res| res.unwrap().0)
+            .collect::<Vec<_>>();
+        // keys are length-prefixed, so shorter keys come first
+        assert_eq!(keys, vec!["\u{ff}", "bar", "baz", "foo"]);
+
+        let total: u32 = access
+            .sub_accessors()
+            .map(|res| res.unwrap().1.len().unwrap())
+            .sum();
+        assert_eq!(total, 11);
+
+        // fixed-size keys
+        let map = Map::<u32, Map<u32, Item<u64, TestEncoding>>>::new(1);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut(&2).entry_mut(&1).set(&1).unwrap();
+        access.entry_mut(&2).entry_mut(&5).set(&1).unwrap();
+        access.entry_mut(&u32::MAX).entry_mut(&1).set(&1).unwrap();
+        access.entry_mut(&7).entry_mut(&3).set(&1).unwrap();
+
+        let keys = access
+            .sub_accessors()
+            .map(|res| res.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![2, 7, u32::MAX]);
     }
 }
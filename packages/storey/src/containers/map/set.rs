@@ -0,0 +1,699 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::iter::Peekable;
+use core::marker::PhantomData;
+use core::ops::Bound;
+
+use crate::containers::{
+    BoundFor, BoundedIterableAccessor, IterableAccessor, NonTerminal, Storable,
+};
+use crate::storage::{IterableStorage, Storage, StorageBranch, StorageMut, StorageRef};
+
+use super::{Key, OwnedKey};
+
+/// The value stored under every member.
+///
+/// Some backends (notably *CosmWasm*) don't support empty values, so a single byte is
+/// stored instead.
+const MEMBER_MARKER: &[u8] = &[1];
+
+/// A set of members stored as keys.
+///
+/// Members are laid out like the keys of a [`Map`](super::Map) of
+/// [`Item`](crate::containers::Item)s, each under a one-byte marker value. The set can be
+/// nested in other containers, e.g. a `Map<String, Set<u32>>`.
+///
+/// Members are iterated over (in key order) with the [`IterableAccessor`] methods - the
+/// member is the key, and the value is always `()`.
+///
+/// # Example
+/// ```
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::{IterableAccessor as _, Set};
+///
+/// let mut storage = TestStorage::new();
+/// let set = Set::<String>::new(0);
+/// let mut access = set.access(&mut storage);
+///
+/// access.insert("foo");
+/// access.insert("bar");
+/// access.insert("foo");
+///
+/// assert!(access.contains("foo"));
+/// assert!(!access.contains("baz"));
+///
+/// access.remove("bar");
+/// assert_eq!(
+///     access.keys().collect::<Result<Vec<_>, _>>().unwrap(),
+///     vec!["foo".to_string()]
+/// );
+/// ```
+pub struct Set<T> {
+    prefix: u8,
+    phantom: PhantomData<T>,
+}
+
+impl<T> Set<T>
+where
+    T: OwnedKey,
+{
+    /// Creates a new set with the given prefix.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    ///
+    /// The key provided here is used as a prefix for all keys managed by the set.
+    pub const fn new(prefix: u8) -> Self {
+        Self {
+            prefix,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquires an accessor for the set.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Set;
+    ///
+    /// // immutable access
+    /// let storage = TestStorage::new();
+    /// let set = Set::<u32>::new(0);
+    /// let access = set.access(&storage);
+    ///
+    /// // mutable access
+    /// let mut storage = TestStorage::new();
+    /// let set = Set::<u32>::new(0);
+    /// let mut access = set.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(&self, storage: S) -> SetAccess<T, StorageBranch<S>> {
+        Self::access_impl(StorageBranch::new(storage, vec![self.prefix]))
+    }
+}
+
+impl<T> Storable for Set<T>
+where
+    T: OwnedKey,
+{
+    type Kind = NonTerminal;
+    type Accessor<S> = SetAccess<T, S>;
+    type Key = T;
+    type KeyDecodeError = InvalidMember;
+    type Value = ();
+    type ValueDecodeError = Infallible;
+
+    fn access_impl<S>(storage: S) -> SetAccess<T, S> {
+        SetAccess {
+            storage,
+            phantom: PhantomData,
+        }
+    }
+
+    fn decode_key(key: &[u8]) -> Result<T, InvalidMember> {
+        T::from_bytes(key).map_err(|_| InvalidMember)
+    }
+
+    fn decode_value(_value: &[u8]) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// An error type representing a failure to decode a set member.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+#[error("invalid set member")]
+pub struct InvalidMember;
+
+impl crate::error::StoreyError for InvalidMember {}
+
+/// An accessor for a set.
+///
+/// The accessor provides methods for interacting with the set in storage.
+pub struct SetAccess<T, S> {
+    storage: S,
+    phantom: PhantomData<T>,
+}
+
+impl<T, S> Clone for SetAccess<T, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, S> SetAccess<T, S>
+where
+    T: OwnedKey,
+    S: Storage,
+{
+    /// Check whether the given value is a member of the set.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Set;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let set = Set::<u32>::new(0);
+    /// let mut access = set.access(&mut storage);
+    ///
+    /// access.insert(&5);
+    ///
+    /// assert!(access.contains(&5));
+    /// assert!(!access.contains(&6));
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Key<Kind = T::Kind> + ?Sized,
+    {
+        self.storage.has(&value.encode())
+    }
+}
+
+impl<T, S> SetAccess<T, S>
+where
+    T: OwnedKey,
+    S: Storage + StorageMut,
+{
+    /// Add the given value to the set. Inserting a value that's already a member has no
+    /// effect.
+    pub fn insert<Q>(&mut self, value: &Q)
+    where
+        T: Borrow<Q>,
+        Q: Key<Kind = T::Kind> + ?Sized,
+    {
+        self.storage.set(&value.encode(), MEMBER_MARKER);
+    }
+
+    /// Remove the given value from the set. Removing a value that isn't a member has no
+    /// effect.
+    pub fn remove<Q>(&mut self, value: &Q)
+    where
+        T: Borrow<Q>,
+        Q: Key<Kind = T::Kind> + ?Sized,
+    {
+        self.storage.remove(&value.encode());
+    }
+}
+
+impl<T, S> SetAccess<T, S>
+where
+    T: OwnedKey,
+    S: IterableStorage,
+{
+    /// Iterate over the members of either this set or `other`, in key order.
+    ///
+    /// The iteration is lazy - both sets are walked in lockstep and neither is loaded into
+    /// memory. Members present in both sets are yielded once.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Set;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let (a, b) = (Set::<u32>::new(0), Set::<u32>::new(1));
+    ///
+    /// for member in [1, 2, 3] {
+    ///     a.access(&mut storage).insert(&member);
+    /// }
+    /// for member in [2, 3, 4] {
+    ///     b.access(&mut storage).insert(&member);
+    /// }
+    ///
+    /// let (a, b) = (a.access(&storage), b.access(&storage));
+    /// let union = a.union(&b).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(union, vec![1, 2, 3, 4]);
+    ///
+    /// let intersection = a.intersection(&b).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(intersection, vec![2, 3]);
+    ///
+    /// let difference = a.difference(&b).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(difference, vec![1]);
+    /// ```
+    pub fn union<'a, S2>(&'a self, other: &'a SetAccess<T, S2>) -> Union<'a, T, S, S2>
+    where
+        S2: IterableStorage,
+    {
+        Union {
+            inner: merge_join(&self.storage, &other.storage),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Iterate over the members of both this set and `other`, in key order.
+    ///
+    /// The iteration is lazy and stops as soon as either set is exhausted. See
+    /// [`union`](Self::union) for an example.
+    pub fn intersection<'a, S2>(&'a self, other: &'a SetAccess<T, S2>) -> Intersection<'a, T, S, S2>
+    where
+        S2: IterableStorage,
+    {
+        Intersection {
+            inner: merge_join(&self.storage, &other.storage),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Iterate over the members of this set that aren't in `other`, in key order.
+    ///
+    /// The iteration is lazy and stops as soon as this set is exhausted. See
+    /// [`union`](Self::union) for an example.
+    pub fn difference<'a, S2>(&'a self, other: &'a SetAccess<T, S2>) -> Difference<'a, T, S, S2>
+    where
+        S2: IterableStorage,
+    {
+        Difference {
+            inner: merge_join(&self.storage, &other.storage),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Check whether every member of this set is also a member of `other`.
+    ///
+    /// Both sets are walked in lockstep, stopping at the first member that's missing from
+    /// `other`.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::Set;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let (roles, allowed) = (Set::<String>::new(0), Set::<String>::new(1));
+    ///
+    /// roles.access(&mut storage).insert("reader");
+    /// for role in ["reader", "writer"] {
+    ///     allowed.access(&mut storage).insert(role);
+    /// }
+    ///
+    /// let (roles, allowed) = (roles.access(&storage), allowed.access(&storage));
+    /// assert!(roles.is_subset(&allowed));
+    /// assert!(!allowed.is_subset(&roles));
+    /// ```
+    pub fn is_subset<S2>(&self, other: &SetAccess<T, S2>) -> bool
+    where
+        S2: IterableStorage,
+    {
+        let mut inner = merge_join(&self.storage, &other.storage);
+
+        while !inner.left_done() {
+            if let Some(Side::Left(_)) = inner.next() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check whether this set and `other` have no members in common.
+    ///
+    /// Both sets are walked in lockstep, stopping at the first common member.
+    pub fn is_disjoint<S2>(&self, other: &SetAccess<T, S2>) -> bool
+    where
+        S2: IterableStorage,
+    {
+        let mut inner = merge_join(&self.storage, &other.storage);
+
+        while !inner.left_done() && !inner.right_done() {
+            if let Some(Side::Both(_)) = inner.next() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Walk the keys of both storages in lockstep.
+fn merge_join<'a, A, B>(
+    left: &'a A,
+    right: &'a B,
+) -> MergeJoin<A::KeysIterator<'a>, B::KeysIterator<'a>>
+where
+    A: IterableStorage,
+    B: IterableStorage,
+{
+    MergeJoin::new(
+        left.keys(Bound::Unbounded, Bound::Unbounded),
+        right.keys(Bound::Unbounded, Bound::Unbounded),
+    )
+}
+
+/// Which of the two sets an encoded member was found in.
+enum Side {
+    Left(Vec<u8>),
+    Right(Vec<u8>),
+    Both(Vec<u8>),
+}
+
+/// Walks the (sorted) keys of two storages in lockstep.
+struct MergeJoin<A: Iterator, B: Iterator> {
+    left: Peekable<A>,
+    right: Peekable<B>,
+}
+
+impl<A, B> MergeJoin<A, B>
+where
+    A: Iterator<Item = Vec<u8>>,
+    B: Iterator<Item = Vec<u8>>,
+{
+    fn new(left: A, right: B) -> Self {
+        Self {
+            left: left.peekable(),
+            right: right.peekable(),
+        }
+    }
+
+    fn left_done(&mut self) -> bool {
+        self.left.peek().is_none()
+    }
+
+    fn right_done(&mut self) -> bool {
+        self.right.peek().is_none()
+    }
+}
+
+impl<A, B> Iterator for MergeJoin<A, B>
+where
+    A: Iterator<Item = Vec<u8>>,
+    B: Iterator<Item = Vec<u8>>,
+{
+    type Item = Side;
+
+    fn next(&mut self) -> Option<Side> {
+        let ordering = match (self.left.peek(), self.right.peek()) {
+            (Some(left), Some(right)) => left.cmp(right),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+
+        match ordering {
+            Ordering::Less => self.left.next().map(Side::Left),
+            Ordering::Greater => self.right.next().map(Side::Right),
+            Ordering::Equal => {
+                self.right.next();
+                self.left.next().map(Side::Both)
+            }
+        }
+    }
+}
+
+/// An iterator over the union of two sets.
+///
+/// This is returned by [`SetAccess::union`].
+pub struct Union<'a, T, A, B>
+where
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    inner: MergeJoin<A::KeysIterator<'a>, B::KeysIterator<'a>>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T, A, B> Iterator for Union<'a, T, A, B>
+where
+    T: OwnedKey,
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    type Item = Result<T, InvalidMember>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (Side::Left(key) | Side::Right(key) | Side::Both(key)) = self.inner.next()?;
+        Some(Set::<T>::decode_key(&key))
+    }
+}
+
+/// An iterator over the intersection of two sets.
+///
+/// This is returned by [`SetAccess::intersection`].
+pub struct Intersection<'a, T, A, B>
+where
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    inner: MergeJoin<A::KeysIterator<'a>, B::KeysIterator<'a>>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T, A, B> Iterator for Intersection<'a, T, A, B>
+where
+    T: OwnedKey,
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    type Item = Result<T, InvalidMember>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.inner.left_done() && !self.inner.right_done() {
+            if let Some(Side::Both(key)) = self.inner.next() {
+                return Some(Set::<T>::decode_key(&key));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the difference of two sets.
+///
+/// This is returned by [`SetAccess::difference`].
+pub struct Difference<'a, T, A, B>
+where
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    inner: MergeJoin<A::KeysIterator<'a>, B::KeysIterator<'a>>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T, A, B> Iterator for Difference<'a, T, A, B>
+where
+    T: OwnedKey,
+    A: IterableStorage + 'a,
+    B: IterableStorage + 'a,
+{
+    type Item = Result<T, InvalidMember>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.inner.left_done() {
+            if let Some(Side::Left(key)) = self.inner.next() {
+                return Some(Set::<T>::decode_key(&key));
+            }
+        }
+        None
+    }
+}
+
+impl<T, S> IterableAccessor for SetAccess<T, S>
+where
+    T: OwnedKey,
+    S: IterableStorage,
+{
+    type Storable = Set<T>;
+    type Storage = S;
+
+    fn storage(&self) -> &Self::Storage {
+        &self.storage
+    }
+}
+
+impl<T, S> BoundedIterableAccessor for SetAccess<T, S>
+where
+    T: OwnedKey,
+    S: IterableStorage,
+{
+}
+
+impl<T, Q> BoundFor<Set<T>> for &Q
+where
+    T: Borrow<Q> + OwnedKey,
+    Q: Key + ?Sized,
+{
+    fn into_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use super::*;
+
+    use crate::containers::{Item, Map};
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+    use storey_storage::Storage as _;
+
+    #[test]
+    fn basic() {
+        let mut storage = TestStorage::new();
+
+        let set = Set::<String>::new(0);
+        let mut access = set.access(&mut storage);
+
+        access.insert("foo");
+        access.insert("bar");
+        access.insert("bar");
+
+        assert!(access.contains("foo"));
+        assert!(access.contains("bar"));
+        assert!(!access.contains("baz"));
+
+        access.remove("bar");
+        access.remove("baz");
+        assert!(!access.contains("bar"));
+
+        assert_eq!(storage.get(&[0, 102, 111, 111]), Some(vec![1]));
+        assert_eq!(storage.get(&[0, 98, 97, 114]), None);
+    }
+
+    #[test]
+    fn iteration() {
+        let mut storage = TestStorage::new();
+
+        let set = Set::<u32>::new(0);
+        let mut access = set.access(&mut storage);
+
+        for member in [7, 1, 300, 42] {
+            access.insert(&member);
+        }
+
+        let members = access.keys().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(members, vec![1, 7, 42, 300]);
+
+        let members = access
+            .bounded_keys(Bound::Included(&7), Bound::Excluded(&300))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(members, vec![7, 42]);
+
+        let pairs = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(pairs, vec![(1, ()), (7, ()), (42, ()), (300, ())]);
+    }
+
+    fn check_algebra<T>(a: &BTreeSet<T>, b: &BTreeSet<T>)
+    where
+        T: OwnedKey + Ord + Clone + core::fmt::Debug,
+    {
+        let mut storage = TestStorage::new();
+        let (set_a, set_b) = (Set::<T>::new(0), Set::<T>::new(1));
+
+        for member in a {
+            set_a.access(&mut storage).insert(member);
+        }
+        for member in b {
+            set_b.access(&mut storage).insert(member);
+        }
+
+        let (access_a, access_b) = (set_a.access(&storage), set_b.access(&storage));
+        let collect = |iter: &mut dyn Iterator<Item = Result<T, InvalidMember>>| {
+            iter.collect::<Result<Vec<_>, _>>().unwrap()
+        };
+
+        assert_eq!(
+            collect(&mut access_a.union(&access_b)),
+            a.union(b).cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            collect(&mut access_a.intersection(&access_b)),
+            a.intersection(b).cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            collect(&mut access_a.difference(&access_b)),
+            a.difference(b).cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            collect(&mut access_b.difference(&access_a)),
+            b.difference(a).cloned().collect::<Vec<_>>()
+        );
+
+        assert_eq!(access_a.is_subset(&access_b), a.is_subset(b));
+        assert_eq!(access_b.is_subset(&access_a), b.is_subset(a));
+        assert_eq!(access_a.is_disjoint(&access_b), a.is_disjoint(b));
+    }
+
+    #[test]
+    fn set_algebra() {
+        // a simple LCG, so the test is deterministic
+        let mut state = 42u32;
+        let mut random = move |max: u32| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) % max
+        };
+
+        for _ in 0..20 {
+            let a: BTreeSet<u32> = (0..random(30)).map(|_| random(300)).collect();
+            let b: BTreeSet<u32> = (0..random(30)).map(|_| random(300)).collect();
+            check_algebra(&a, &b);
+        }
+
+        // empty sets
+        let some: BTreeSet<u32> = [1, 5, 9].into();
+        check_algebra(&some, &BTreeSet::new());
+        check_algebra(&BTreeSet::new(), &some);
+        check_algebra::<u32>(&BTreeSet::new(), &BTreeSet::new());
+
+        // variable-length members, where one is a prefix of another
+        let a: BTreeSet<String> = ["", "a", "ab", "b", "ba"].map(String::from).into();
+        let b: BTreeSet<String> = ["a", "aa", "abc", "ba", "c"].map(String::from).into();
+        check_algebra(&a, &b);
+    }
+
+    #[test]
+    fn subset_and_disjoint() {
+        let set = |members: &[u32]| members.iter().copied().collect::<BTreeSet<_>>();
+
+        // equal sets
+        check_algebra(&set(&[1, 2, 3]), &set(&[1, 2, 3]));
+        // proper subsets
+        check_algebra(&set(&[2]), &set(&[1, 2, 3]));
+        check_algebra(&set(&[]), &set(&[1, 2, 3]));
+        check_algebra(&set(&[1, 3]), &set(&[1, 2, 3, 4]));
+        // disjoint sets
+        check_algebra(&set(&[1, 3, 5]), &set(&[2, 4, 6]));
+        check_algebra(&set(&[7, 8]), &set(&[1, 2]));
+        // overlapping, but neither a subset nor disjoint
+        check_algebra(&set(&[1, 2, 5]), &set(&[2, 3]));
+    }
+
+    #[test]
+    fn nested_in_map() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Set<u32>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").insert(&1);
+        access.entry_mut("foo").insert(&2);
+        access.entry_mut("bar").insert(&1);
+
+        assert!(access.entry("foo").contains(&2));
+        assert!(!access.entry("bar").contains(&2));
+
+        let keys = access.keys().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                ("bar".to_string(), 1),
+                ("foo".to_string(), 1),
+                ("foo".to_string(), 2)
+            ]
+        );
+
+        // doesn't clash with other containers
+        let item = Item::<u64, TestEncoding>::new(1);
+        item.access(&mut storage).set(&42).unwrap();
+        assert_eq!(map.access(&storage).keys().count(), 3);
+    }
+}
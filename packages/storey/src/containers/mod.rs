@@ -3,14 +3,29 @@
 
 mod column;
 pub mod common;
+mod counter;
+mod deque;
 mod item;
 pub mod map;
+mod ordered_map;
+mod position_index;
+mod reference;
 
-use std::{marker::PhantomData, ops::Bound};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::{marker::PhantomData, ops::Bound};
 
-pub use column::{Column, ColumnAccess};
+pub use column::{Column, ColumnAccess, ColumnId};
+pub use counter::{Counter, CounterAccess, CounterError};
+pub use deque::{Deque, DequeAccess};
 pub use item::{Item, ItemAccess};
-pub use map::{Map, MapAccess};
+pub use map::{
+    CountedMap, CountedMapAccess, IndexedMap, IndexedMapAccess, Map, MapAccess, Set, SetAccess,
+};
+pub use ordered_map::{OrderedMap, OrderedMapAccess};
+pub use reference::{Ref, RefTarget};
+#[cfg(feature = "derive")]
+pub use storey_macros::Storable;
 use storey_storage::RevIterableStorage;
 
 use crate::storage::IterableStorage;
@@ -72,7 +87,10 @@ pub enum KVDecodeError<K, V> {
     Value(V),
 }
 
-impl<K: std::fmt::Display, V: std::fmt::Display> crate::error::StoreyError for KVDecodeError<K, V> {}
+impl<K: core::fmt::Display, V: core::fmt::Display> crate::error::StoreyError
+    for KVDecodeError<K, V>
+{
+}
 
 /// A trait for collection accessors (see [`Storable::Accessor`]) that provide iteration over
 /// their contents.
@@ -118,6 +136,281 @@ pub trait IterableAccessor: Sized {
             phantom: PhantomData,
         }
     }
+
+    /// Iterate over key-value pairs in this collection, leaving the values undecoded.
+    ///
+    /// Keys are decoded as in [`pairs`](Self::pairs), but values are yielded as the raw bytes
+    /// found in storage. This is useful for copying or hashing values without paying for
+    /// decoding them.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    /// use storey::encoding::EncodableWith;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// let encoded = EncodableWith::<TestEncoding>::encode(&1337u64).unwrap();
+    /// let pairs = access.raw_pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(pairs, vec![(("foo".to_string(), ()), encoded)]);
+    /// ```
+    fn raw_pairs(
+        &self,
+    ) -> StorableRawPairs<Self::Storable, <Self::Storage as IterableStorage>::PairsIterator<'_>>
+    {
+        StorableRawPairs {
+            inner: self.storage().pairs(Bound::Unbounded, Bound::Unbounded),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Iterate over values in this collection as the raw bytes found in storage, without
+    /// decoding them.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    /// use storey::encoding::EncodableWith;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    ///
+    /// let encoded = EncodableWith::<TestEncoding>::encode(&1337u64).unwrap();
+    /// assert_eq!(access.raw_values().collect::<Vec<_>>(), vec![encoded]);
+    /// ```
+    fn raw_values(&self) -> <Self::Storage as IterableStorage>::ValuesIterator<'_> {
+        self.storage().values(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    /// Collect the keys of this collection into a [`BTreeSet`].
+    ///
+    /// Returns the first key decoding error encountered, if any.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use std::collections::BTreeSet;
+    ///
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    /// access.entry_mut("bar").set(&42).unwrap();
+    ///
+    /// let keys = access.try_keys_set().unwrap();
+    /// assert_eq!(
+    ///     keys,
+    ///     BTreeSet::from([("bar".to_string(), ()), ("foo".to_string(), ())])
+    /// );
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn try_keys_set(
+        &self,
+    ) -> Result<
+        BTreeSet<<Self::Storable as Storable>::Key>,
+        <Self::Storable as Storable>::KeyDecodeError,
+    >
+    where
+        <Self::Storable as Storable>::Key: Ord,
+    {
+        self.keys().collect()
+    }
+
+    /// Collect the first `n` key-value pairs of this collection into a [`Vec`].
+    ///
+    /// Iteration stops after `n` entries. Returns the first decoding error encountered, if any.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    /// access.entry_mut("bar").set(&42).unwrap();
+    /// access.entry_mut("baz").set(&7).unwrap();
+    ///
+    /// assert_eq!(
+    ///     access.first_n(2).unwrap(),
+    ///     vec![(("bar".to_string(), ()), 42), (("baz".to_string(), ()), 7)]
+    /// );
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn first_n(
+        &self,
+        n: usize,
+    ) -> Result<
+        Vec<(
+            <Self::Storable as Storable>::Key,
+            <Self::Storable as Storable>::Value,
+        )>,
+        KVDecodeError<
+            <Self::Storable as Storable>::KeyDecodeError,
+            <Self::Storable as Storable>::ValueDecodeError,
+        >,
+    > {
+        self.pairs().take(n).collect()
+    }
+
+    /// Split the key-value pairs of this collection into two [`Vec`]s in a single scan.
+    ///
+    /// The first [`Vec`] holds the pairs for which `f` returns `true`, the second the rest.
+    /// Both keep iteration order. Returns the first decoding error encountered, if any.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    /// access.entry_mut("bar").set(&42).unwrap();
+    ///
+    /// let (large, small) = access.try_partition(|(_, v)| *v > 100).unwrap();
+    /// assert_eq!(large, vec![(("foo".to_string(), ()), 1337)]);
+    /// assert_eq!(small, vec![(("bar".to_string(), ()), 42)]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn try_partition<F>(
+        &self,
+        mut f: F,
+    ) -> Result<
+        (
+            Vec<(
+                <Self::Storable as Storable>::Key,
+                <Self::Storable as Storable>::Value,
+            )>,
+            Vec<(
+                <Self::Storable as Storable>::Key,
+                <Self::Storable as Storable>::Value,
+            )>,
+        ),
+        KVDecodeError<
+            <Self::Storable as Storable>::KeyDecodeError,
+            <Self::Storable as Storable>::ValueDecodeError,
+        >,
+    >
+    where
+        F: FnMut(
+            &(
+                <Self::Storable as Storable>::Key,
+                <Self::Storable as Storable>::Value,
+            ),
+        ) -> bool,
+    {
+        let mut matching = Vec::new();
+        let mut rest = Vec::new();
+
+        for pair in self.pairs() {
+            let pair = pair?;
+            if f(&pair) {
+                matching.push(pair);
+            } else {
+                rest.push(pair);
+            }
+        }
+
+        Ok((matching, rest))
+    }
+
+    /// Write the contents of this collection to `w` as CSV, for debugging.
+    ///
+    /// Each entry is written as one row, with no header row. The key comes first, followed by
+    /// the value. Composite keys and values are flattened into one column per component.
+    /// For example, keys of a [`Map`] of [`Item`]s are `(K, ())`, so those rows have the map
+    /// key, an empty column for the item's `()` key, and then the value.
+    ///
+    /// This is developer tooling for inspecting state dumps offline, not something to use
+    /// on chain. It's only available with the `csv` feature enabled.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, IterableAccessor as _, Map};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    /// access.entry_mut("bar").set(&42).unwrap();
+    ///
+    /// let mut csv = vec![];
+    /// access.export_csv(&mut csv).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "bar,,42\nfoo,,1337\n");
+    /// ```
+    #[cfg(feature = "csv")]
+    #[allow(clippy::type_complexity)]
+    fn export_csv<W: std::io::Write>(
+        &self,
+        w: W,
+    ) -> Result<
+        (),
+        ExportCsvError<
+            <Self::Storable as Storable>::KeyDecodeError,
+            <Self::Storable as Storable>::ValueDecodeError,
+        >,
+    >
+    where
+        <Self::Storable as Storable>::Key: serde::Serialize,
+        <Self::Storable as Storable>::Value: serde::Serialize,
+    {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_writer(w);
+
+        for entry in self.pairs() {
+            writer
+                .serialize(entry.map_err(ExportCsvError::Decode)?)
+                .map_err(ExportCsvError::Csv)?;
+        }
+
+        writer.flush().map_err(|e| ExportCsvError::Csv(e.into()))?;
+
+        Ok(())
+    }
+}
+
+/// An error returned by [`IterableAccessor::export_csv`].
+#[cfg(feature = "csv")]
+#[derive(Debug, thiserror::Error)]
+pub enum ExportCsvError<K, V> {
+    #[error(transparent)]
+    Decode(KVDecodeError<K, V>),
+    #[error("failed to write CSV: {0}")]
+    Csv(csv::Error),
+}
+
+#[cfg(feature = "csv")]
+impl<K: core::fmt::Display, V: core::fmt::Display> crate::error::StoreyError
+    for ExportCsvError<K, V>
+{
 }
 
 pub trait RevIterableAccessor
@@ -159,6 +452,46 @@ where
             phantom: PhantomData,
         }
     }
+
+    /// Collect the last `n` key-value pairs of this collection into a [`Vec`], in reverse order.
+    ///
+    /// Iteration stops after `n` entries. Returns the first decoding error encountered, if any.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::{Item, Map, RevIterableAccessor as _};
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.entry_mut("foo").set(&1337).unwrap();
+    /// access.entry_mut("bar").set(&42).unwrap();
+    /// access.entry_mut("baz").set(&7).unwrap();
+    ///
+    /// assert_eq!(
+    ///     access.last_n(2).unwrap(),
+    ///     vec![(("foo".to_string(), ()), 1337), (("baz".to_string(), ()), 7)]
+    /// );
+    /// ```
+    #[allow(clippy::type_complexity)]
+    fn last_n(
+        &self,
+        n: usize,
+    ) -> Result<
+        Vec<(
+            <Self::Storable as Storable>::Key,
+            <Self::Storable as Storable>::Value,
+        )>,
+        KVDecodeError<
+            <Self::Storable as Storable>::KeyDecodeError,
+            <Self::Storable as Storable>::ValueDecodeError,
+        >,
+    > {
+        self.rev_pairs().take(n).collect()
+    }
 }
 
 impl<I> RevIterableAccessor for I
@@ -356,6 +689,10 @@ pub trait BoundFor<T> {
 }
 
 /// The iterator over key-value pairs in a collection.
+///
+/// Like [`StorableKeys`] and [`StorableValues`], this is a [`DoubleEndedIterator`] whenever the
+/// underlying storage iterator is one, so the last entries can be read with
+/// [`next_back`](DoubleEndedIterator::next_back) without a separate reverse iteration.
 pub struct StorableIter<S, I> {
     inner: I,
     phantom: PhantomData<S>,
@@ -369,13 +706,74 @@ where
     type Item = Result<(S::Key, S::Value), KVDecodeError<S::KeyDecodeError, S::ValueDecodeError>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|(k, v)| -> Self::Item {
-            match (S::decode_key(&k), S::decode_value(&v)) {
-                (Err(e), _) => Err(KVDecodeError::Key(e)),
-                (_, Err(e)) => Err(KVDecodeError::Value(e)),
-                (Ok(k), Ok(v)) => Ok((k, v)),
-            }
-        })
+        self.inner.next().map(|(k, v)| decode_pair::<S>(&k, &v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S, I> DoubleEndedIterator for StorableIter<S, I>
+where
+    S: Storable,
+    I: DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(k, v)| decode_pair::<S>(&k, &v))
+    }
+}
+
+/// Decode a raw key-value pair of `S`.
+#[allow(clippy::type_complexity)]
+fn decode_pair<S: Storable>(
+    key: &[u8],
+    value: &[u8],
+) -> Result<(S::Key, S::Value), KVDecodeError<S::KeyDecodeError, S::ValueDecodeError>> {
+    match (S::decode_key(key), S::decode_value(value)) {
+        (Err(e), _) => Err(KVDecodeError::Key(e)),
+        (_, Err(e)) => Err(KVDecodeError::Value(e)),
+        (Ok(k), Ok(v)) => Ok((k, v)),
+    }
+}
+
+/// The iterator over key-value pairs in a collection, with only the keys decoded.
+///
+/// Returned by [`IterableAccessor::raw_pairs`].
+pub struct StorableRawPairs<S, I> {
+    inner: I,
+    phantom: PhantomData<S>,
+}
+
+impl<S, I> Iterator for StorableRawPairs<S, I>
+where
+    S: Storable,
+    I: Iterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    type Item = Result<(S::Key, Vec<u8>), S::KeyDecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(k, v)| S::decode_key(&k).map(|k| (k, v)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S, I> DoubleEndedIterator for StorableRawPairs<S, I>
+where
+    S: Storable,
+    I: DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(k, v)| S::decode_key(&k).map(|k| (k, v)))
     }
 }
 
@@ -395,6 +793,20 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|k| S::decode_key(&k))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S, I> DoubleEndedIterator for StorableKeys<S, I>
+where
+    S: Storable,
+    I: DoubleEndedIterator<Item = Vec<u8>>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|k| S::decode_key(&k))
+    }
 }
 
 /// The iterator over values in a collection.
@@ -413,6 +825,20 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|v| S::decode_value(&v))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S, I> DoubleEndedIterator for StorableValues<S, I>
+where
+    S: Storable,
+    I: DoubleEndedIterator<Item = Vec<u8>>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|v| S::decode_value(&v))
+    }
 }
 
 /// The kind of a storable.
@@ -439,8 +865,15 @@ impl StorableKind for Terminal {}
 impl StorableKind for NonTerminal {}
 
 mod sealed {
-    pub trait StorableKindSeal {}
+    pub trait StorableKindSeal {
+        /// Whether the storable keeps its value directly under its own key.
+        const TERMINAL: bool;
+    }
 
-    impl StorableKindSeal for super::Terminal {}
-    impl StorableKindSeal for super::NonTerminal {}
+    impl StorableKindSeal for super::Terminal {
+        const TERMINAL: bool = true;
+    }
+    impl StorableKindSeal for super::NonTerminal {
+        const TERMINAL: bool = false;
+    }
 }
@@ -0,0 +1,417 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+use core::ops::Bound;
+
+use thiserror::Error;
+
+use crate::encoding::{DecodableWith, EncodableWith, Encoding};
+use crate::storage::{IterableStorage, Storage, StorageMut};
+use crate::storage::{StorageBranch, StorageRef};
+
+use super::map::{Key, OwnedKey};
+
+/// Storage keys for metadata.
+mod meta_keys {
+    /// The sequence number that will be assigned to the next newly inserted key.
+    pub const META_NEXT_SEQ: &[u8] = &[0];
+}
+
+/// Sub-namespaces managed by the ordered map.
+mod namespaces {
+    /// Key -> value.
+    pub const VALUES: u8 = 0;
+    /// Key -> sequence number (big-endian `u32`).
+    pub const SEQS: u8 = 1;
+    /// Sequence number (big-endian `u32`) -> key.
+    pub const ORDER: u8 = 2;
+}
+
+/// A map that remembers the order in which keys were first inserted.
+///
+/// In addition to the values themselves, the ordered map maintains an auxiliary index
+/// mapping a monotonically increasing sequence number to each key. This allows iterating
+/// over the entries in insertion order with [`iter_insertion_order`]. Overwriting the
+/// value of an existing key does not change its position. Removing a key leaves a gap in
+/// the sequence, which is skipped during iteration; re-inserting it later places it at the
+/// end.
+///
+/// Maintaining the index costs two extra writes per newly inserted key and two extra
+/// removals per removed key, so prefer [`Map`] unless insertion order matters.
+///
+/// Unlike [`Map`], the ordered map stores values directly rather than doling out access
+/// to nested containers.
+///
+/// [`iter_insertion_order`]: OrderedMapAccess::iter_insertion_order
+/// [`Map`]: super::Map
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::OrderedMap;
+///
+/// let mut storage = TestStorage::new();
+/// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+/// let mut access = map.access(&mut storage);
+///
+/// access.insert("foo", &1).unwrap();
+/// access.insert("bar", &2).unwrap();
+///
+/// assert_eq!(
+///     access.iter_insertion_order().collect::<Result<Vec<_>, _>>().unwrap(),
+///     vec![("foo".to_string(), 1), ("bar".to_string(), 2)]
+/// );
+/// ```
+pub struct OrderedMap<K, V, E> {
+    prefix: u8,
+    phantom: PhantomData<(K, V, E)>,
+}
+
+impl<K, V, E> OrderedMap<K, V, E>
+where
+    K: OwnedKey,
+    E: Encoding,
+    V: EncodableWith<E> + DecodableWith<E>,
+{
+    /// Creates a new ordered map with the given prefix.
+    ///
+    /// It is the responsibility of the caller to ensure that the prefix is unique and does not conflict
+    /// with other keys in the storage.
+    ///
+    /// The key provided here is used as a prefix for all keys managed by the ordered map.
+    pub const fn new(prefix: u8) -> Self {
+        Self {
+            prefix,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Acquires an accessor for the ordered map.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::OrderedMap;
+    ///
+    /// // immutable access
+    /// let storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let access = map.access(&storage);
+    ///
+    /// // mutable access
+    /// let mut storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    /// ```
+    pub fn access<S: StorageRef>(&self, storage: S) -> OrderedMapAccess<K, V, E, StorageBranch<S>> {
+        OrderedMapAccess {
+            storage: StorageBranch::new(storage, vec![self.prefix]),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// An accessor for an ordered map.
+///
+/// The accessor provides methods for interacting with the ordered map in storage.
+pub struct OrderedMapAccess<K, V, E, S> {
+    storage: S,
+    phantom: PhantomData<(K, V, E)>,
+}
+
+impl<K, V, E, S> OrderedMapAccess<K, V, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    V: EncodableWith<E> + DecodableWith<E>,
+    S: Storage,
+{
+    /// Get the value associated with the given key.
+    ///
+    /// Returns `Ok(None)` if the entry doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::OrderedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// assert_eq!(access.get("bar").unwrap(), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Result<Option<V>, E::DecodeError>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        self.storage
+            .get(&sub_key(namespaces::VALUES, &key.encode()))
+            .map(|bytes| V::decode(&bytes))
+            .transpose()
+    }
+
+    fn next_seq(&self) -> Result<u32, InsertError<E::EncodeError>> {
+        match self.storage.get_meta(meta_keys::META_NEXT_SEQ) {
+            Some(bytes) => {
+                let bytes: [u8; 4] = bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| InsertError::InconsistentState)?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+impl<K, V, E, S> OrderedMapAccess<K, V, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    V: EncodableWith<E> + DecodableWith<E>,
+    S: IterableStorage + Storage,
+{
+    /// Iterate over the entries in the order their keys were first inserted.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::OrderedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1).unwrap();
+    /// access.insert("bar", &2).unwrap();
+    /// access.insert("baz", &3).unwrap();
+    /// access.remove("bar");
+    ///
+    /// assert_eq!(
+    ///     access.iter_insertion_order().collect::<Result<Vec<_>, _>>().unwrap(),
+    ///     vec![("foo".to_string(), 1), ("baz".to_string(), 3)]
+    /// );
+    /// ```
+    pub fn iter_insertion_order(&self) -> InsertionOrderIter<'_, K, V, E, S> {
+        InsertionOrderIter {
+            storage: &self.storage,
+            inner: self.storage.values(
+                Bound::Included(&[namespaces::ORDER]),
+                Bound::Excluded(&[namespaces::ORDER + 1]),
+            ),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, V, E, S> OrderedMapAccess<K, V, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    V: EncodableWith<E> + DecodableWith<E>,
+    S: Storage + StorageMut,
+{
+    /// Insert a value under the given key.
+    ///
+    /// If the key is new, it's appended to the insertion order. If it already exists,
+    /// its value is overwritten and its position is retained.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::OrderedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// assert_eq!(access.get("foo").unwrap(), Some(1337));
+    /// ```
+    pub fn insert<Q>(&mut self, key: &Q, value: &V) -> Result<(), InsertError<E::EncodeError>>
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let key = key.encode();
+        let bytes = value.encode().map_err(InsertError::EncodingError)?;
+
+        let seq_key = sub_key(namespaces::SEQS, &key);
+        if !self.storage.has(&seq_key) {
+            let seq = self.next_seq()?;
+            let next = seq.checked_add(1).ok_or(InsertError::SeqOverflow)?;
+
+            self.storage
+                .set(&sub_key(namespaces::ORDER, &seq.to_be_bytes()), &key);
+            self.storage.set(&seq_key, &seq.to_be_bytes());
+            self.storage
+                .set_meta(meta_keys::META_NEXT_SEQ, &next.to_be_bytes());
+        }
+
+        self.storage.set(&sub_key(namespaces::VALUES, &key), &bytes);
+
+        Ok(())
+    }
+
+    /// Remove the entry associated with the given key.
+    ///
+    /// # Example
+    /// ```
+    /// # use mocks::encoding::TestEncoding;
+    /// # use mocks::backend::TestStorage;
+    /// use storey::containers::OrderedMap;
+    ///
+    /// let mut storage = TestStorage::new();
+    /// let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+    /// let mut access = map.access(&mut storage);
+    ///
+    /// access.insert("foo", &1337).unwrap();
+    /// access.remove("foo");
+    /// assert_eq!(access.get("foo").unwrap(), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Key<Kind = K::Kind> + ?Sized,
+    {
+        let key = key.encode();
+
+        let seq_key = sub_key(namespaces::SEQS, &key);
+        if let Some(seq) = self.storage.get(&seq_key) {
+            self.storage.remove(&sub_key(namespaces::ORDER, &seq));
+            self.storage.remove(&seq_key);
+        }
+
+        self.storage.remove(&sub_key(namespaces::VALUES, &key));
+    }
+}
+
+fn sub_key(namespace: u8, key: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(key.len() + 1);
+    result.push(namespace);
+    result.extend_from_slice(key);
+    result
+}
+
+/// An iterator over the entries of an [`OrderedMap`] in insertion order.
+pub struct InsertionOrderIter<'a, K, V, E, S>
+where
+    S: IterableStorage + 'a,
+{
+    storage: &'a S,
+    inner: S::ValuesIterator<'a>,
+    phantom: PhantomData<(K, V, E)>,
+}
+
+impl<K, V, E, S> Iterator for InsertionOrderIter<'_, K, V, E, S>
+where
+    K: OwnedKey,
+    E: Encoding,
+    V: DecodableWith<E>,
+    S: IterableStorage + Storage,
+{
+    type Item = Result<(K, V), InsertionOrderIterError<K::Error, E::DecodeError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|key| {
+            let value = self
+                .storage
+                .get(&sub_key(namespaces::VALUES, &key))
+                .ok_or(InsertionOrderIterError::InconsistentState)?;
+
+            let key = K::from_bytes(&key).map_err(InsertionOrderIterError::Key)?;
+            let value = V::decode(&value).map_err(InsertionOrderIterError::Value)?;
+
+            Ok((key, value))
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum InsertError<E> {
+    #[error("sequence number overflow")]
+    SeqOverflow,
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("{0}")]
+    EncodingError(E),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
+pub enum InsertionOrderIterError<K, V> {
+    #[error("inconsistent state")]
+    InconsistentState,
+    #[error("failed to decode key: {0}")]
+    Key(K),
+    #[error("failed to decode value: {0}")]
+    Value(V),
+}
+
+impl<K: core::fmt::Display, V: core::fmt::Display> crate::error::StoreyError
+    for InsertionOrderIterError<K, V>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    #[test]
+    fn insertion_order() {
+        let mut storage = TestStorage::new();
+
+        let map = OrderedMap::<String, u64, TestEncoding>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.insert("foo", &1).unwrap();
+        access.insert("bar", &2).unwrap();
+        access.insert("baz", &3).unwrap();
+        access.remove("bar");
+        access.insert("qux", &4).unwrap();
+        // overwriting keeps the original position
+        access.insert("foo", &5).unwrap();
+        // re-inserting a removed key appends it
+        access.insert("bar", &6).unwrap();
+        access.remove("baz");
+        // removing a missing key is a no-op
+        access.remove("quux");
+
+        assert_eq!(
+            access
+                .iter_insertion_order()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![
+                ("foo".to_string(), 5),
+                ("qux".to_string(), 4),
+                ("bar".to_string(), 6)
+            ]
+        );
+
+        assert_eq!(access.get("foo").unwrap(), Some(5));
+        assert_eq!(access.get("baz").unwrap(), None);
+    }
+
+    #[test]
+    fn empty() {
+        let storage = TestStorage::new();
+
+        let map = OrderedMap::<u32, u64, TestEncoding>::new(0);
+        let access = map.access(&storage);
+
+        assert_eq!(access.iter_insertion_order().count(), 0);
+    }
+}
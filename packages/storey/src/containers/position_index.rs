@@ -0,0 +1,210 @@
+//! A positional index over integer IDs, kept in the metadata namespace.
+//!
+//! The index is a [Fenwick tree] counting which IDs are live. It allows finding the
+//! `n`th live ID (in ascending order) without scanning the entries.
+//!
+//! The tree is stored sparsely - a node that was never written counts as `0`. Its capacity
+//! is a power of two that grows with the highest ID inserted, so operations cost
+//! `O(log(max_id))` metadata reads and writes rather than a fixed 64. The capacity is
+//! capped at `2^63`, so higher IDs can't be indexed.
+//!
+//! [Fenwick tree]: https://en.wikipedia.org/wiki/Fenwick_tree
+
+use alloc::vec::Vec;
+
+use crate::storage::{Storage, StorageMut};
+
+/// `log2` of the highest capacity the tree can grow to.
+const MAX_DEPTH: u8 = 63;
+
+/// The index metadata is corrupt, or the ID is out of the supported range.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct IndexError;
+
+/// A Fenwick tree stored in the metadata namespace, under the given keys.
+pub(crate) struct PositionIndex {
+    /// Metadata key holding `log2` of the tree's capacity.
+    pub depth_key: &'static [u8],
+    /// Metadata key prefix for tree nodes. Nodes are keyed by their big-endian `u64` index.
+    pub node_prefix: &'static [u8],
+}
+
+impl PositionIndex {
+    /// The number of live IDs.
+    pub fn count<S: Storage>(&self, storage: &S) -> Result<u32, IndexError> {
+        let capacity = 1u64 << self.depth(storage)?;
+        self.node(storage, capacity)
+    }
+
+    /// Mark the given ID as live.
+    pub fn insert<S: Storage + StorageMut>(
+        &self,
+        storage: &mut S,
+        id: u64,
+    ) -> Result<(), IndexError> {
+        let mut depth = self.depth(storage)?;
+
+        // Grow the tree until it covers `id`. The new root covers the entire new range,
+        // of which only the old half can contain live IDs.
+        if (1u64 << depth) < id {
+            while (1u64 << depth) < id {
+                if depth == MAX_DEPTH {
+                    return Err(IndexError);
+                }
+                let root = self.node(storage, 1 << depth)?;
+                depth += 1;
+                self.set_node(storage, 1 << depth, root);
+            }
+            storage.set_meta(self.depth_key, &[depth]);
+        }
+
+        self.add(storage, id, depth, |count| count.checked_add(1))
+    }
+
+    /// Mark the given ID as no longer live.
+    pub fn remove<S: Storage + StorageMut>(
+        &self,
+        storage: &mut S,
+        id: u64,
+    ) -> Result<(), IndexError> {
+        let depth = self.depth(storage)?;
+
+        if (1u64 << depth) < id {
+            return Err(IndexError);
+        }
+
+        self.add(storage, id, depth, |count| count.checked_sub(1))
+    }
+
+    /// Find the live ID at the given (zero-based) position.
+    ///
+    /// Returns `Ok(None)` if there are not enough live IDs.
+    pub fn select<S: Storage>(
+        &self,
+        storage: &S,
+        position: u32,
+    ) -> Result<Option<u64>, IndexError> {
+        let depth = self.depth(storage)?;
+        let capacity = 1u64 << depth;
+
+        if position >= self.node(storage, capacity)? {
+            return Ok(None);
+        }
+
+        // Standard Fenwick tree descent: find the largest index whose prefix count is
+        // at most `position`. The ID we're looking for is the next one.
+        let mut index = 0u64;
+        let mut remaining = position + 1;
+        let mut step = capacity;
+
+        while step > 0 {
+            let next = index + step;
+            if next <= capacity {
+                let count = self.node(storage, next)?;
+                if count < remaining {
+                    index = next;
+                    remaining -= count;
+                }
+            }
+            step >>= 1;
+        }
+
+        Ok(Some(index + 1))
+    }
+
+    fn add<S: Storage + StorageMut>(
+        &self,
+        storage: &mut S,
+        id: u64,
+        depth: u8,
+        f: impl Fn(u32) -> Option<u32>,
+    ) -> Result<(), IndexError> {
+        let capacity = 1u64 << depth;
+        let mut index = id;
+
+        while index <= capacity {
+            let count = f(self.node(storage, index)?).ok_or(IndexError)?;
+            self.set_node(storage, index, count);
+
+            // The root is the last node. Stop there rather than overflow at full depth.
+            if index == capacity {
+                break;
+            }
+            index += index & index.wrapping_neg();
+        }
+
+        Ok(())
+    }
+
+    fn depth<S: Storage>(&self, storage: &S) -> Result<u8, IndexError> {
+        match storage.get_meta(self.depth_key).as_deref() {
+            None => Ok(0),
+            Some(&[depth]) if depth <= MAX_DEPTH => Ok(depth),
+            Some(_) => Err(IndexError),
+        }
+    }
+
+    fn node<S: Storage>(&self, storage: &S, index: u64) -> Result<u32, IndexError> {
+        match storage.get_meta(&self.node_key(index)) {
+            None => Ok(0),
+            Some(bytes) => {
+                let bytes: [u8; 4] = bytes.as_slice().try_into().map_err(|_| IndexError)?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+        }
+    }
+
+    fn set_node<S: StorageMut>(&self, storage: &mut S, index: u64, count: u32) {
+        let key = self.node_key(index);
+
+        if count == 0 {
+            storage.remove_meta(&key);
+        } else {
+            storage.set_meta(&key, &count.to_be_bytes());
+        }
+    }
+
+    fn node_key(&self, index: u64) -> Vec<u8> {
+        [self.node_prefix, &index.to_be_bytes()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mocks::backend::TestStorage;
+
+    const INDEX: PositionIndex = PositionIndex {
+        depth_key: &[0],
+        node_prefix: &[1],
+    };
+
+    #[test]
+    fn select() {
+        let mut storage = TestStorage::new();
+
+        assert_eq!(INDEX.count(&storage), Ok(0));
+        assert_eq!(INDEX.select(&storage, 0), Ok(None));
+
+        let mut live = vec![];
+        for id in [1, 2, 3, 7, 8, 100, 1000, u32::MAX as u64, 1 << 63] {
+            INDEX.insert(&mut storage, id).unwrap();
+            live.push(id);
+        }
+        for id in [2, 8, 1000] {
+            INDEX.remove(&mut storage, id).unwrap();
+            live.retain(|&x| x != id);
+        }
+        INDEX.insert(&mut storage, 2).unwrap();
+        live.insert(1, 2);
+
+        assert_eq!(INDEX.count(&storage), Ok(live.len() as u32));
+        for (position, id) in live.iter().enumerate() {
+            assert_eq!(INDEX.select(&storage, position as u32), Ok(Some(*id)));
+        }
+        assert_eq!(INDEX.select(&storage, live.len() as u32), Ok(None));
+
+        assert_eq!(INDEX.insert(&mut storage, (1 << 63) + 1), Err(IndexError));
+    }
+}
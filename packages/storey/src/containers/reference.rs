@@ -0,0 +1,165 @@
+use core::fmt;
+
+use crate::storage::Storage;
+
+use super::Storable;
+
+/// A container that can be pointed at by a [`Ref`].
+///
+/// This is implemented for containers whose entries can be looked up by a single key,
+/// like [`Column`](super::Column) (by ID) and a [`Map`](super::Map) of
+/// [`Item`](super::Item)s (by map key).
+pub trait RefTarget: Storable {
+    /// The type used to identify an entry in the container.
+    type Id;
+
+    /// The type of the value a reference resolves to.
+    type Target;
+
+    /// The error type returned when resolving fails.
+    type ResolveError;
+
+    /// Look up the entry identified by `id` using the given accessor.
+    fn resolve_id<S: Storage>(
+        access: &Self::Accessor<S>,
+        id: &Self::Id,
+    ) -> Result<Option<Self::Target>, Self::ResolveError>;
+}
+
+/// A typed reference to an entry in another container.
+///
+/// A `Ref` only stores the key of the entry it points to, but its type ties that key
+/// to the target container. This makes it harder to mix up IDs that point into different
+/// containers.
+///
+/// A `Ref` is not kept in sync with its target. If the referenced entry is removed,
+/// the reference becomes dangling and [`resolve`](Self::resolve) returns `Ok(None)`.
+///
+/// With the `serde` feature enabled, a `Ref` (de)serializes as its bare ID, so it can
+/// be stored as a value in other containers.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::{Column, Ref};
+///
+/// let mut storage = TestStorage::new();
+/// let column = Column::<u64, TestEncoding>::new(0);
+/// let mut access = column.access(&mut storage);
+///
+/// let id = access.push(&1337).unwrap();
+/// let reference = Ref::<Column<u64, TestEncoding>>::new(id);
+///
+/// assert_eq!(reference.resolve(&access).unwrap(), Some(1337));
+///
+/// access.remove(id).unwrap();
+/// assert_eq!(reference.resolve(&access).unwrap(), None);
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        transparent,
+        bound(
+            serialize = "T::Id: serde::Serialize",
+            deserialize = "T::Id: serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Ref<T: RefTarget> {
+    id: T::Id,
+}
+
+impl<T: RefTarget> Ref<T> {
+    /// Create a reference to the entry identified by `id`.
+    pub const fn new(id: T::Id) -> Self {
+        Self { id }
+    }
+
+    /// The ID of the referenced entry.
+    pub fn id(&self) -> &T::Id {
+        &self.id
+    }
+
+    /// Consume the reference, returning the ID of the referenced entry.
+    pub fn into_id(self) -> T::Id {
+        self.id
+    }
+
+    /// Fetch the referenced value using an accessor for the target container.
+    ///
+    /// Returns `Ok(None)` if the reference is dangling.
+    pub fn resolve<S: Storage>(
+        &self,
+        target: &T::Accessor<S>,
+    ) -> Result<Option<T::Target>, T::ResolveError> {
+        T::resolve_id(target, &self.id)
+    }
+}
+
+impl<T: RefTarget> fmt::Debug for Ref<T>
+where
+    T::Id: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ref").field(&self.id).finish()
+    }
+}
+
+impl<T: RefTarget> Clone for Ref<T>
+where
+    T::Id: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<T: RefTarget> Copy for Ref<T> where T::Id: Copy {}
+
+impl<T: RefTarget> PartialEq for Ref<T>
+where
+    T::Id: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: RefTarget> Eq for Ref<T> where T::Id: Eq {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::containers::{Column, Item, Map};
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::TestEncoding;
+
+    #[test]
+    fn resolve() {
+        let mut storage = TestStorage::new();
+
+        let column = Column::<u64, TestEncoding>::new(0);
+        let mut column_access = column.access(&mut storage);
+        let valid = Ref::<Column<u64, TestEncoding>>::new(column_access.push(&1337).unwrap());
+        let dangling = Ref::<Column<u64, TestEncoding>>::new(2);
+
+        assert_eq!(valid.resolve(&column_access), Ok(Some(1337)));
+        assert_eq!(dangling.resolve(&column_access), Ok(None));
+
+        let map = Map::<String, Item<u64, TestEncoding>>::new(1);
+        let mut map_access = map.access(&mut storage);
+        map_access.entry_mut("foo").set(&42).unwrap();
+        let valid = Ref::<Map<String, Item<u64, TestEncoding>>>::new("foo".to_string());
+        let dangling = Ref::<Map<String, Item<u64, TestEncoding>>>::new("bar".to_string());
+
+        assert_eq!(valid.resolve(&map_access), Ok(Some(42)));
+        assert_eq!(dangling.resolve(&map_access), Ok(None));
+        assert_eq!(valid.into_id(), "foo");
+    }
+}
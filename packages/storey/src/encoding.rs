@@ -54,6 +54,7 @@
 //! // If there's only one encoding present for `u64`, we can use `encode` directly.
 //! // Otherwise, we would need to disambiguate.
 //!
+//! # #[cfg(not(feature = "bincode"))]
 //! assert_eq!(12u64.encode(), Ok("12".as_bytes().to_vec()));
 //! ```
 //!
@@ -90,9 +91,23 @@
 //! // If there's only one encoding present for `u64`, we can use `decode` directly.
 //! // Otherwise, we would need to disambiguate.
 //!
+//! # #[cfg(not(feature = "bincode"))]
 //! assert_eq!(u64::decode("12".as_bytes()), Ok(12));
 //! ```
 
+#[cfg(feature = "bincode")]
+mod bincode;
+
+#[cfg(feature = "bincode")]
+pub use self::bincode::{BincodeDecodeError, BincodeEncodeError, BincodeEncoding};
+
+mod raw;
+
+pub use self::raw::RawEncoding;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
 /// A trait for types that serve as "markers" for a particular encoding.
 /// These types are expected to be empty structs.
 pub use storey_encoding::Encoding;
@@ -157,3 +172,394 @@ pub use storey_encoding::DecodableWithImpl;
 /// [orphan rules]: https://doc.rust-lang.org/reference/items/implementations.html#orphan-rules
 /// [cover]: https://doc.rust-lang.org/reference/glossary.html#uncovered-type
 pub use storey_encoding::Cover;
+
+/// An encoding adapter that stores [`Option`] values with an explicit presence tag.
+///
+/// Many encodings represent `None` in a way that's easy to confuse with "nothing stored"
+/// (or don't support it at all). `OptionEncoding<E>` prefixes every value with a tag
+/// byte: `0` for `None` and `1` for `Some`, followed by the `E`-encoded inner value. That
+/// way a stored `None` is a real value that can be retrieved, distinct from a missing entry.
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Item;
+/// use storey::encoding::OptionEncoding;
+///
+/// let mut storage = TestStorage::new();
+/// let item = Item::<Option<u64>, OptionEncoding<TestEncoding>>::new(0);
+///
+/// assert_eq!(item.access(&storage).get().unwrap(), None);
+///
+/// item.access(&mut storage).set(&None).unwrap();
+/// assert_eq!(item.access(&storage).get().unwrap(), Some(None));
+///
+/// item.access(&mut storage).set(&Some(42)).unwrap();
+/// assert_eq!(item.access(&storage).get().unwrap(), Some(Some(42)));
+/// ```
+pub struct OptionEncoding<E>(core::marker::PhantomData<E>);
+
+impl<E: Encoding> Encoding for OptionEncoding<E> {
+    type EncodeError = E::EncodeError;
+    type DecodeError = OptionDecodeError<E::DecodeError>;
+}
+
+impl<E, T> EncodableWithImpl<OptionEncoding<E>> for Cover<&Option<T>>
+where
+    E: Encoding,
+    T: EncodableWith<E>,
+{
+    fn encode_impl(self) -> Result<Vec<u8>, E::EncodeError> {
+        match self.0 {
+            None => Ok(vec![0]),
+            Some(value) => {
+                let inner = value.encode()?;
+                let mut result = Vec::with_capacity(inner.len() + 1);
+                result.push(1);
+                result.extend_from_slice(&inner);
+                Ok(result)
+            }
+        }
+    }
+}
+
+impl<E, T> DecodableWithImpl<OptionEncoding<E>> for Cover<Option<T>>
+where
+    E: Encoding,
+    T: DecodableWith<E>,
+{
+    fn decode_impl(data: &[u8]) -> Result<Self, OptionDecodeError<E::DecodeError>> {
+        match data.split_first() {
+            Some((0, [])) => Ok(Cover(None)),
+            Some((0, _)) => Err(OptionDecodeError::TrailingData),
+            Some((1, rest)) => T::decode(rest)
+                .map(|value| Cover(Some(value)))
+                .map_err(OptionDecodeError::Inner),
+            Some((tag, _)) => Err(OptionDecodeError::InvalidTag(*tag)),
+            None => Err(OptionDecodeError::Empty),
+        }
+    }
+}
+
+/// An error returned when decoding a value encoded with [`OptionEncoding`] fails.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum OptionDecodeError<E> {
+    #[error("empty value, expected a presence tag")]
+    Empty,
+    #[error("invalid presence tag: {0}")]
+    InvalidTag(u8),
+    #[error("unexpected data after `None` tag")]
+    TrailingData,
+    #[error("{0}")]
+    Inner(E),
+}
+
+impl<E: core::fmt::Display> crate::error::StoreyError for OptionDecodeError<E> {}
+
+/// A trait for enums that can be stored with [`EnumEncoding`].
+///
+/// Each variant is identified by a discriminant byte. The variant's payload (if any) is
+/// encoded with the inner encoding `E`.
+///
+/// See [`EnumEncoding`] for an example.
+pub trait EnumVariants<E: Encoding>: Sized {
+    /// The discriminant byte identifying the variant of `self`.
+    fn discriminant(&self) -> u8;
+
+    /// Encode the payload of the variant. Unit variants should return an empty vector.
+    fn encode_payload(&self) -> Result<Vec<u8>, E::EncodeError>;
+
+    /// Decode the variant identified by `discriminant` from its payload.
+    ///
+    /// Returns `Ok(None)` if the discriminant doesn't identify any variant.
+    fn decode_payload(discriminant: u8, payload: &[u8]) -> Result<Option<Self>, E::DecodeError>;
+}
+
+/// An encoding adapter that stores enums as a discriminant byte followed by the variant
+/// payload encoded with `E`.
+///
+/// This allows storing enums compactly without relying on serde. The enum needs to
+/// implement [`EnumVariants`].
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::{MockError, TestEncoding};
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Item;
+/// use storey::encoding::{DecodableWith as _, EncodableWith as _, EnumEncoding, EnumVariants};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Status {
+///     Pending,
+///     Done(u64),
+/// }
+///
+/// # #[cfg(not(feature = "bincode"))]
+/// impl EnumVariants<TestEncoding> for Status {
+///     fn discriminant(&self) -> u8 {
+///         match self {
+///             Status::Pending => 0,
+///             Status::Done(_) => 1,
+///         }
+///     }
+///
+///     fn encode_payload(&self) -> Result<Vec<u8>, MockError> {
+///         match self {
+///             Status::Pending => Ok(vec![]),
+///             Status::Done(at) => at.encode(),
+///         }
+///     }
+///
+///     fn decode_payload(discriminant: u8, payload: &[u8]) -> Result<Option<Self>, MockError> {
+///         match discriminant {
+///             0 => Ok(Some(Status::Pending)),
+///             1 => Ok(Some(Status::Done(u64::decode(payload)?))),
+///             _ => Ok(None),
+///         }
+///     }
+/// }
+///
+/// # #[cfg(not(feature = "bincode"))]
+/// # {
+/// let mut storage = TestStorage::new();
+/// let item = Item::<Status, EnumEncoding<TestEncoding>>::new(0);
+///
+/// item.access(&mut storage).set(&Status::Done(42)).unwrap();
+/// assert_eq!(item.access(&storage).get().unwrap(), Some(Status::Done(42)));
+/// # }
+/// ```
+pub struct EnumEncoding<E>(core::marker::PhantomData<E>);
+
+impl<E: Encoding> Encoding for EnumEncoding<E> {
+    type EncodeError = E::EncodeError;
+    type DecodeError = EnumDecodeError<E::DecodeError>;
+}
+
+impl<E, T> EncodableWithImpl<EnumEncoding<E>> for Cover<&T>
+where
+    E: Encoding,
+    T: EnumVariants<E>,
+{
+    fn encode_impl(self) -> Result<Vec<u8>, E::EncodeError> {
+        let payload = self.0.encode_payload()?;
+        let mut result = Vec::with_capacity(payload.len() + 1);
+        result.push(self.0.discriminant());
+        result.extend_from_slice(&payload);
+        Ok(result)
+    }
+}
+
+impl<E, T> DecodableWithImpl<EnumEncoding<E>> for Cover<T>
+where
+    E: Encoding,
+    T: EnumVariants<E>,
+{
+    fn decode_impl(data: &[u8]) -> Result<Self, EnumDecodeError<E::DecodeError>> {
+        let (&discriminant, payload) = data.split_first().ok_or(EnumDecodeError::Empty)?;
+
+        T::decode_payload(discriminant, payload)
+            .map_err(EnumDecodeError::Inner)?
+            .map(Cover)
+            .ok_or(EnumDecodeError::UnknownDiscriminant(discriminant))
+    }
+}
+
+/// An error returned when decoding a value encoded with [`EnumEncoding`] fails.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum EnumDecodeError<E> {
+    #[error("empty value, expected a discriminant")]
+    Empty,
+    #[error("unknown discriminant: {0}")]
+    UnknownDiscriminant(u8),
+    #[error("{0}")]
+    Inner(E),
+}
+
+impl<E: core::fmt::Display> crate::error::StoreyError for EnumDecodeError<E> {}
+
+/// A cap on the size of encoded values: values encoded with `E` may take at most `MAX` bytes.
+///
+/// This is a policy cap guarding against accidental state bloat (e.g. from user-supplied
+/// data), independent of any limits the storage backend might impose. An oversized value
+/// results in [`SizeLimitError::TooLarge`], so it never reaches the storage.
+///
+/// This isn't an encoding itself - an encoding adapter would make every encodable type
+/// encodable in infinitely many ways, and `value.encode()` could no longer infer which one
+/// is meant. Instead, the cap is checked explicitly, either with [`encode`](Self::encode)
+/// or by an accessor method like [`ItemAccess::set_limited`].
+///
+/// [`ItemAccess::set_limited`]: crate::containers::ItemAccess::set_limited
+///
+/// # Example
+/// ```
+/// # use mocks::encoding::TestEncoding;
+/// use storey::encoding::{SizeLimitError, SizeLimited};
+///
+/// assert_eq!(SizeLimited::<TestEncoding, 8>::encode(&42u64).unwrap().len(), 8);
+/// assert_eq!(
+///     SizeLimited::<TestEncoding, 4>::encode(&42u64),
+///     Err(SizeLimitError::TooLarge { size: 8, max: 4 })
+/// );
+/// ```
+pub struct SizeLimited<E, const MAX: usize>(core::marker::PhantomData<E>);
+
+impl<E: Encoding, const MAX: usize> SizeLimited<E, MAX> {
+    /// Encode `value` with `E`, failing if the result is larger than `MAX` bytes.
+    pub fn encode<T>(value: &T) -> Result<Vec<u8>, SizeLimitError<E::EncodeError>>
+    where
+        T: EncodableWith<E>,
+    {
+        let encoded = value.encode().map_err(SizeLimitError::Inner)?;
+
+        if encoded.len() > MAX {
+            return Err(SizeLimitError::TooLarge {
+                size: encoded.len(),
+                max: MAX,
+            });
+        }
+
+        Ok(encoded)
+    }
+}
+
+/// An error returned when encoding a value with [`SizeLimited`] fails.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, thiserror::Error)]
+pub enum SizeLimitError<E> {
+    #[error("encoded value is {size} bytes, exceeding the limit of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+    #[error("{0}")]
+    Inner(E),
+}
+
+impl<E: core::fmt::Display> crate::error::StoreyError for SizeLimitError<E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::containers::{Item, Map};
+
+    use mocks::backend::TestStorage;
+    use mocks::encoding::{MockError, TestEncoding};
+
+    #[test]
+    fn option_encoding() {
+        type Enc = OptionEncoding<TestEncoding>;
+
+        assert_eq!(EncodableWith::<Enc>::encode(&None::<u64>), Ok(vec![0]));
+        assert_eq!(
+            EncodableWith::<Enc>::encode(&Some(42u64)),
+            Ok([&[1][..], &42u64.to_le_bytes()].concat())
+        );
+
+        assert_eq!(<Option<u64> as DecodableWith<Enc>>::decode(&[0]), Ok(None));
+        assert_eq!(
+            <Option<u64> as DecodableWith<Enc>>::decode(&[0, 1]),
+            Err(OptionDecodeError::TrailingData)
+        );
+        assert_eq!(
+            <Option<u64> as DecodableWith<Enc>>::decode(&[2]),
+            Err(OptionDecodeError::InvalidTag(2))
+        );
+        assert_eq!(
+            <Option<u64> as DecodableWith<Enc>>::decode(&[]),
+            Err(OptionDecodeError::Empty)
+        );
+    }
+
+    #[test]
+    fn default_encode_into_appends() {
+        let mut buf = vec![0xff];
+        EncodableWith::<TestEncoding>::encode_into(&42u64, &mut buf).unwrap();
+        assert_eq!(buf, [&[0xff][..], &42u64.to_le_bytes()].concat());
+    }
+
+    #[test]
+    fn stored_none_is_not_absent() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<Option<u64>, OptionEncoding<TestEncoding>>>::new(0);
+        let mut access = map.access(&mut storage);
+
+        access.entry_mut("foo").set(&None).unwrap();
+        access.entry_mut("bar").set(&Some(42)).unwrap();
+
+        assert_eq!(access.entry("foo").get().unwrap(), Some(None));
+        assert_eq!(access.entry("bar").get().unwrap(), Some(Some(42)));
+        assert_eq!(access.entry("baz").get().unwrap(), None);
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Square(u64),
+        Unit,
+    }
+
+    impl EnumVariants<TestEncoding> for Shape {
+        fn discriminant(&self) -> u8 {
+            match self {
+                Shape::Empty => 0,
+                Shape::Square(_) => 1,
+                Shape::Unit => 2,
+            }
+        }
+
+        fn encode_payload(&self) -> Result<Vec<u8>, MockError> {
+            match self {
+                Shape::Square(side) => EncodableWith::<TestEncoding>::encode(side),
+                _ => Ok(vec![]),
+            }
+        }
+
+        fn decode_payload(discriminant: u8, payload: &[u8]) -> Result<Option<Self>, MockError> {
+            match discriminant {
+                0 => Ok(Some(Shape::Empty)),
+                1 => Ok(Some(Shape::Square(
+                    <u64 as DecodableWith<TestEncoding>>::decode(payload)?,
+                ))),
+                2 => Ok(Some(Shape::Unit)),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn enum_encoding() {
+        type Enc = EnumEncoding<TestEncoding>;
+
+        for shape in [Shape::Empty, Shape::Square(42), Shape::Unit] {
+            let bytes = EncodableWith::<Enc>::encode(&shape).unwrap();
+            assert_eq!(bytes[0], shape.discriminant());
+            assert_eq!(<Shape as DecodableWith<Enc>>::decode(&bytes), Ok(shape));
+        }
+
+        assert_eq!(
+            EncodableWith::<Enc>::encode(&Shape::Square(42)),
+            Ok([&[1][..], &42u64.to_le_bytes()].concat())
+        );
+        assert_eq!(EncodableWith::<Enc>::encode(&Shape::Unit), Ok(vec![2]));
+
+        assert_eq!(
+            <Shape as DecodableWith<Enc>>::decode(&[3]),
+            Err(EnumDecodeError::UnknownDiscriminant(3))
+        );
+        assert_eq!(
+            <Shape as DecodableWith<Enc>>::decode(&[]),
+            Err(EnumDecodeError::Empty)
+        );
+    }
+
+    #[test]
+    fn size_limited() {
+        assert_eq!(
+            SizeLimited::<TestEncoding, 8>::encode(&42u64),
+            Ok(42u64.to_le_bytes().to_vec())
+        );
+        assert_eq!(
+            SizeLimited::<TestEncoding, 7>::encode(&42u64),
+            Err(SizeLimitError::TooLarge { size: 8, max: 7 })
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use super::{Cover, DecodableWithImpl, EncodableWithImpl, Encoding};
+
+/// An encoding that delegates to [*Bincode*] (1.x, with its default configuration).
+///
+/// Any type implementing [`serde::Serialize`] can be encoded, and any type implementing
+/// [`serde::de::DeserializeOwned`] can be decoded. This is only available with the `bincode`
+/// feature enabled.
+///
+/// # Example
+/// ```
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Item;
+/// use storey::encoding::BincodeEncoding;
+///
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     owner: String,
+///     fee: u16,
+/// }
+///
+/// let mut storage = TestStorage::new();
+/// let item = Item::<Config, BincodeEncoding>::new(0);
+///
+/// let config = Config { owner: "alice".to_string(), fee: 30 };
+/// item.access(&mut storage).set(&config).unwrap();
+/// assert_eq!(item.access(&storage).get().unwrap(), Some(config));
+/// ```
+///
+/// [*Bincode*]: https://docs.rs/bincode/1
+pub struct BincodeEncoding;
+
+impl Encoding for BincodeEncoding {
+    type DecodeError = BincodeDecodeError;
+    type EncodeError = BincodeEncodeError;
+}
+
+impl<T> EncodableWithImpl<BincodeEncoding> for Cover<&T>
+where
+    T: serde::Serialize,
+{
+    fn encode_impl(self) -> Result<Vec<u8>, BincodeEncodeError> {
+        ::bincode::serialize(self.0).map_err(BincodeEncodeError)
+    }
+
+    fn encode_into_impl(self, buf: &mut Vec<u8>) -> Result<(), BincodeEncodeError> {
+        ::bincode::serialize_into(buf, self.0).map_err(BincodeEncodeError)
+    }
+}
+
+impl<T> DecodableWithImpl<BincodeEncoding> for Cover<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn decode_impl(data: &[u8]) -> Result<Self, BincodeDecodeError> {
+        ::bincode::deserialize(data)
+            .map(Cover)
+            .map_err(BincodeDecodeError)
+    }
+}
+
+/// An error returned when encoding a value with [`BincodeEncoding`] fails.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to encode value with bincode: {0}")]
+pub struct BincodeEncodeError(pub ::bincode::Error);
+
+impl crate::error::StoreyError for BincodeEncodeError {}
+
+/// An error returned when decoding a value with [`BincodeEncoding`] fails.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to decode value with bincode: {0}")]
+pub struct BincodeDecodeError(pub ::bincode::Error);
+
+impl crate::error::StoreyError for BincodeDecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::containers::{Column, Item};
+
+    use super::*;
+
+    use mocks::backend::TestStorage;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Account {
+        owner: String,
+        balance: u128,
+        frozen: bool,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Action {
+        Transfer { to: String, amount: u64 },
+        Freeze,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Registry {
+        admins: Vec<String>,
+        limits: BTreeMap<String, Option<u32>>,
+    }
+
+    #[test]
+    fn item_roundtrip() {
+        let mut storage = TestStorage::new();
+
+        let account = Account {
+            owner: "alice".to_string(),
+            balance: u128::MAX,
+            frozen: false,
+        };
+        let item = Item::<Account, BincodeEncoding>::new(0);
+        item.access(&mut storage).set(&account).unwrap();
+        assert_eq!(item.access(&storage).get().unwrap(), Some(account));
+
+        let registry = Registry {
+            admins: vec!["alice".to_string(), "bob".to_string()],
+            limits: BTreeMap::from([("alice".to_string(), Some(5)), ("bob".to_string(), None)]),
+        };
+        let item = Item::<Registry, BincodeEncoding>::new(1);
+        item.access(&mut storage).set(&registry).unwrap();
+        assert_eq!(item.access(&storage).get().unwrap(), Some(registry));
+    }
+
+    #[test]
+    fn column_roundtrip() {
+        let mut storage = TestStorage::new();
+
+        let actions = [
+            Action::Transfer {
+                to: "bob".to_string(),
+                amount: 10,
+            },
+            Action::Freeze,
+        ];
+        let column = Column::<Action, BincodeEncoding>::new(0);
+        let mut access = column.access(&mut storage);
+        for action in &actions {
+            access.push(action).unwrap();
+        }
+
+        assert_eq!(access.get(1).unwrap(), Some(actions[0].clone()));
+        assert_eq!(access.get(2).unwrap(), Some(actions[1].clone()));
+    }
+
+    #[test]
+    fn decode_error() {
+        let mut storage = TestStorage::new();
+
+        Item::<u8, BincodeEncoding>::new(0)
+            .access(&mut storage)
+            .set(&1)
+            .unwrap();
+
+        let err = Item::<Account, BincodeEncoding>::new(0)
+            .access(&storage)
+            .get()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("failed to decode value with bincode"));
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use super::{Cover, DecodableWithImpl, EncodableWithImpl, Encoding};
+
+/// A passthrough encoding for values that already are bytes.
+///
+/// Values are stored as-is, with no framing, and decoding copies the stored bytes. This
+/// avoids double-encoding things like hashes or pre-serialized blobs.
+///
+/// To avoid accidentally storing arbitrary types as raw bytes, this is only implemented for
+/// byte-like types - ones that can be viewed as `[u8]` and built from a `Vec<u8>`, like
+/// `Vec<u8>`, `Box<[u8]>` or *CosmWasm*'s `Binary`. Notably, `String` isn't covered.
+///
+/// # Example
+/// ```
+/// # use mocks::backend::TestStorage;
+/// use storey::containers::Item;
+/// use storey::encoding::RawEncoding;
+/// use storey::storage::Storage as _;
+///
+/// let mut storage = TestStorage::new();
+/// let item = Item::<Vec<u8>, RawEncoding>::new(0);
+///
+/// item.access(&mut storage).set(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(item.access(&storage).get().unwrap(), Some(vec![1, 2, 3]));
+/// assert_eq!(storage.get(&[0]), Some(vec![1, 2, 3]));
+/// ```
+pub struct RawEncoding;
+
+impl Encoding for RawEncoding {
+    type DecodeError = Infallible;
+    type EncodeError = Infallible;
+}
+
+impl<T> EncodableWithImpl<RawEncoding> for Cover<&T>
+where
+    T: AsRef<[u8]> + From<Vec<u8>>,
+{
+    fn encode_impl(self) -> Result<Vec<u8>, Infallible> {
+        Ok(self.0.as_ref().to_vec())
+    }
+
+    fn encode_into_impl(self, buf: &mut Vec<u8>) -> Result<(), Infallible> {
+        buf.extend_from_slice(self.0.as_ref());
+        Ok(())
+    }
+}
+
+impl<T> DecodableWithImpl<RawEncoding> for Cover<T>
+where
+    T: AsRef<[u8]> + From<Vec<u8>>,
+{
+    fn decode_impl(data: &[u8]) -> Result<Self, Infallible> {
+        Ok(Cover(T::from(data.to_vec())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::containers::{Item, Map};
+    use crate::storage::Storage as _;
+
+    use super::*;
+
+    use mocks::backend::TestStorage;
+
+    #[test]
+    fn no_framing() {
+        let mut storage = TestStorage::new();
+
+        let item = Item::<Vec<u8>, RawEncoding>::new(0);
+        item.access(&mut storage).set(&vec![]).unwrap();
+        assert_eq!(storage.get(&[0]), Some(vec![]));
+        assert_eq!(item.access(&storage).get(), Ok(Some(vec![])));
+
+        let item = Item::<Box<[u8]>, RawEncoding>::new(1);
+        let hash: Box<[u8]> = Box::new([0xde, 0xad, 0xbe, 0xef]);
+        item.access(&mut storage).set(&hash).unwrap();
+        assert_eq!(storage.get(&[1]), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(item.access(&storage).get(), Ok(Some(hash)));
+    }
+
+    #[test]
+    fn in_map() {
+        let mut storage = TestStorage::new();
+
+        let map = Map::<String, Item<Vec<u8>, RawEncoding>>::new(0);
+        map.access(&mut storage)
+            .entry_mut("foo")
+            .set(&b"bar".to_vec())
+            .unwrap();
+
+        assert_eq!(storage.get(&[0, b'f', b'o', b'o']), Some(b"bar".to_vec()));
+        assert_eq!(
+            map.access(&storage).entry("foo").get(),
+            Ok(Some(b"bar".to_vec()))
+        );
+    }
+}
@@ -8,13 +8,33 @@
 //!
 //! The encoding of keys is the responsibility of this framework and its collections.
 //! The encoding of values is abstracted away by the traits in the [`encoding`] module.
-//! Specific value encodings are implemented outside of this crate. It's not hard
-//! to plug in any encoding you like.
+//! Specific value encodings are mostly implemented outside of this crate, with the exception
+//! of an optional [*Bincode*](https://docs.rs/bincode/1) encoding behind the `bincode`
+//! feature. It's not hard to plug in any encoding you like.
 //!
 //! Similarly, the storage backend is pluggable. The [`storage`] module provides traits
 //! for that.
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` compatible (though it still requires `alloc`). Disable the default
+//! `std` feature to use it that way. Without `std`, error types implement
+//! [`core::error::Error`], which requires Rust 1.81. Features that pull in `std`-only
+//! dependencies (`csv`, `bincode`) enable `std` themselves, and `Path`/`PathBuf` keys are
+//! only available with `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod containers;
 pub mod encoding;
 pub mod error;
 pub mod storage;
+
+/// Support code for the derive macros. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
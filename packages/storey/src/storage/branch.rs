@@ -1,6 +1,12 @@
-use std::ops::Bound;
+use alloc::vec::Vec;
+use core::ops::Bound;
 
-use crate::storage::{IterableStorage, RevIterableStorage, Storage, StorageMut};
+#[cfg(feature = "async")]
+use crate::storage::{AsyncStorage, AsyncStorageMut};
+use crate::storage::{
+    IterableMetaStorage, IterableStorage, RevIterableStorage, Seekable, Storage, StorageMut,
+};
+use storey_storage::__private::sub_bounds;
 
 /// A type representing a storage namespace created by applying a prefix to all keys.
 ///
@@ -22,6 +28,7 @@ use crate::storage::{IterableStorage, RevIterableStorage, Storage, StorageMut};
 /// assert_eq!(branch.get(b"bar"), Some(b"baz".to_vec()));
 /// assert_eq!(storage.get(b"foobar"), Some(b"baz".to_vec()));
 /// ```
+#[derive(Clone)]
 pub struct StorageBranch<S> {
     backend: S,
     prefix: Vec<u8>,
@@ -32,44 +39,143 @@ impl<S> StorageBranch<S> {
     pub fn new(backend: S, prefix: Vec<u8>) -> Self {
         Self { backend, prefix }
     }
+
+    /// Returns the prefix applied to all keys in this branch.
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
 }
 
-impl<S: Storage> Storage for StorageBranch<&S> {
+impl<S: Storage + ?Sized> Storage for StorageBranch<&S> {
     fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
-        self.backend.get(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.get(key))
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        with_full_key(&self.prefix, key, |key| self.backend.has(key))
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let keys: Vec<_> = keys
+            .iter()
+            .map(|key| [&self.prefix, *key].concat())
+            .collect();
+        let keys: Vec<_> = keys.iter().map(Vec::as_slice).collect();
+        self.backend.get_many(&keys)
     }
 
     fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
-        self.backend.get_meta(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.get_meta(key))
+    }
+
+    fn has_meta(&self, key: &[u8]) -> bool {
+        with_full_key(&self.prefix, key, |key| self.backend.has_meta(key))
     }
 }
 
-impl<S: Storage> Storage for StorageBranch<&mut S> {
+impl<S: Storage + ?Sized> Storage for StorageBranch<&mut S> {
     fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
-        self.backend.get(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.get(key))
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        with_full_key(&self.prefix, key, |key| self.backend.has(key))
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let keys: Vec<_> = keys
+            .iter()
+            .map(|key| [&self.prefix, *key].concat())
+            .collect();
+        let keys: Vec<_> = keys.iter().map(Vec::as_slice).collect();
+        self.backend.get_many(&keys)
     }
 
     fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
-        self.backend.get_meta(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.get_meta(key))
+    }
+
+    fn has_meta(&self, key: &[u8]) -> bool {
+        with_full_key(&self.prefix, key, |key| self.backend.has_meta(key))
     }
 }
 
-impl<S: StorageMut> StorageMut for StorageBranch<&mut S> {
+impl<S: StorageMut + ?Sized> StorageMut for StorageBranch<&mut S> {
     fn set(&mut self, key: &[u8], value: &[u8]) {
-        self.backend.set(&[&self.prefix[..], key].concat(), value)
+        with_full_key(&self.prefix, key, |key| self.backend.set(key, value))
     }
 
     fn remove(&mut self, key: &[u8]) {
-        self.backend.remove(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.remove(key))
     }
 
     fn set_meta(&mut self, key: &[u8], value: &[u8]) {
-        self.backend
-            .set_meta(&[&self.prefix[..], key].concat(), value)
+        with_full_key(&self.prefix, key, |key| self.backend.set_meta(key, value))
     }
 
     fn remove_meta(&mut self, key: &[u8]) {
-        self.backend.remove_meta(&[&self.prefix[..], key].concat())
+        with_full_key(&self.prefix, key, |key| self.backend.remove_meta(key))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: AsyncStorage + ?Sized> AsyncStorage for StorageBranch<&S> {
+    async fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.backend.get(&[&self.prefix, key].concat()).await
+    }
+
+    async fn has(&self, key: &[u8]) -> bool {
+        self.backend.has(&[&self.prefix, key].concat()).await
+    }
+
+    async fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.backend.get_meta(&[&self.prefix, key].concat()).await
+    }
+
+    async fn has_meta(&self, key: &[u8]) -> bool {
+        self.backend.has_meta(&[&self.prefix, key].concat()).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: AsyncStorage + ?Sized> AsyncStorage for StorageBranch<&mut S> {
+    async fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.backend.get(&[&self.prefix, key].concat()).await
+    }
+
+    async fn has(&self, key: &[u8]) -> bool {
+        self.backend.has(&[&self.prefix, key].concat()).await
+    }
+
+    async fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.backend.get_meta(&[&self.prefix, key].concat()).await
+    }
+
+    async fn has_meta(&self, key: &[u8]) -> bool {
+        self.backend.has_meta(&[&self.prefix, key].concat()).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S: AsyncStorageMut + ?Sized> AsyncStorageMut for StorageBranch<&mut S> {
+    async fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.backend.set(&[&self.prefix, key].concat(), value).await
+    }
+
+    async fn remove(&mut self, key: &[u8]) {
+        self.backend.remove(&[&self.prefix, key].concat()).await
+    }
+
+    async fn set_meta(&mut self, key: &[u8], value: &[u8]) {
+        self.backend
+            .set_meta(&[&self.prefix, key].concat(), value)
+            .await
+    }
+
+    async fn remove_meta(&mut self, key: &[u8]) {
+        self.backend
+            .remove_meta(&[&self.prefix, key].concat())
+            .await
     }
 }
 
@@ -86,7 +192,7 @@ impl<S: IterableStorage> IterableStorage for StorageBranch<&S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 
@@ -107,7 +213,7 @@ impl<S: IterableStorage> IterableStorage for StorageBranch<&S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 }
@@ -125,7 +231,7 @@ impl<S: IterableStorage> IterableStorage for StorageBranch<&mut S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 
@@ -146,7 +252,7 @@ impl<S: IterableStorage> IterableStorage for StorageBranch<&mut S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 }
@@ -164,7 +270,7 @@ impl<S: RevIterableStorage> RevIterableStorage for StorageBranch<&S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 
@@ -193,7 +299,7 @@ impl<S: RevIterableStorage> RevIterableStorage for StorageBranch<&S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 }
@@ -211,7 +317,7 @@ impl<S: RevIterableStorage> RevIterableStorage for StorageBranch<&mut S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 
@@ -240,46 +346,75 @@ impl<S: RevIterableStorage> RevIterableStorage for StorageBranch<&mut S> {
                 start.as_ref().map(AsRef::as_ref),
                 end.as_ref().map(AsRef::as_ref),
             ),
-            prefix_len: self.prefix.len(),
+            prefix: self.prefix.clone(),
         }
     }
 }
 
-fn sub_bounds(
-    prefix: &[u8],
-    start: Bound<&[u8]>,
-    end: Bound<&[u8]>,
-) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
-    if prefix.is_empty() {
-        (start.map(|s| s.to_vec()), end.map(|s| s.to_vec()))
+impl<S: IterableMetaStorage> IterableMetaStorage for StorageBranch<&S> {
+    type MetaKeysIterator<'a> = BranchKeysIter<S::MetaKeysIterator<'a>> where Self: 'a;
+
+    fn meta_keys<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::MetaKeysIterator<'a> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        BranchKeysIter {
+            inner: self.backend.meta_keys(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+}
+
+impl<S: IterableMetaStorage> IterableMetaStorage for StorageBranch<&mut S> {
+    type MetaKeysIterator<'a> = BranchKeysIter<S::MetaKeysIterator<'a>> where Self: 'a;
+
+    fn meta_keys<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::MetaKeysIterator<'a> {
+        let (start, end) = sub_bounds(&self.prefix, start, end);
+
+        BranchKeysIter {
+            inner: self.backend.meta_keys(
+                start.as_ref().map(AsRef::as_ref),
+                end.as_ref().map(AsRef::as_ref),
+            ),
+            prefix: self.prefix.clone(),
+        }
+    }
+}
+
+/// Keys up to this length are assembled on the stack rather than allocated.
+const STACK_KEY_LEN: usize = 64;
+
+/// Call `f` with `key` appended to `prefix`.
+///
+/// Branch keys are usually short, so this avoids allocating a fresh `Vec` for every operation
+/// in the common case.
+fn with_full_key<R>(prefix: &[u8], key: &[u8], f: impl FnOnce(&[u8]) -> R) -> R {
+    let len = prefix.len() + key.len();
+
+    if len <= STACK_KEY_LEN {
+        let mut buf = [0; STACK_KEY_LEN];
+        buf[..prefix.len()].copy_from_slice(prefix);
+        buf[prefix.len()..len].copy_from_slice(key);
+        f(&buf[..len])
     } else {
-        (
-            // concat prefix and start if bounded
-            // return just the prefix if unbounded
-            if let Bound::Unbounded = start {
-                Bound::Included(prefix.to_vec())
-            } else {
-                start.map(|s| [prefix, s].concat())
-            },
-            if let Bound::Unbounded = end {
-                Bound::Excluded({
-                    let mut pref = prefix.to_vec();
-                    if let Some(x) = pref.last_mut() {
-                        *x += 1;
-                    }
-                    pref
-                })
-            } else {
-                end.map(|e| [prefix, e].concat())
-            },
-        )
+        f(&[prefix, key].concat())
     }
 }
 
 /// An iterator over the keys of a `StorageBranch`.
 pub struct BranchKeysIter<I> {
     inner: I,
-    prefix_len: usize,
+    prefix: Vec<u8>,
 }
 
 impl<I> Iterator for BranchKeysIter<I>
@@ -289,14 +424,31 @@ where
     type Item = Vec<u8>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|key| key[self.prefix_len..].to_vec())
+        self.inner
+            .next()
+            .map(|key| key[self.prefix.len()..].to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for BranchKeysIter<I>
+where
+    I: DoubleEndedIterator<Item = Vec<u8>>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|key| key[self.prefix.len()..].to_vec())
     }
 }
 
 /// An iterator over the key-value pairs of a `StorageBranch`.
 pub struct BranchKVIter<I> {
     inner: I,
-    prefix_len: usize,
+    prefix: Vec<u8>,
 }
 
 impl<I> Iterator for BranchKVIter<I>
@@ -307,12 +459,46 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|(key, value)| {
-            let key = key[self.prefix_len..].to_vec();
+            let key = key[self.prefix.len()..].to_vec();
+            (key, value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I> DoubleEndedIterator for BranchKVIter<I>
+where
+    I: DoubleEndedIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, value)| {
+            let key = key[self.prefix.len()..].to_vec();
             (key, value)
         })
     }
 }
 
+impl<I> Seekable for BranchKeysIter<I>
+where
+    I: Seekable<Item = Vec<u8>>,
+{
+    fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(&[&self.prefix[..], key].concat())
+    }
+}
+
+impl<I> Seekable for BranchKVIter<I>
+where
+    I: Seekable<Item = (Vec<u8>, Vec<u8>)>,
+{
+    fn seek(&mut self, key: &[u8]) {
+        self.inner.seek(&[&self.prefix[..], key].concat())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,6 +520,109 @@ mod tests {
         assert_eq!(storage.get(b"fooqux"), Some(b"quux".to_vec()));
     }
 
+    #[test]
+    fn keys_around_stack_len() {
+        let mut storage = TestStorage::new();
+        let mut branch = StorageBranch::new(&mut storage, b"foo".to_vec());
+
+        for len in [STACK_KEY_LEN - 4, STACK_KEY_LEN - 3, STACK_KEY_LEN - 2, 200] {
+            let key = vec![b'a'; len];
+            branch.set(&key, &[len as u8]);
+            assert_eq!(branch.get(&key), Some(vec![len as u8]));
+
+            branch.set_meta(&key, b"meta");
+            assert!(branch.has_meta(&key));
+            branch.remove_meta(&key);
+            assert!(!branch.has_meta(&key));
+        }
+
+        for len in [STACK_KEY_LEN - 4, STACK_KEY_LEN - 3, STACK_KEY_LEN - 2, 200] {
+            let full_key = [b"foo".as_slice(), &vec![b'a'; len]].concat();
+            assert_eq!(storage.get(&full_key), Some(vec![len as u8]));
+        }
+    }
+
+    #[test]
+    fn has_is_forwarded() {
+        use core::cell::Cell;
+
+        use crate::storage::StorageBackend;
+
+        // A backend that counts how many times a value was fetched.
+        #[derive(Default)]
+        struct CountingStorage {
+            inner: TestStorage,
+            gets: Cell<u32>,
+        }
+
+        impl StorageBackend for CountingStorage {
+            fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+                self.gets.set(self.gets.get() + 1);
+                StorageBackend::get(&self.inner, key)
+            }
+
+            fn has(&self, key: &[u8]) -> bool {
+                StorageBackend::has(&self.inner, key)
+            }
+        }
+
+        let mut storage = CountingStorage::default();
+        storage.inner.set(b"foobar", b"baz");
+        storage.inner.set_meta(b"foom", b"meta");
+
+        let branch = StorageBranch::new(&storage, b"foo".to_vec());
+        assert!(branch.has(b"bar"));
+        assert!(!branch.has(b"qux"));
+        assert!(branch.has_meta(b"m"));
+        assert_eq!(storage.gets.get(), 0);
+
+        let nested = StorageBranch::new(&branch, b"b".to_vec());
+        assert!(nested.has(b"ar"));
+        assert_eq!(storage.gets.get(), 0);
+
+        assert_eq!(branch.get(b"bar"), Some(b"baz".to_vec()));
+        assert_eq!(storage.gets.get(), 1);
+    }
+
+    #[test]
+    fn get_many_is_forwarded() {
+        use core::cell::Cell;
+
+        use crate::storage::StorageBackend;
+
+        // A backend that counts how many batches it was asked for.
+        #[derive(Default)]
+        struct BatchingStorage {
+            inner: TestStorage,
+            batches: Cell<u32>,
+        }
+
+        impl StorageBackend for BatchingStorage {
+            fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+                StorageBackend::get(&self.inner, key)
+            }
+
+            fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+                self.batches.set(self.batches.get() + 1);
+                StorageBackend::get_many(&self.inner, keys)
+            }
+        }
+
+        let mut storage = BatchingStorage::default();
+        storage.inner.set(b"foobar", b"baz");
+        storage.inner.set(b"fooqux", b"quux");
+
+        let branch = StorageBranch::new(&storage, b"foo".to_vec());
+        let nested = StorageBranch::new(&branch, b"ba".to_vec());
+
+        assert_eq!(
+            branch.get_many(&[b"qux", b"nope", b"bar"]),
+            [Some(b"quux".to_vec()), None, Some(b"baz".to_vec())]
+        );
+        assert_eq!(nested.get_many(&[b"r"]), [Some(b"baz".to_vec())]);
+        assert_eq!(storage.batches.get(), 2);
+    }
+
     #[test]
     fn sub_bounds_no_prefix() {
         assert_eq!(
@@ -437,6 +726,26 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn seek() {
+        let mut storage = TestStorage::new();
+        storage.set(b"bar", b"outside");
+        let mut branch = StorageBranch::new(&mut storage, b"foo".to_vec());
+
+        branch.set(b"bar", b"baz");
+        branch.set(b"baz", b"qux");
+        branch.set(b"qux", b"quux");
+
+        let mut iter = branch.keys(Bound::Unbounded, Bound::Unbounded);
+        iter.seek(b"bb");
+        assert_eq!(iter.next(), Some(b"qux".to_vec()));
+        assert_eq!(iter.next(), None);
+
+        let mut iter = branch.pairs(Bound::Unbounded, Bound::Unbounded);
+        iter.seek(b"baz");
+        assert_eq!(iter.next(), Some((b"baz".to_vec(), b"qux".to_vec())));
+    }
+
     #[test]
     fn meta() {
         let mut storage = TestStorage::new();
@@ -454,4 +763,69 @@ mod tests {
         assert_eq!(storage.get(b"foobar"), None);
         assert_eq!(storage.get(b"fooqux"), None);
     }
+
+    #[test]
+    fn meta_keys() {
+        let mut storage = TestStorage::new();
+        storage.set_meta(b"bar", b"x");
+        storage.set(b"foobaz", b"x");
+
+        let mut branch = StorageBranch::new(&mut storage, b"foo".to_vec());
+        branch.set_meta(b"bar", b"baz");
+        branch.set_meta(b"qux", b"quux");
+
+        let keys: Vec<_> = branch
+            .meta_keys(Bound::Unbounded, Bound::Unbounded)
+            .collect();
+        assert_eq!(keys, vec![b"bar".to_vec(), b"qux".to_vec()]);
+
+        let keys: Vec<_> = branch
+            .meta_keys(Bound::Excluded(b"bar"), Bound::Unbounded)
+            .collect();
+        assert_eq!(keys, vec![b"qux".to_vec()]);
+
+        let keys: Vec<_> = storage
+            .meta_keys(Bound::Unbounded, Bound::Unbounded)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![b"bar".to_vec(), b"foobar".to_vec(), b"fooqux".to_vec()]
+        );
+    }
+
+    #[test]
+    fn sub_bounds_with_max_bytes() {
+        let (start, end) = sub_bounds(&[1, 0xFF], Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(start, Bound::Included(vec![1, 0xFF]));
+        assert_eq!(end, Bound::Excluded(vec![2]));
+
+        let (start, end) = sub_bounds(&[0xFF], Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(start, Bound::Included(vec![0xFF]));
+        assert_eq!(end, Bound::Unbounded);
+
+        let (start, end) = sub_bounds(&[0xFF, 0xFF], Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(start, Bound::Included(vec![0xFF, 0xFF]));
+        assert_eq!(end, Bound::Unbounded);
+    }
+
+    #[test]
+    fn iterate_prefix_ending_in_max_byte() {
+        let mut storage = TestStorage::new();
+        storage.set(&[1, 0xFE, 0], b"before");
+        storage.set(&[1, 0xFF, 0], b"foo");
+        storage.set(&[1, 0xFF, 0xFF], b"bar");
+        storage.set(&[2, 0], b"after");
+
+        let branch = StorageBranch::new(&storage, vec![1, 0xFF]);
+        let pairs: Vec<_> = branch.pairs(Bound::Unbounded, Bound::Unbounded).collect();
+        assert_eq!(
+            pairs,
+            vec![(vec![0], b"foo".to_vec()), (vec![0xFF], b"bar".to_vec())]
+        );
+
+        let keys: Vec<_> = branch
+            .rev_keys(Bound::Unbounded, Bound::Unbounded)
+            .collect();
+        assert_eq!(keys, vec![vec![0xFF], vec![0]]);
+    }
 }
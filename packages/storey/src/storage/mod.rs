@@ -9,13 +9,48 @@
 //! and [`IterableStorage`] represent binary storage types that provide iteration. These traits
 //! are something you might be interested in if you're implementing a new container.
 //!
+//! [`IterableMetaStorage`] provides iteration over the metadata namespace, which is needed to
+//! wipe a namespace completely.
+//!
+//! [`Seekable`] is an optional extension for iterators returned by [`IterableStorage`], letting
+//! an in-progress iteration skip ahead to a given key.
+//!
+//! [`ObservedStorage`] wraps a storage backend, reporting the key of every write to a callback.
+//! This can be used to invalidate an off-chain read cache.
+//!
+//! [`CachedStorage`] wraps a storage backend, staging writes in memory until they're committed
+//! or rolled back.
+//!
+//! [`MeteredStorage`] wraps a storage backend, counting the operations performed on it. This is
+//! useful for benchmarks and for catching accidental full scans.
+//!
+//! [`ReadOnly`] wraps a storage, exposing its read interface only. This lets the compiler reject
+//! writes in code shared between queries and transactions.
+//!
+//! [`StorageRef`] is implemented for references to a storage, which is what the `access`
+//! methods of containers expect.
+//!
+//! With the `async` feature, `AsyncStorage` and `AsyncStorageMut` are asynchronous
+//! counterparts of [`Storage`] and [`StorageMut`], for backends like networked KV stores.
+//! Storage branches implement them too, so an [`Item`](crate::containers::Item) - including one
+//! nested in a map - can be read and written asynchronously.
+//!
 //! [`StorageBranch`] is a storage namespace. It can be used to divide a backend's key namespace
 //! into smaller namespaces. This is a fundamental building block for the hierarchy of storage
 //! containers. You only need to be aware of it if you're implementing a new container.
 
 mod branch;
+mod read_only;
+mod storage_ref;
 
 pub use branch::StorageBranch;
+pub use read_only::ReadOnly;
+pub use storage_ref::StorageRef;
+pub(crate) use storey_storage::__private::successor;
+#[cfg(feature = "async")]
+pub use storey_storage::{AsyncStorage, AsyncStorageMut};
 pub use storey_storage::{
-    IterableStorage, RevIterableStorage, Storage, StorageBackend, StorageBackendMut, StorageMut,
+    CachedStorage, IterableMetaStorage, IterableStorage, MetaKeysIter, MeteredIter, MeteredStorage,
+    ObservedStorage, PrefixedKVIter, PrefixedKeysIter, PrefixedView, RevIterableStorage, ScanSeek,
+    SeekKey, Seekable, Storage, StorageBackend, StorageBackendMut, StorageMut, StorageStats,
 };
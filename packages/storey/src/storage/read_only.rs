@@ -0,0 +1,115 @@
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use crate::storage::{IterableMetaStorage, IterableStorage, RevIterableStorage, Storage};
+
+/// A read-only view of a storage.
+///
+/// This type implements [`Storage`] and the iteration traits by delegating to the wrapped
+/// storage, but deliberately not [`StorageMut`](crate::storage::StorageMut). Code that is
+/// generic over [`Storage`] can be handed a `ReadOnly` to make the compiler reject any write
+/// attempted through it, e.g. when sharing code between queries and transactions.
+///
+/// # Example
+/// ```
+/// # use mocks::backend::TestStorage;
+/// # use mocks::encoding::TestEncoding;
+/// use storey::containers::Item;
+/// use storey::storage::ReadOnly;
+///
+/// let mut storage = TestStorage::new();
+/// let item = Item::<u64, TestEncoding>::new(0);
+/// item.access(&mut storage).set(&42).unwrap();
+///
+/// let read_only = ReadOnly::new(&storage);
+/// assert_eq!(item.access(&read_only).get().unwrap(), Some(42));
+/// ```
+#[derive(Clone, Copy)]
+pub struct ReadOnly<S> {
+    storage: S,
+}
+
+impl<S> ReadOnly<S> {
+    /// Wrap the given storage, typically a shared reference to it.
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+}
+
+impl<S: Storage> Storage for ReadOnly<&S> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(key)
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        self.storage.has(key)
+    }
+
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        self.storage.get_many(keys)
+    }
+
+    fn get_meta(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get_meta(key)
+    }
+
+    fn has_meta(&self, key: &[u8]) -> bool {
+        self.storage.has_meta(key)
+    }
+}
+
+impl<S: IterableStorage> IterableStorage for ReadOnly<&S> {
+    type KeysIterator<'a> = S::KeysIterator<'a> where Self: 'a;
+    type ValuesIterator<'a> = S::ValuesIterator<'a> where Self: 'a;
+    type PairsIterator<'a> = S::PairsIterator<'a> where Self: 'a;
+
+    fn keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::KeysIterator<'a> {
+        self.storage.keys(start, end)
+    }
+
+    fn values<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::ValuesIterator<'a> {
+        self.storage.values(start, end)
+    }
+
+    fn pairs<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::PairsIterator<'a> {
+        self.storage.pairs(start, end)
+    }
+}
+
+impl<S: RevIterableStorage> RevIterableStorage for ReadOnly<&S> {
+    type RevKeysIterator<'a> = S::RevKeysIterator<'a> where Self: 'a;
+    type RevValuesIterator<'a> = S::RevValuesIterator<'a> where Self: 'a;
+    type RevPairsIterator<'a> = S::RevPairsIterator<'a> where Self: 'a;
+
+    fn rev_keys<'a>(&'a self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Self::RevKeysIterator<'a> {
+        self.storage.rev_keys(start, end)
+    }
+
+    fn rev_values<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevValuesIterator<'a> {
+        self.storage.rev_values(start, end)
+    }
+
+    fn rev_pairs<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::RevPairsIterator<'a> {
+        self.storage.rev_pairs(start, end)
+    }
+}
+
+impl<S: IterableMetaStorage> IterableMetaStorage for ReadOnly<&S> {
+    type MetaKeysIterator<'a> = S::MetaKeysIterator<'a> where Self: 'a;
+
+    fn meta_keys<'a>(
+        &'a self,
+        start: Bound<&[u8]>,
+        end: Bound<&[u8]>,
+    ) -> Self::MetaKeysIterator<'a> {
+        self.storage.meta_keys(start, end)
+    }
+}
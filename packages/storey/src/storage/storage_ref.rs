@@ -0,0 +1,23 @@
+/// A reference to a storage, as accepted by the `access` methods of containers.
+///
+/// Accessors borrow the storage they operate on, so this is implemented for `&S` and
+/// `&mut S` only. Passing an owned storage to `access` fails at the call site with a
+/// hint to borrow it, rather than with an unsatisfied bound on every accessor method.
+///
+/// This trait is [sealed](https://rust-lang.github.io/api-guidelines/future-proofing.html#sealed-traits-protect-against-downstream-implementations-c-sealed)
+/// and cannot be implemented outside of this crate.
+#[diagnostic::on_unimplemented(
+    message = "storage must be passed by reference, but `{Self}` is not a reference",
+    note = "did you mean `&storage` or `&mut storage`?"
+)]
+pub trait StorageRef: sealed::StorageRefSeal {}
+
+impl<S: ?Sized> StorageRef for &S {}
+impl<S: ?Sized> StorageRef for &mut S {}
+
+mod sealed {
+    pub trait StorageRefSeal {}
+
+    impl<S: ?Sized> StorageRefSeal for &S {}
+    impl<S: ?Sized> StorageRefSeal for &mut S {}
+}
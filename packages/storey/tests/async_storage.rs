@@ -0,0 +1,81 @@
+#![cfg(feature = "async")]
+
+use storey::containers::{Item, Map};
+
+use mocks::async_backend::{block_on, AsyncTestStorage};
+use mocks::encoding::TestEncoding;
+
+#[test]
+fn item() {
+    let mut storage = AsyncTestStorage::new();
+    let item = Item::<u64, TestEncoding>::new(0);
+
+    block_on(async {
+        assert_eq!(item.access(&storage).get_async().await, Ok(None));
+
+        item.access(&mut storage).set_async(&42).await.unwrap();
+        assert_eq!(item.access(&storage).get_async().await, Ok(Some(42)));
+
+        item.access(&mut storage).remove_async().await;
+        assert_eq!(item.access(&storage).get_async().await, Ok(None));
+    });
+}
+
+#[test]
+fn map_entry() {
+    let mut storage = AsyncTestStorage::new();
+    let map = Map::<String, Map<u32, Item<u64, TestEncoding>>>::new(0);
+
+    block_on(async {
+        let mut access = map.access(&mut storage);
+        access
+            .entry_mut("foo")
+            .entry_mut(&1)
+            .set_async(&42)
+            .await
+            .unwrap();
+        access
+            .entry_mut("foo")
+            .entry_mut(&2)
+            .set_async(&43)
+            .await
+            .unwrap();
+        access
+            .entry_mut("bar")
+            .entry_mut(&1)
+            .set_async(&44)
+            .await
+            .unwrap();
+
+        let access = map.access(&storage);
+        assert_eq!(
+            access.entry("foo").entry(&1).get_async().await,
+            Ok(Some(42))
+        );
+        assert_eq!(
+            access.entry("foo").entry(&2).get_async().await,
+            Ok(Some(43))
+        );
+        assert_eq!(
+            access.entry("bar").entry(&1).get_async().await,
+            Ok(Some(44))
+        );
+        assert_eq!(access.entry("bar").entry(&2).get_async().await, Ok(None));
+    });
+}
+
+#[test]
+fn same_layout_as_sync() {
+    let mut storage = AsyncTestStorage::new();
+    let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+
+    block_on(async {
+        map.access(&mut storage)
+            .entry_mut("foo")
+            .set_async(&42)
+            .await
+            .unwrap();
+    });
+
+    assert_eq!(map.access(&storage.0).entry("foo").get(), Ok(Some(42)));
+}
@@ -0,0 +1,160 @@
+use std::ops::Bound;
+
+use storey::containers::{
+    BoundedIterableAccessor as _, BoundedRevIterableAccessor as _, Item, IterableAccessor as _,
+    Map, RevIterableAccessor as _,
+};
+use storey::storage::{CachedStorage, Storage as _};
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+const MAP: Map<String, Item<u64, TestEncoding>> = Map::new(0);
+
+fn populated() -> TestStorage {
+    let mut storage = TestStorage::new();
+
+    let mut access = MAP.access(&mut storage);
+    for (key, value) in [("a", 1), ("b", 2), ("c", 3), ("d", 4)] {
+        access.entry_mut(key).set(&value).unwrap();
+    }
+
+    storage
+}
+
+/// Stage a mix of writes that shadow, remove and add entries around the populated ones.
+fn stage(storage: &mut CachedStorage<TestStorage>) {
+    let mut access = MAP.access(storage);
+    access.entry_mut("a").remove();
+    access.entry_mut("b").set(&20).unwrap();
+    access.entry_mut("bb").set(&22).unwrap();
+    access.entry_mut("d").remove();
+    access.entry_mut("e").set(&5).unwrap();
+    access.entry_mut("f").remove();
+}
+
+fn pairs(list: &[(&str, u64)]) -> Vec<((String, ()), u64)> {
+    list.iter()
+        .map(|(key, value)| ((key.to_string(), ()), *value))
+        .collect()
+}
+
+#[test]
+fn reads_see_staged_writes() {
+    let mut storage = CachedStorage::new(populated());
+    stage(&mut storage);
+
+    let access = MAP.access(&storage);
+    assert_eq!(access.entry("a").get().unwrap(), None);
+    assert_eq!(access.entry("b").get().unwrap(), Some(20));
+    assert_eq!(access.entry("c").get().unwrap(), Some(3));
+    assert_eq!(access.entry("e").get().unwrap(), Some(5));
+    assert!(!access.entry("d").has());
+    assert!(access.entry("bb").has());
+
+    // the wrapped backend is untouched
+    let access = MAP.access(storage.inner());
+    assert_eq!(access.entry("a").get().unwrap(), Some(1));
+    assert_eq!(access.entry("e").get().unwrap(), None);
+}
+
+#[test]
+fn iteration_merges_staged_writes() {
+    let mut storage = CachedStorage::new(populated());
+    stage(&mut storage);
+
+    let access = MAP.access(&storage);
+    let expected = pairs(&[("b", 20), ("bb", 22), ("c", 3), ("e", 5)]);
+
+    let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(items, expected);
+
+    let keys = access.keys().collect::<Result<Vec<_>, _>>().unwrap();
+    let keys: Vec<_> = keys.into_iter().map(|(key, ())| key).collect();
+    assert_eq!(keys, ["b", "bb", "c", "e"]);
+
+    let values = access.values().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(values, [20, 22, 3, 5]);
+
+    let items = access.rev_pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(items, expected.into_iter().rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn bounded_iteration_merges_staged_writes() {
+    let mut storage = CachedStorage::new(populated());
+    stage(&mut storage);
+
+    let access = MAP.access(&storage);
+
+    let items = access
+        .bounded_pairs(Bound::Included("a"), Bound::Excluded("c"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, pairs(&[("b", 20), ("bb", 22)]));
+
+    let items = access
+        .bounded_rev_pairs(Bound::Excluded("b"), Bound::Included("e"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, pairs(&[("e", 5), ("c", 3), ("bb", 22)]));
+
+    // empty and inverted ranges yield nothing
+    let items = access
+        .bounded_pairs(Bound::Excluded("b"), Bound::Excluded("b"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, []);
+    let items = access
+        .bounded_rev_pairs(Bound::Included("e"), Bound::Included("b"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, []);
+}
+
+#[test]
+fn commit_and_rollback() {
+    let mut storage = CachedStorage::new(populated());
+    stage(&mut storage);
+
+    let storage = storage.rollback();
+    let items = MAP
+        .access(&storage)
+        .pairs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, pairs(&[("a", 1), ("b", 2), ("c", 3), ("d", 4)]));
+
+    let mut storage = CachedStorage::new(storage);
+    stage(&mut storage);
+
+    let storage = storage.commit();
+    let items = MAP
+        .access(&storage)
+        .pairs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items, pairs(&[("b", 20), ("bb", 22), ("c", 3), ("e", 5)]));
+}
+
+#[test]
+fn get_many_merges_staged_writes() {
+    let mut storage = CachedStorage::new(populated());
+    stage(&mut storage);
+
+    let keys: Vec<Vec<u8>> = ["a", "b", "bb", "c", "d", "e", "f"]
+        .iter()
+        .map(|key| [&[0], key.as_bytes()].concat())
+        .collect();
+    let keys: Vec<&[u8]> = keys.iter().map(Vec::as_slice).collect();
+
+    let values = storage.get_many(&keys);
+    assert_eq!(
+        values.iter().map(Option::is_some).collect::<Vec<_>>(),
+        [false, true, true, true, false, true, false]
+    );
+    assert_eq!(
+        values,
+        keys.iter().map(|key| storage.get(key)).collect::<Vec<_>>()
+    );
+}
@@ -67,3 +67,45 @@ fn map_of_column() {
         ]
     );
 }
+
+#[test]
+fn nested_column_metadata_isolation() {
+    let mut storage = TestStorage::new();
+
+    let map = Map::<String, Map<String, Column<u64, TestEncoding>>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    // "a" is a prefix of "ab" - the length prefix keeps their namespaces apart
+    for _ in 0..3 {
+        access.entry_mut("a").entry_mut("x").push(&1).unwrap();
+    }
+    access.entry_mut("ab").entry_mut("x").push(&2).unwrap();
+    for _ in 0..2 {
+        access.entry_mut("a").entry_mut("y").push(&3).unwrap();
+    }
+
+    assert_eq!(access.entry("a").entry("x").len().unwrap(), 3);
+    assert_eq!(access.entry("ab").entry("x").len().unwrap(), 1);
+    assert_eq!(access.entry("a").entry("y").len().unwrap(), 2);
+    assert_eq!(access.entry("b").entry("x").len().unwrap(), 0);
+
+    // each column's `META_LEN` lives under its own namespace
+    assert_eq!(
+        storage.get_meta(&[0, 1, b'a', 1, b'x', 1]),
+        Some(3u32.to_be_bytes().to_vec())
+    );
+    assert_eq!(
+        storage.get_meta(&[0, 2, b'a', b'b', 1, b'x', 1]),
+        Some(1u32.to_be_bytes().to_vec())
+    );
+
+    // removing from one column leaves its siblings alone
+    let mut access = map.access(&mut storage);
+    access.entry_mut("a").entry_mut("x").remove(1).unwrap();
+    assert_eq!(access.entry("a").entry("x").len().unwrap(), 2);
+    assert_eq!(access.entry("ab").entry("x").len().unwrap(), 1);
+    assert_eq!(access.entry("a").entry("y").len().unwrap(), 2);
+
+    // metadata doesn't leak into iteration
+    assert_eq!(access.keys().count(), 5);
+}
@@ -0,0 +1,51 @@
+use std::collections::hash_map::DefaultHasher;
+
+use storey::containers::{Item, Map};
+use storey::storage::{IterableStorage as _, StorageBranch, StorageMut as _};
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+const MAP: Map<String, Item<u64, TestEncoding>> = Map::new(1);
+
+fn digest(storage: &TestStorage) -> u64 {
+    StorageBranch::new(storage, vec![1]).digest(DefaultHasher::new())
+}
+
+#[test]
+fn digest_ignores_insertion_order() {
+    let mut a = TestStorage::new();
+    let mut access = MAP.access(&mut a);
+    access.entry_mut("foo").set(&1).unwrap();
+    access.entry_mut("bar").set(&2).unwrap();
+    access.entry_mut("baz").set(&3).unwrap();
+
+    let mut b = TestStorage::new();
+    // unrelated data outside the namespace
+    Item::<u64, TestEncoding>::new(0)
+        .access(&mut b)
+        .set(&42)
+        .unwrap();
+    let mut access = MAP.access(&mut b);
+    access.entry_mut("baz").set(&3).unwrap();
+    access.entry_mut("qux").set(&4).unwrap();
+    access.entry_mut("foo").set(&1).unwrap();
+    access.entry_mut("bar").set(&2).unwrap();
+    access.entry_mut("qux").remove();
+
+    assert_eq!(digest(&a), digest(&b));
+
+    MAP.access(&mut b).entry_mut("bar").set(&5).unwrap();
+    assert_ne!(digest(&a), digest(&b));
+}
+
+#[test]
+fn digest_separates_keys_from_values() {
+    // the same bytes, split differently between key and value
+    let mut a = TestStorage::new();
+    StorageBranch::new(&mut a, vec![1]).set(b"ab", b"c");
+    let mut b = TestStorage::new();
+    StorageBranch::new(&mut b, vec![1]).set(b"a", b"bc");
+
+    assert_ne!(digest(&a), digest(&b));
+}
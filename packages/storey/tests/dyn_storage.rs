@@ -0,0 +1,41 @@
+use storey::containers::{Column, Item, Map};
+use storey::storage::{Storage, StorageMut};
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+const ITEM: Item<u64, TestEncoding> = Item::new(0);
+const MAP: Map<String, Item<u64, TestEncoding>> = Map::new(1);
+const COLUMN: Column<u64, TestEncoding> = Column::new(2);
+
+fn write(storage: &mut dyn StorageMut) -> u32 {
+    ITEM.access(&mut *storage).set(&42).unwrap();
+    ITEM.access(&mut *storage)
+        .update(|v| v.map(|v| v + 1))
+        .unwrap();
+
+    let mut map = MAP.access(&mut *storage);
+    map.entry_mut("foo").set(&1337).unwrap();
+
+    COLUMN.access(storage).push(&7).unwrap()
+}
+
+fn read(storage: &dyn Storage, id: u32) -> (Option<u64>, Option<u64>, Option<u64>) {
+    (
+        ITEM.access(storage).get().unwrap(),
+        MAP.access(storage).entry("foo").get().unwrap(),
+        COLUMN.access(storage).get(id).unwrap(),
+    )
+}
+
+#[test]
+fn trait_objects() {
+    let mut storage = TestStorage::new();
+    let id = write(&mut storage);
+
+    assert_eq!(read(&storage, id), (Some(43), Some(1337), Some(7)));
+
+    // a writable trait object can be read through too
+    let storage: &mut dyn StorageMut = &mut storage;
+    assert_eq!(ITEM.access(storage).get().unwrap(), Some(43));
+}
@@ -1,6 +1,9 @@
 use std::ops::Bound;
 
 use storey::containers::{BoundedIterableAccessor, Item, IterableAccessor as _, Map};
+use storey::storage::{
+    IterableStorage as _, RevIterableStorage as _, Seekable as _, StorageMut as _,
+};
 
 use mocks::backend::TestStorage;
 use mocks::encoding::TestEncoding;
@@ -120,3 +123,208 @@ fn map_of_map_bounded_iteration() {
         ]
     );
 }
+
+#[test]
+fn tuple_key_iteration() {
+    let mut storage = TestStorage::new();
+
+    let map = Map::<(u32, String), Item<u64, TestEncoding>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    access.entry_mut(&(2, "foo".to_string())).set(&1).unwrap();
+    access.entry_mut(&(1, "qux".to_string())).set(&2).unwrap();
+    access.entry_mut(&(2, "bar".to_string())).set(&3).unwrap();
+    access.entry_mut(&(1, "quux".to_string())).set(&4).unwrap();
+
+    // ordered by the fixed-size `u32` first, then lexicographically by the string
+    let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        items,
+        vec![
+            (((1, "quux".to_string()), ()), 4),
+            (((1, "qux".to_string()), ()), 2),
+            (((2, "bar".to_string()), ()), 3),
+            (((2, "foo".to_string()), ()), 1),
+        ]
+    );
+
+    // a dynamic first member is length-prefixed, so shorter strings come first
+    let map = Map::<(String, u32), Item<u64, TestEncoding>>::new(1);
+    let mut access = map.access(&mut storage);
+
+    access.entry_mut(&("foo".to_string(), 2)).set(&1).unwrap();
+    access.entry_mut(&("quux".to_string(), 1)).set(&2).unwrap();
+    access.entry_mut(&("foo".to_string(), 1)).set(&3).unwrap();
+
+    let keys = access.keys().map(|res| res.unwrap().0).collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec![
+            ("foo".to_string(), 1),
+            ("foo".to_string(), 2),
+            ("quux".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn prefixed_view_iteration() {
+    let mut storage = TestStorage::new();
+    storage.set(b"a", b"before");
+    storage.set(b"fo", b"short");
+    storage.set(b"foo", b"empty");
+    storage.set(b"foo1", b"1");
+    storage.set(b"foo2", b"2");
+    storage.set(b"foo23", b"23");
+    storage.set(b"fop", b"after");
+
+    let view = storage.prefix(b"foo");
+
+    let pairs = view
+        .pairs(Bound::Unbounded, Bound::Unbounded)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        vec![
+            (b"".to_vec(), b"empty".to_vec()),
+            (b"1".to_vec(), b"1".to_vec()),
+            (b"2".to_vec(), b"2".to_vec()),
+            (b"23".to_vec(), b"23".to_vec()),
+        ]
+    );
+
+    // bounds are relative to the prefix
+    let keys = view
+        .keys(Bound::Excluded(b"1"), Bound::Excluded(b"23"))
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![b"2".to_vec()]);
+
+    let values = view
+        .rev_values(Bound::Included(b"1"), Bound::Unbounded)
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![b"23".to_vec(), b"2".to_vec(), b"1".to_vec()]);
+
+    // views compose
+    let nested = view.prefix(b"2");
+    let keys = nested
+        .keys(Bound::Unbounded, Bound::Unbounded)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![b"".to_vec(), b"3".to_vec()]);
+
+    // seeking is relative to the prefix too
+    let mut iter = view.keys(Bound::Unbounded, Bound::Unbounded);
+    iter.seek(b"2");
+    assert_eq!(iter.next(), Some(b"2".to_vec()));
+}
+
+#[test]
+fn prefixed_view_max_bytes() {
+    let mut storage = TestStorage::new();
+    storage.set(&[1, 0xFE, 0], b"before");
+    storage.set(&[1, 0xFF, 0], b"foo");
+    storage.set(&[1, 0xFF, 0xFF], b"bar");
+    storage.set(&[2, 0], b"after");
+
+    let view = storage.prefix(&[1, 0xFF]);
+    let keys = view
+        .keys(Bound::Unbounded, Bound::Unbounded)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![vec![0], vec![0xFF]]);
+}
+
+#[test]
+fn raw_iteration() {
+    let mut storage = TestStorage::new();
+
+    let map = Map::<String, Map<String, Item<u64, TestEncoding>>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    access.entry_mut("foo").entry_mut("bar").set(&1337).unwrap();
+    access.entry_mut("foo").entry_mut("baz").set(&42).unwrap();
+    access
+        .entry_mut("qux")
+        .entry_mut("quux")
+        .set(&9001)
+        .unwrap();
+
+    // raw values are exactly what's in the backend
+    let values = access.raw_values().collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        storage
+            .values(Bound::Unbounded, Bound::Unbounded)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(values.len(), 3);
+
+    let access = map.access(&storage);
+    let pairs = access.raw_pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    let keys = pairs.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+    assert_eq!(keys, access.keys().collect::<Result<Vec<_>, _>>().unwrap());
+    assert_eq!(
+        pairs.into_iter().map(|(_, v)| v).collect::<Vec<_>>(),
+        values
+    );
+
+    // still double-ended
+    let (key, value) = access.raw_pairs().next_back().unwrap().unwrap();
+    assert_eq!(key, ("qux".to_string(), ("quux".to_string(), ())));
+    assert_eq!(Some(&value), values.last());
+
+    // works on inner containers too
+    let values = access.entry("foo").raw_values().collect::<Vec<_>>();
+    assert_eq!(values.len(), 2);
+}
+
+#[test]
+fn tuple_key_iteration_long_members() {
+    let mut storage = TestStorage::new();
+
+    let long = "a".repeat(300);
+
+    // a first member longer than 255 bytes has to decode back intact
+    let map = Map::<(String, String), Item<u64, TestEncoding>>::new(0);
+    let mut access = map.access(&mut storage);
+
+    access
+        .entry_mut(&(long.clone(), "b".to_string()))
+        .set(&1)
+        .unwrap();
+    access
+        .entry_mut(&("a".repeat(44), "a".repeat(256) + "b"))
+        .set(&2)
+        .unwrap();
+
+    let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        items,
+        vec![
+            ((("a".repeat(44), "a".repeat(256) + "b"), ()), 2),
+            (((long.clone(), "b".to_string()), ()), 1),
+        ]
+    );
+
+    // a long member in the key of an outer map, where the whole tuple is length-prefixed too
+    let map = Map::<(String, u32), Map<u32, Item<u64, TestEncoding>>>::new(1);
+    let mut access = map.access(&mut storage);
+
+    access
+        .entry_mut(&(long.clone(), 7))
+        .entry_mut(&1)
+        .set(&3)
+        .unwrap();
+    access
+        .entry_mut(&("b".to_string(), 8))
+        .entry_mut(&2)
+        .set(&4)
+        .unwrap();
+
+    let items = access.pairs().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(
+        items,
+        vec![
+            ((("b".to_string(), 8), (2, ())), 4),
+            (((long, 7), (1, ())), 3),
+        ]
+    );
+}
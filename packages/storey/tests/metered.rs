@@ -0,0 +1,81 @@
+use storey::containers::{Item, IterableAccessor as _, Map};
+use storey::storage::{MeteredStorage, Storage as _, StorageMut as _, StorageStats};
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+#[test]
+fn item_set_is_a_single_write() {
+    let mut storage = MeteredStorage::new(TestStorage::new());
+
+    let item = Item::<u64, TestEncoding>::new(0);
+    item.access(&mut storage).set(&42).unwrap();
+
+    // one byte of key, eight bytes of encoded `u64`
+    assert_eq!(
+        storage.stats(),
+        StorageStats {
+            sets: 1,
+            bytes_written: 9,
+            ..Default::default()
+        }
+    );
+
+    storage.reset_stats();
+    assert_eq!(item.access(&storage).get().unwrap(), Some(42));
+    assert_eq!(
+        storage.stats(),
+        StorageStats {
+            gets: 1,
+            bytes_read: 8,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn iteration_counts_entries() {
+    let mut storage = MeteredStorage::new(TestStorage::new());
+
+    let map = Map::<String, Item<u64, TestEncoding>>::new(0);
+    let mut access = map.access(&mut storage);
+    access.entry_mut("foo").set(&1).unwrap();
+    access.entry_mut("bar").set(&2).unwrap();
+    access.entry_mut("bar").remove();
+    access.entry_mut("baz").set(&3).unwrap();
+
+    let stats = storage.stats();
+    assert_eq!(stats.sets, 3);
+    assert_eq!(stats.removes, 1);
+
+    storage.reset_stats();
+    let values = map
+        .access(&storage)
+        .values()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values, [3, 1]);
+
+    let stats = storage.stats();
+    assert_eq!(stats.gets, 0);
+    assert_eq!(stats.iterator_advances, 2);
+    assert_eq!(stats.bytes_read, 16);
+}
+
+#[test]
+fn get_many_counts_each_key() {
+    let mut storage = MeteredStorage::new(TestStorage::new());
+    storage.set(b"foo", b"bar");
+    storage.reset_stats();
+
+    let values = storage.get_many(&[b"foo", b"baz", b"foo"]);
+    assert_eq!(values, [Some(b"bar".to_vec()), None, Some(b"bar".to_vec())]);
+    assert_eq!(
+        storage.stats(),
+        StorageStats {
+            gets: 3,
+            bytes_read: 6,
+            ..Default::default()
+        }
+    );
+}
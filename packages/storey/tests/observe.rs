@@ -0,0 +1,31 @@
+use storey::containers::{Item, Map};
+use storey::storage::ObservedStorage;
+
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+
+#[test]
+fn invalidation_keys() {
+    let mut invalidated = Vec::new();
+    let mut storage = ObservedStorage::new(TestStorage::new(), |key: &[u8]| {
+        invalidated.push(key.to_vec())
+    });
+
+    let item = Item::<u64, TestEncoding>::new(0);
+    item.access(&mut storage).set(&42).unwrap();
+    // reads don't fire the observer
+    assert_eq!(item.access(&storage).get().unwrap(), Some(42));
+
+    let map = Map::<String, Item<u64, TestEncoding>>::new(1);
+    let mut access = map.access(&mut storage);
+    access.entry_mut("foo").set(&1337).unwrap();
+    access.entry_mut("foo").remove();
+
+    let storage = storage.into_inner();
+    assert_eq!(item.access(&storage).get().unwrap(), Some(42));
+
+    assert_eq!(
+        invalidated,
+        vec![vec![0], b"\x01foo".to_vec(), b"\x01foo".to_vec()]
+    );
+}
@@ -0,0 +1,10 @@
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+use storey::containers::Item;
+
+fn main() {
+    let storage = TestStorage::new();
+    let item = Item::<u64, TestEncoding>::new(0);
+
+    item.access_mut(&storage).set(&42).unwrap();
+}
@@ -0,0 +1,10 @@
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+use storey::containers::Item;
+
+fn main() {
+    let storage = TestStorage::new();
+    let item = Item::<u64, TestEncoding>::new(0);
+
+    item.access(storage).get().unwrap();
+}
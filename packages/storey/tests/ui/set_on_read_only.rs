@@ -0,0 +1,12 @@
+use mocks::backend::TestStorage;
+use mocks::encoding::TestEncoding;
+use storey::containers::Item;
+use storey::storage::ReadOnly;
+
+fn main() {
+    let storage = TestStorage::new();
+    let item = Item::<u64, TestEncoding>::new(0);
+
+    let mut read_only = ReadOnly::new(&storage);
+    item.access(&mut read_only).set(&42).unwrap();
+}